@@ -0,0 +1,659 @@
+//! Opt-in local automation API.
+//!
+//! A small HTTP/1.1 server on `127.0.0.1` lets scripts and other tools drive
+//! the running instance through the same operation queue as the UI, instead of
+//! spawning a second `scoop` that races it. Every request needs an
+//! `Authorization: Bearer <token>` header, and requests with an `Origin` header
+//! are refused so web pages cannot reach the server.
+//!
+//! - `POST /operations` enqueues an [`EnqueueAction`] and returns its id.
+//! - `GET /operations` returns the current [`OperationStateSnapshot`].
+//! - `GET /operations/events` streams snapshots as server-sent events.
+//! - `GET /updates` and `GET /status` answer like `check_for_updates` and
+//!   `check_scoop_status`.
+//!
+//! While the server runs, its URL and token are written to `automation.json`
+//! in the app data directory for tools to pick up.
+use crate::commands::settings;
+use crate::operations::{self, EnqueueAction, OperationStateSnapshot};
+use crate::state::AppState;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::io;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinSet;
+
+pub const ENABLED_KEY: &str = "automation.enabled";
+pub const PORT_KEY: &str = "automation.port";
+pub const TOKEN_KEY: &str = "automation.token";
+pub const DEFAULT_PORT: u16 = 47319;
+
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+/// How long a client may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Comment lines keep idle event streams open through proxies and let us
+/// notice clients that went away.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+pub type BackendFuture<'a> = Pin<Box<dyn Future<Output = Result<Value, String>> + Send + 'a>>;
+
+/// What the server drives. The app forwards to `operations` and its
+/// `ScoopRoot`; tests use a fake.
+pub trait Backend: Send + Sync + 'static {
+    fn enqueue(&self, action: EnqueueAction) -> String;
+    fn operation_state(&self) -> OperationStateSnapshot;
+    fn subscribe(&self) -> broadcast::Receiver<OperationStateSnapshot>;
+    fn updates(&self) -> BackendFuture<'_>;
+    fn status(&self) -> BackendFuture<'_>;
+}
+
+struct AppBackend {
+    app: AppHandle,
+}
+
+impl Backend for AppBackend {
+    fn enqueue(&self, action: EnqueueAction) -> String {
+        operations::enqueue(&self.app, action)
+    }
+
+    fn operation_state(&self) -> OperationStateSnapshot {
+        operations::snapshot(&self.app)
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<OperationStateSnapshot> {
+        operations::subscribe()
+    }
+
+    fn updates(&self) -> BackendFuture<'_> {
+        Box::pin(async move {
+            let updates = self.app.state::<AppState>().root().updates().await?;
+            serde_json::to_value(updates).map_err(|e| e.to_string())
+        })
+    }
+
+    fn status(&self) -> BackendFuture<'_> {
+        Box::pin(async move {
+            let status = self.app.state::<AppState>().root().status().await?;
+            serde_json::to_value(status).map_err(|e| e.to_string())
+        })
+    }
+}
+
+// --- HTTP --------------------------------------------------------------------
+
+struct Request {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+impl Response {
+    fn json<T: Serialize>(status: u16, body: &T) -> Self {
+        match serde_json::to_value(body) {
+            Ok(body) => Self { status, body },
+            Err(e) => Self::error(500, e.to_string()),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+
+    async fn write_to(&self, stream: &mut TcpStream) -> io::Result<()> {
+        let body = self.body.to_string();
+        let head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+            self.status,
+            reason(self.status),
+            body.len()
+        );
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(body.as_bytes()).await?;
+        stream.shutdown().await
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+fn find_head_end(buf: &[u8]) -> Option<usize> {
+    buf.windows(4).position(|window| window == b"\r\n\r\n")
+}
+
+/// Reads one request. `Err` carries the response for a malformed one.
+async fn read_request(stream: &mut TcpStream) -> io::Result<Result<Request, Response>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(end) = find_head_end(&buf) {
+            break end;
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Ok(Err(Response::error(431, "Request head too large")));
+        }
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    };
+
+    let Ok(head) = std::str::from_utf8(&buf[..head_end]) else {
+        return Ok(Err(Response::error(400, "Request head is not UTF-8")));
+    };
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Ok(Err(Response::error(400, "Malformed request line")));
+    };
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let mut request = Request {
+        method: method.to_string(),
+        path: target.split('?').next().unwrap_or_default().to_string(),
+        headers,
+        body: Vec::new(),
+    };
+
+    let length = match request.header("content-length").map(str::parse::<usize>) {
+        None => 0,
+        Some(Ok(length)) if length <= MAX_BODY_BYTES => length,
+        Some(Ok(_)) => return Ok(Err(Response::error(413, "Request body too large"))),
+        Some(Err(_)) => return Ok(Err(Response::error(400, "Invalid Content-Length"))),
+    };
+    let mut body = buf.split_off(head_end + 4);
+    while body.len() < length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(length);
+    request.body = body;
+    Ok(Ok(request))
+}
+
+/// Compares in constant time so the token cannot be guessed byte by byte.
+fn token_matches(given: &str, token: &str) -> bool {
+    !token.is_empty()
+        && given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+enum Reply {
+    Json(Response),
+    Events,
+}
+
+async fn route(request: &Request, token: &str, backend: &dyn Backend) -> Reply {
+    if request.header("origin").is_some() {
+        return Reply::Json(Response::error(403, "Browser requests are not accepted"));
+    }
+    let authorized = request
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| token_matches(given.trim(), token));
+    if !authorized {
+        return Reply::Json(Response::error(401, "Missing or invalid token"));
+    }
+
+    let from_backend = |result: Result<Value, String>| match result {
+        Ok(value) => Response::json(200, &value),
+        Err(e) => Response::error(500, e),
+    };
+    let response = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/operations") => Response::json(200, &backend.operation_state()),
+        ("POST", "/operations") => match serde_json::from_slice::<EnqueueAction>(&request.body) {
+            Ok(action) => {
+                log::info!("Automation API enqueued {:?}", action);
+                Response::json(202, &json!({ "id": backend.enqueue(action) }))
+            }
+            Err(e) => Response::error(400, format!("Invalid action: {}", e)),
+        },
+        ("GET", "/operations/events") => return Reply::Events,
+        ("GET", "/updates") => from_backend(backend.updates().await),
+        ("GET", "/status") => from_backend(backend.status().await),
+        (_, "/operations" | "/operations/events" | "/updates" | "/status") => {
+            Response::error(405, "Method not allowed")
+        }
+        _ => Response::error(404, "Not found"),
+    };
+    Reply::Json(response)
+}
+
+fn event_frame(snapshot: &OperationStateSnapshot) -> String {
+    let data = serde_json::to_string(snapshot).unwrap_or_else(|_| "null".to_string());
+    format!("event: operation-state\ndata: {}\n\n", data)
+}
+
+/// Streams the current snapshot, then every change, until the client leaves.
+async fn stream_events(mut stream: TcpStream, backend: &dyn Backend) -> io::Result<()> {
+    // Subscribe before reading the snapshot so no change falls in between.
+    let mut changes = backend.subscribe();
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+    stream
+        .write_all(event_frame(&backend.operation_state()).as_bytes())
+        .await?;
+
+    loop {
+        let frame = match tokio::time::timeout(KEEP_ALIVE, changes.recv()).await {
+            Ok(Ok(snapshot)) => event_frame(&snapshot),
+            // Skipped some changes; the latest state supersedes them.
+            Ok(Err(broadcast::error::RecvError::Lagged(_))) => {
+                event_frame(&backend.operation_state())
+            }
+            Ok(Err(broadcast::error::RecvError::Closed)) => return Ok(()),
+            Err(_) => ": keep-alive\n\n".to_string(),
+        };
+        stream.write_all(frame.as_bytes()).await?;
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    token: &str,
+    backend: &dyn Backend,
+) -> io::Result<()> {
+    let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(Ok(request))) => request,
+        Ok(Ok(Err(response))) => return response.write_to(&mut stream).await,
+        Ok(Err(e)) => return Err(e),
+        Err(_) => return Ok(()),
+    };
+    match route(&request, token, backend).await {
+        Reply::Json(response) => response.write_to(&mut stream).await,
+        Reply::Events => stream_events(stream, backend).await,
+    }
+}
+
+/// Accepts connections until the returned future is dropped or its task
+/// aborted, which also closes every open connection.
+pub async fn serve(listener: TcpListener, token: String, backend: Arc<dyn Backend>) {
+    let token: Arc<str> = token.into();
+    let mut connections = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let (token, backend) = (token.clone(), backend.clone());
+                    connections.spawn(async move {
+                        if let Err(e) = handle_connection(stream, &token, backend.as_ref()).await {
+                            log::debug!("Automation API connection ended: {}", e);
+                        }
+                    });
+                }
+                Err(e) => {
+                    log::warn!("Automation API failed to accept a connection: {}", e);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            },
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+        }
+    }
+}
+
+// --- Lifecycle ---------------------------------------------------------------
+
+struct Running {
+    port: u16,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+static RUNNING: Lazy<Mutex<Option<Running>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationApiInfo {
+    pub enabled: bool,
+    pub port: u16,
+    /// Port actually listened on; `None` while stopped.
+    pub listening_on: Option<u16>,
+    pub token: Option<String>,
+    pub discovery_file: Option<String>,
+}
+
+fn read_config(app: &AppHandle, key: &str) -> Option<Value> {
+    settings::get_config_value(app.clone(), key.to_string())
+        .ok()
+        .flatten()
+}
+
+pub fn is_enabled(app: &AppHandle) -> bool {
+    read_config(app, ENABLED_KEY)
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false)
+}
+
+pub fn configured_port(app: &AppHandle) -> u16 {
+    read_config(app, PORT_KEY)
+        .and_then(|value| value.as_u64())
+        .and_then(|port| u16::try_from(port).ok())
+        .filter(|port| *port != 0)
+        .unwrap_or(DEFAULT_PORT)
+}
+
+fn stored_token(app: &AppHandle) -> Option<String> {
+    read_config(app, TOKEN_KEY)
+        .and_then(|value| value.as_str().map(str::to_string))
+        .filter(|token| !token.is_empty())
+}
+
+/// 256 bits from the OS random source, as hex.
+fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate a token: {}", e))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Replaces the stored token with a fresh one.
+pub fn rotate_token(app: &AppHandle) -> Result<String, String> {
+    let token = generate_token()?;
+    settings::set_config_value(app.clone(), TOKEN_KEY.to_string(), json!(token))?;
+    Ok(token)
+}
+
+fn ensure_token(app: &AppHandle) -> Result<String, String> {
+    match stored_token(app) {
+        Some(token) => Ok(token),
+        None => rotate_token(app),
+    }
+}
+
+fn discovery_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("rscoop").join("automation.json"))
+}
+
+fn write_discovery_file(port: u16, token: &str) {
+    let Some(path) = discovery_path() else {
+        return;
+    };
+    let contents = json!({
+        "url": format!("http://127.0.0.1:{}", port),
+        "token": token,
+        "pid": std::process::id(),
+    });
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, contents.to_string()));
+    #[cfg(unix)]
+    let result = result.and_then(|_| {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to write {}: {}", path.display(), e);
+    }
+}
+
+/// Starts the server with the stored port and token, replacing a running one.
+pub async fn start(app: &AppHandle) -> Result<u16, String> {
+    stop();
+    let token = ensure_token(app)?;
+    let port = configured_port(app);
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .await
+        .map_err(|e| format!("Failed to listen on 127.0.0.1:{}: {}", port, e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let backend: Arc<dyn Backend> = Arc::new(AppBackend { app: app.clone() });
+    let task = tauri::async_runtime::spawn(serve(listener, token.clone(), backend));
+    write_discovery_file(port, &token);
+    *RUNNING.lock().unwrap_or_else(|e| e.into_inner()) = Some(Running { port, task });
+
+    log::info!("Automation API listening on 127.0.0.1:{}", port);
+    Ok(port)
+}
+
+/// Stops the server, if running, and removes the discovery file.
+pub fn stop() {
+    let running = RUNNING.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(running) = running {
+        running.task.abort();
+        if let Some(path) = discovery_path() {
+            let _ = std::fs::remove_file(path);
+        }
+        log::info!("Automation API on port {} stopped", running.port);
+    }
+}
+
+fn listening_on() -> Option<u16> {
+    RUNNING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|running| running.port)
+}
+
+pub fn info(app: &AppHandle) -> AutomationApiInfo {
+    let listening_on = listening_on();
+    AutomationApiInfo {
+        enabled: is_enabled(app),
+        port: configured_port(app),
+        listening_on,
+        token: stored_token(app),
+        discovery_file: listening_on
+            .and(discovery_path())
+            .map(|path| path.to_string_lossy().to_string()),
+    }
+}
+
+/// Starts the server on launch when the user has turned it on.
+pub fn start_if_enabled(app: AppHandle) {
+    if !is_enabled(&app) {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = start(&app).await {
+            log::warn!("Automation API not started: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::{OperationKind, QueuedOperation};
+    use std::net::SocketAddr;
+    use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+
+    const TOKEN: &str = "0123456789abcdef";
+
+    struct FakeBackend {
+        enqueued: Mutex<Vec<EnqueueAction>>,
+        changes: broadcast::Sender<OperationStateSnapshot>,
+    }
+
+    impl Backend for FakeBackend {
+        fn enqueue(&self, action: EnqueueAction) -> String {
+            let mut enqueued = self.enqueued.lock().unwrap();
+            enqueued.push(action);
+            format!("op-{}", enqueued.len())
+        }
+
+        fn operation_state(&self) -> OperationStateSnapshot {
+            OperationStateSnapshot::default()
+        }
+
+        fn subscribe(&self) -> broadcast::Receiver<OperationStateSnapshot> {
+            self.changes.subscribe()
+        }
+
+        fn updates(&self) -> BackendFuture<'_> {
+            Box::pin(async { Ok(json!([{ "name": "git", "available": "2.47.0" }])) })
+        }
+
+        fn status(&self) -> BackendFuture<'_> {
+            Box::pin(async { Err("status unavailable".to_string()) })
+        }
+    }
+
+    async fn start_server() -> (SocketAddr, Arc<FakeBackend>) {
+        let backend = Arc::new(FakeBackend {
+            enqueued: Mutex::new(Vec::new()),
+            changes: broadcast::channel(8).0,
+        });
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, TOKEN.to_string(), backend.clone()));
+        (addr, backend)
+    }
+
+    fn raw(method: &str, path: &str, headers: &[&str], body: &str) -> String {
+        let mut request = format!("{} {} HTTP/1.1\r\nHost: 127.0.0.1\r\n", method, path);
+        for header in headers {
+            request.push_str(header);
+            request.push_str("\r\n");
+        }
+        format!("{}Content-Length: {}\r\n\r\n{}", request, body.len(), body)
+    }
+
+    async fn send(addr: SocketAddr, request: String) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response.split_once("\r\n\r\n").unwrap().1;
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    fn auth() -> String {
+        format!("Authorization: Bearer {}", TOKEN)
+    }
+
+    #[tokio::test]
+    async fn rejects_unauthenticated_and_browser_requests() {
+        let (addr, backend) = start_server().await;
+        let body = r#"{"type":"update-all"}"#;
+
+        let (status, _) = send(addr, raw("POST", "/operations", &[], body)).await;
+        assert_eq!(status, 401);
+        let wrong = "Authorization: Bearer 0123456789abcdeX";
+        let (status, _) = send(addr, raw("POST", "/operations", &[wrong], body)).await;
+        assert_eq!(status, 401);
+        let origin = "Origin: https://example.com";
+        let (status, _) = send(addr, raw("POST", "/operations", &[&auth(), origin], body)).await;
+        assert_eq!(status, 403);
+
+        assert!(backend.enqueued.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn enqueues_actions_and_answers_queries() {
+        let (addr, backend) = start_server().await;
+
+        let body = r#"{"type":"install","package":"git","bucket":"main"}"#;
+        let (status, reply) = send(addr, raw("POST", "/operations", &[&auth()], body)).await;
+        assert_eq!((status, reply), (202, json!({ "id": "op-1" })));
+        assert!(matches!(
+            backend.enqueued.lock().unwrap().as_slice(),
+            [EnqueueAction::Install { package, .. }] if package == "git"
+        ));
+
+        let (status, _) = send(addr, raw("POST", "/operations", &[&auth()], "{}")).await;
+        assert_eq!(status, 400);
+        let (status, reply) = send(addr, raw("GET", "/operations", &[&auth()], "")).await;
+        assert_eq!((status, reply["queue"].clone()), (200, json!([])));
+        let (status, reply) = send(addr, raw("GET", "/updates?fresh=1", &[&auth()], "")).await;
+        assert_eq!((status, reply[0]["name"].clone()), (200, json!("git")));
+        let (status, reply) = send(addr, raw("GET", "/status", &[&auth()], "")).await;
+        assert_eq!(
+            (status, reply),
+            (500, json!({ "error": "status unavailable" }))
+        );
+        let (status, _) = send(addr, raw("DELETE", "/status", &[&auth()], "")).await;
+        assert_eq!(status, 405);
+        let (status, _) = send(addr, raw("GET", "/nope", &[&auth()], "")).await;
+        assert_eq!(status, 404);
+    }
+
+    async fn next_data(lines: &mut Lines<BufReader<TcpStream>>) -> Value {
+        loop {
+            let line = lines.next_line().await.unwrap().unwrap();
+            if let Some(data) = line.strip_prefix("data: ") {
+                return serde_json::from_str(data).unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_operation_state_changes() {
+        let (addr, backend) = start_server().await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(raw("GET", "/operations/events", &[&auth()], "").as_bytes())
+            .await
+            .unwrap();
+        let mut lines = BufReader::new(stream).lines();
+
+        // The current state comes first.
+        assert_eq!(next_data(&mut lines).await["queue"], json!([]));
+
+        backend
+            .changes
+            .send(OperationStateSnapshot {
+                queue: vec![QueuedOperation {
+                    id: "op-7".to_string(),
+                    title: "Installing git".to_string(),
+                    kind: OperationKind::Install,
+                    package_name: Some("git".to_string()),
+                }],
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(next_data(&mut lines).await["queue"][0]["id"], json!("op-7"));
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(rscoop_lib::cli::main());
+}
@@ -0,0 +1,601 @@
+//! Headless `rscoop-cli`: the filesystem-based parts of the backend, without a
+//! webview, for scripts, CI and remote sessions.
+//!
+//! Every command goes through the same `ScoopRoot` service as the app's
+//! commands, but against a root taken from `--scoop`, the `SCOOP` environment
+//! variable or auto-detection instead of the app state. `--json` prints the same
+//! payloads the frontend receives.
+use crate::commands::doctor::checkup;
+use crate::commands::profile;
+use crate::scoop_root::ScoopRoot;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::PathBuf;
+
+const USAGE: &str = "Usage: rscoop-cli [--scoop <path>] [--json] <command> [options]
+
+Commands:
+  search <term>          Search the manifests of all added buckets
+  installed              List installed packages
+  status                 Show what `scoop status` would report
+  updates                List installed packages with newer manifests
+  buckets                List buckets
+      [--health]         Add a health score column (reads every manifest)
+  profile export         Print a profile of this machine
+      [--groups <list>]  Comma-separated: apps,buckets,holds,scoopConfig
+      [--include-secrets] Keep the VirusTotal API key in scoopConfig
+      [--script]         Print a PowerShell setup script instead of JSON
+  profile import <file>  Print the setup script for a profile
+      [--groups <list>]  Only the given groups
+      [--apply]          Run the script with PowerShell
+  checkup                Run the doctor checkup
+  cache                  List the download cache
+  shims                  List shims
+
+Options:
+  --scoop <path>         Scoop root; defaults to $SCOOP, then auto-detection
+  --json                 Print JSON instead of tables
+  -h, --help             Show this help";
+
+/// Groups a headless export can read. rScoop's own preferences live in the
+/// app's store, which only the app can open.
+const DEFAULT_PROFILE_GROUPS: &[&str] = &["apps", "buckets", "holds", "scoopConfig"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    Search(String),
+    Installed,
+    Status,
+    Updates,
+    Buckets {
+        health: bool,
+    },
+    ProfileExport {
+        groups: Vec<String>,
+        include_secrets: bool,
+        script: bool,
+    },
+    ProfileImport {
+        path: PathBuf,
+        groups: Option<Vec<String>>,
+        apply: bool,
+    },
+    Checkup,
+    Cache,
+    Shims,
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Invocation {
+    scoop: Option<PathBuf>,
+    json: bool,
+    command: Command,
+}
+
+/// What a command prints: a JSON payload, and the same as text.
+struct Output {
+    json: Value,
+    text: String,
+}
+
+impl Output {
+    fn new<T: Serialize>(payload: &T, text: String) -> Result<Self, String> {
+        Ok(Self {
+            json: serde_json::to_value(payload).map_err(|e| e.to_string())?,
+            text,
+        })
+    }
+}
+
+fn split_groups(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|group| !group.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Invocation, String> {
+    let mut scoop = None;
+    let mut json = false;
+    let mut groups = None;
+    let mut include_secrets = false;
+    let mut script = false;
+    let mut apply = false;
+    let mut health = false;
+    let mut positional = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--scoop" => {
+                let path = args.next().ok_or("--scoop needs a path")?;
+                scoop = Some(PathBuf::from(path));
+            }
+            "--groups" => {
+                let list = args.next().ok_or("--groups needs a list")?;
+                groups = Some(split_groups(&list));
+            }
+            "--json" => json = true,
+            "--include-secrets" => include_secrets = true,
+            "--script" => script = true,
+            "--apply" => apply = true,
+            "--health" => health = true,
+            "-h" | "--help" => positional = vec!["help".to_string()],
+            flag if flag.starts_with("--") => return Err(format!("Unknown option '{}'", flag)),
+            _ => positional.push(arg),
+        }
+    }
+
+    let words: Vec<&str> = positional.iter().map(String::as_str).collect();
+    let command = match words.as_slice() {
+        [] | ["help"] => Command::Help,
+        ["search", term @ ..] if !term.is_empty() => Command::Search(term.join(" ")),
+        ["search"] => return Err("search needs a term".to_string()),
+        ["installed"] => Command::Installed,
+        ["status"] => Command::Status,
+        ["updates"] => Command::Updates,
+        ["buckets"] => Command::Buckets { health },
+        ["profile", "export"] => Command::ProfileExport {
+            groups: groups.take().unwrap_or_else(|| {
+                DEFAULT_PROFILE_GROUPS
+                    .iter()
+                    .map(|g| g.to_string())
+                    .collect()
+            }),
+            include_secrets,
+            script,
+        },
+        ["profile", "import", path] => Command::ProfileImport {
+            path: PathBuf::from(path),
+            groups: groups.take(),
+            apply,
+        },
+        ["profile", ..] => {
+            return Err("Use `profile export` or `profile import <file>`".to_string())
+        }
+        ["checkup"] => Command::Checkup,
+        ["cache"] => Command::Cache,
+        ["shims"] => Command::Shims,
+        [other, ..] => return Err(format!("Unknown command '{}'", other)),
+    };
+
+    Ok(Invocation {
+        scoop,
+        json,
+        command,
+    })
+}
+
+fn resolve_scoop_path(explicit: Option<PathBuf>) -> Result<PathBuf, String> {
+    if let Some(path) = explicit {
+        return Ok(path);
+    }
+    match std::env::var_os("SCOOP") {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => crate::utils::detect_scoop_root()
+            .map_err(|e| format!("{} Pass --scoop <path> or set SCOOP.", e)),
+    }
+}
+
+/// Aligns rows into columns under a header.
+fn table(header: &[&str], rows: Vec<Vec<String>>) -> String {
+    if rows.is_empty() {
+        return "Nothing to show.".to_string();
+    }
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+    std::iter::once(header)
+        .chain(rows)
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+fn run_profile_script(script: &str) -> Result<(), String> {
+    let path = std::env::temp_dir().join(format!("rscoop-profile-{}.ps1", std::process::id()));
+    std::fs::write(&path, script).map_err(|e| format!("Failed to write script: {}", e))?;
+    let status = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File"])
+        .arg(&path)
+        .status();
+    let _ = std::fs::remove_file(&path);
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("Setup script failed ({})", status)),
+        Err(e) => Err(format!("Failed to run PowerShell: {}", e)),
+    }
+}
+
+async fn execute(root: &ScoopRoot<'_>, command: Command) -> Result<Output, String> {
+    match command {
+        Command::Search(term) => {
+            let result = root.search(&term).await?;
+            let mut rows: Vec<Vec<String>> = result
+                .packages
+                .iter()
+                .map(|p| {
+                    vec![
+                        p.name.clone(),
+                        p.version.clone(),
+                        p.source.clone(),
+                        if p.is_installed { "installed" } else { "" }.to_string(),
+                    ]
+                })
+                .collect();
+            rows.extend(result.remote_matches.iter().map(|m| {
+                vec![
+                    m.name.clone(),
+                    m.version.clone(),
+                    m.bucket.clone(),
+                    "bucket not added".to_string(),
+                ]
+            }));
+            let text = table(&["Name", "Version", "Bucket", ""], rows);
+            Output::new(&result, text)
+        }
+        Command::Installed => {
+            let mut packages = root.installed_packages().await?;
+            packages.sort_by_key(|p| p.name.to_lowercase());
+            let rows = packages
+                .iter()
+                .map(|p| {
+                    vec![
+                        p.name.clone(),
+                        p.version.clone(),
+                        p.source.clone(),
+                        p.updated.clone(),
+                    ]
+                })
+                .collect();
+            let text = table(&["Name", "Version", "Bucket", "Updated"], rows);
+            Output::new(&packages, text)
+        }
+        Command::Status => {
+            let report = root.status().await?;
+            let mut lines = Vec::new();
+            if report.scoop_needs_update {
+                lines.push("Scoop is out of date. Run `scoop update`.".to_string());
+            }
+            if report.bucket_needs_update {
+                lines.push("Some buckets are out of date. Run `scoop update`.".to_string());
+            }
+            let rows: Vec<Vec<String>> = report
+                .apps_with_issues
+                .iter()
+                .map(|app| {
+                    vec![
+                        app.name.clone(),
+                        app.installed_version.clone(),
+                        app.latest_version.clone().unwrap_or_default(),
+                        app.info.join(", "),
+                    ]
+                })
+                .collect();
+            if !rows.is_empty() {
+                lines.push(table(&["Name", "Installed", "Latest", "Info"], rows));
+            }
+            if report.is_everything_ok {
+                lines.push("Everything is ok!".to_string());
+            }
+            Output::new(&report, lines.join("\n"))
+        }
+        Command::Updates => {
+            let updatable = root.updates().await?;
+            let rows = updatable
+                .iter()
+                .map(|u| vec![u.name.clone(), u.current.clone(), u.available.clone()])
+                .collect();
+            let text = table(&["Name", "Installed", "Available"], rows);
+            Output::new(&updatable, text)
+        }
+        Command::Buckets { health } => {
+            let buckets = if health {
+                root.buckets_with_health().await?
+            } else {
+                root.buckets().await?
+            };
+            let rows = buckets
+                .iter()
+                .map(|b| {
+                    let mut row = vec![b.name.clone(), b.manifest_count.to_string()];
+                    if health {
+                        row.push(
+                            b.health
+                                .as_ref()
+                                .map(|h| h.score.to_string())
+                                .unwrap_or_default(),
+                        );
+                    }
+                    row.push(b.git_url.clone().unwrap_or_default());
+                    row
+                })
+                .collect();
+            let header: &[&str] = if health {
+                &["Name", "Manifests", "Health", "Source"]
+            } else {
+                &["Name", "Manifests", "Source"]
+            };
+            let text = table(header, rows);
+            Output::new(&buckets, text)
+        }
+        Command::ProfileExport {
+            groups,
+            include_secrets,
+            script,
+        } => {
+            if groups.iter().any(|g| g == "rscoopSettings") {
+                eprintln!(
+                    "rscoop-cli: rscoopSettings can only be exported from the app; skipping."
+                );
+            }
+            let profile =
+                profile::build_profile(root.path(), groups, include_secrets, None).await?;
+            let text = if script {
+                profile::render_profile_setup_script(&profile)
+            } else {
+                serde_json::to_string_pretty(&profile).map_err(|e| e.to_string())?
+            };
+            Output::new(&profile, text)
+        }
+        Command::ProfileImport {
+            path,
+            groups,
+            apply,
+        } => {
+            let json = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let (mut parsed, warnings) = profile::parse_profile_lenient(&json)?;
+            if let Some(groups) = &groups {
+                let want = |id: &str| groups.iter().any(|g| g == id);
+                if !want("apps") {
+                    parsed.apps = None;
+                }
+                if !want("buckets") {
+                    parsed.buckets = None;
+                }
+                if !want("holds") {
+                    parsed.holds = None;
+                }
+                if !want("scoopConfig") {
+                    parsed.scoop_config = None;
+                }
+                parsed.rscoop_settings = None;
+                parsed.groups = groups.clone();
+            }
+            for warning in &warnings {
+                eprintln!("rscoop-cli: {}", warning);
+            }
+            let script = profile::render_profile_setup_script(&parsed);
+            if apply {
+                run_profile_script(&script)?;
+            }
+            let payload = serde_json::json!({
+                "script": script,
+                "applied": apply,
+                "warnings": warnings,
+            });
+            let text = if apply {
+                "Profile applied.".to_string()
+            } else {
+                script
+            };
+            Output::new(&payload, text)
+        }
+        Command::Checkup => {
+            let scoop_path = root.path().to_path_buf();
+            let items = tokio::task::spawn_blocking(move || {
+                checkup::run_checks(&scoop_path, checkup::check_git_installed_blocking())
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+            let text = items
+                .iter()
+                .map(|item| {
+                    let mark = if item.status { "ok  " } else { "FAIL" };
+                    match (&item.suggestion, item.status) {
+                        (Some(suggestion), false) => {
+                            format!("[{}] {}\n       {}", mark, item.text, suggestion)
+                        }
+                        _ => format!("[{}] {}", mark, item.text),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Output::new(&items, text)
+        }
+        Command::Cache => {
+            let entries = root.cache_entries().await?;
+            let rows = entries
+                .iter()
+                .map(|e| {
+                    vec![
+                        e.name.clone(),
+                        e.version.clone(),
+                        format!("{:.1} MB", e.length as f64 / 1_048_576.0),
+                        yes_no(e.is_safe_to_delete),
+                    ]
+                })
+                .collect();
+            let text = table(&["Name", "Version", "Size", "Safe to delete"], rows);
+            Output::new(&entries, text)
+        }
+        Command::Shims => {
+            let shims = root.shims()?;
+            let rows = shims
+                .iter()
+                .map(|s| {
+                    vec![
+                        s.name.clone(),
+                        s.source.clone(),
+                        yes_no(s.is_global),
+                        yes_no(s.is_hidden),
+                        s.path.clone(),
+                    ]
+                })
+                .collect();
+            let text = table(&["Name", "Source", "Global", "Hidden", "Target"], rows);
+            Output::new(&shims, text)
+        }
+        Command::Help => Ok(Output {
+            json: Value::String(USAGE.to_string()),
+            text: USAGE.to_string(),
+        }),
+    }
+}
+
+/// Runs the CLI with the process arguments and returns the exit code.
+pub fn main() -> i32 {
+    let invocation = match parse_args(std::env::args().skip(1)) {
+        Ok(invocation) => invocation,
+        Err(e) => {
+            eprintln!("rscoop-cli: {}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+    if invocation.command == Command::Help {
+        println!("{}", USAGE);
+        return 0;
+    }
+
+    let scoop_path = match resolve_scoop_path(invocation.scoop) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("rscoop-cli: {}", e);
+            return 1;
+        }
+    };
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("rscoop-cli: failed to start runtime: {}", e);
+            return 1;
+        }
+    };
+
+    match runtime.block_on(execute(&ScoopRoot::new(scoop_path), invocation.command)) {
+        Ok(output) if invocation.json => {
+            match serde_json::to_string_pretty(&output.json) {
+                Ok(json) => println!("{}", json),
+                Err(e) => {
+                    eprintln!("rscoop-cli: {}", e);
+                    return 1;
+                }
+            }
+            0
+        }
+        Ok(output) => {
+            println!("{}", output.text);
+            0
+        }
+        Err(e) => {
+            eprintln!("rscoop-cli: {}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Invocation, String> {
+        parse_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn parses_global_options_anywhere() {
+        let invocation = parse(&[
+            "search",
+            "--json",
+            "visual",
+            "studio",
+            "--scoop",
+            "D:\\scoop",
+        ])
+        .unwrap();
+        assert!(invocation.json);
+        assert_eq!(invocation.scoop, Some(PathBuf::from("D:\\scoop")));
+        assert_eq!(
+            invocation.command,
+            Command::Search("visual studio".to_string())
+        );
+        assert_eq!(parse(&[]).unwrap().command, Command::Help);
+        assert_eq!(parse(&["status", "-h"]).unwrap().command, Command::Help);
+        assert_eq!(
+            parse(&["buckets", "--health"]).unwrap().command,
+            Command::Buckets { health: true }
+        );
+    }
+
+    #[test]
+    fn parses_profile_commands() {
+        assert_eq!(
+            parse(&["profile", "export", "--groups", "apps, holds", "--script"])
+                .unwrap()
+                .command,
+            Command::ProfileExport {
+                groups: vec!["apps".to_string(), "holds".to_string()],
+                include_secrets: false,
+                script: true,
+            }
+        );
+        match parse(&["profile", "export"]).unwrap().command {
+            Command::ProfileExport { groups, .. } => assert_eq!(groups, DEFAULT_PROFILE_GROUPS),
+            other => panic!("unexpected command {:?}", other),
+        }
+        assert_eq!(
+            parse(&["profile", "import", "me.json", "--apply"])
+                .unwrap()
+                .command,
+            Command::ProfileImport {
+                path: PathBuf::from("me.json"),
+                groups: None,
+                apply: true,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_bad_usage() {
+        assert!(parse(&["search"]).is_err());
+        assert!(parse(&["profile"]).is_err());
+        assert!(parse(&["frobnicate"]).is_err());
+        assert!(parse(&["installed", "--verbose"]).is_err());
+        assert!(parse(&["installed", "--scoop"]).is_err());
+    }
+
+    #[test]
+    fn aligns_table_columns() {
+        let text = table(
+            &["Name", "Version"],
+            vec![
+                vec!["git".to_string(), "2.47.0".to_string()],
+                vec!["python312".to_string(), "3.12.7".to_string()],
+            ],
+        );
+        assert_eq!(
+            text,
+            "Name       Version\ngit        2.47.0\npython312  3.12.7"
+        );
+        assert_eq!(table(&["Name"], vec![]), "Nothing to show.");
+    }
+}
@@ -9,17 +9,19 @@ use std::collections::HashSet;
 use std::path::Path;
 use tauri::{AppHandle, Runtime, State};
 
+/// Numeric segments of a version string, split on `.`, `-` and `_`.
+/// Non-numeric segments count as 0.
+pub fn version_segments(version: &str) -> Vec<u64> {
+    version
+        .split(['.', '-', '_'])
+        .map(|seg| seg.parse::<u64>().unwrap_or(0))
+        .collect()
+}
+
 /// Compares two version strings numerically by splitting on `.` and `-`.
 /// For example: "148.0-1" > "147.0.3-2" > "9.0" > "2.0".
 pub fn compare_versions(a: &str, b: &str) -> Ordering {
-    let parse_segments = |s: &str| -> Vec<u64> {
-        s.split(|c: char| c == '.' || c == '-' || c == '_')
-            .map(|seg| seg.parse::<u64>().unwrap_or(0))
-            .collect()
-    };
-    let sa = parse_segments(a);
-    let sb = parse_segments(b);
-    sa.cmp(&sb)
+    version_segments(a).cmp(&version_segments(b))
 }
 
 /// Settings for automatic cleanup operations.
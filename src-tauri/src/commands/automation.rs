@@ -0,0 +1,49 @@
+//! Tauri command surface for the local automation API.
+use crate::automation::{self, AutomationApiInfo};
+use serde_json::json;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub fn get_automation_api_info(app: AppHandle) -> AutomationApiInfo {
+    automation::info(&app)
+}
+
+/// Turns the server on or off and remembers the choice. A `port` of `None`
+/// keeps the configured one.
+#[tauri::command]
+pub async fn set_automation_api_enabled(
+    app: AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<AutomationApiInfo, String> {
+    if let Some(port) = port {
+        crate::commands::settings::set_config_value(
+            app.clone(),
+            automation::PORT_KEY.to_string(),
+            json!(port),
+        )?;
+    }
+    crate::commands::settings::set_config_value(
+        app.clone(),
+        automation::ENABLED_KEY.to_string(),
+        json!(enabled),
+    )?;
+
+    if enabled {
+        automation::start(&app).await?;
+    } else {
+        automation::stop();
+    }
+    Ok(automation::info(&app))
+}
+
+/// Issues a new token. A running server is restarted so the old token stops
+/// working immediately.
+#[tauri::command]
+pub async fn rotate_automation_api_token(app: AppHandle) -> Result<AutomationApiInfo, String> {
+    automation::rotate_token(&app)?;
+    if automation::is_enabled(&app) {
+        automation::start(&app).await?;
+    }
+    Ok(automation::info(&app))
+}
@@ -1,12 +1,18 @@
 //! Command for managing Scoop buckets - repositories containing package manifests.
-use crate::models::BucketInfo;
+use crate::commands::{bucket_health, bucket_pin};
+use crate::models::{BucketHealth, BucketInfo};
 use crate::state::AppState;
 use crate::utils::{self, validate_scoop_child_dir};
 use git2::Repository;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tauri::{AppHandle, Runtime, State};
 
+/// Timeout for a single download-host probe.
+const HOST_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Checks if a directory is a Git repository by looking for .git directory.
 fn is_git_repo(path: &Path) -> bool {
     path.join(".git").exists()
@@ -39,6 +45,15 @@ fn get_git_info(bucket_path: &Path) -> (Option<String>, Option<String>) {
     (git_url, git_branch)
 }
 
+/// Reads the pin of a bucket and how far upstream has moved past it.
+fn get_pin_info(bucket_path: &Path) -> Option<(bucket_pin::BucketPin, Option<usize>)> {
+    let pin = bucket_pin::read_pin(bucket_path)?;
+    let behind = Repository::open(bucket_path)
+        .ok()
+        .and_then(|repo| bucket_pin::commits_behind(&repo, &pin));
+    Some((pin, behind))
+}
+
 /// Gets the last modified time of a directory.
 fn get_last_updated(path: &Path) -> Option<String> {
     fs::metadata(path)
@@ -66,11 +81,24 @@ fn load_bucket_info(bucket_path: &Path) -> Result<BucketInfo, String> {
 
     let manifest_count = utils::count_manifests(bucket_path);
     let is_git_repo = is_git_repo(bucket_path);
-    let (git_url, git_branch) = if is_git_repo {
+    let (git_url, mut git_branch) = if is_git_repo {
         get_git_info(bucket_path)
     } else {
         (None, None)
     };
+    let pin_info = if is_git_repo {
+        get_pin_info(bucket_path)
+    } else {
+        None
+    };
+    let (pinned_ref, commits_behind) = match pin_info {
+        Some((pin, behind)) => {
+            // HEAD is detached while pinned; report the branch it follows.
+            git_branch = Some(pin.branch);
+            (Some(pin.reference), behind)
+        }
+        None => (None, None),
+    };
     let last_updated = get_last_updated(bucket_path);
 
     Ok(BucketInfo {
@@ -81,18 +109,35 @@ fn load_bucket_info(bucket_path: &Path) -> Result<BucketInfo, String> {
         git_url,
         git_branch,
         last_updated,
+        pinned_ref,
+        commits_behind,
+        health: None,
     })
 }
 
 /// Fetches a list of all Scoop buckets by scanning the buckets directory.
+/// Health is only computed with `include_health`, since it reads every
+/// manifest of every bucket.
 #[tauri::command]
 pub async fn get_buckets<R: Runtime>(
     _app: AppHandle<R>,
     state: State<'_, AppState>,
+    include_health: Option<bool>,
 ) -> Result<Vec<BucketInfo>, String> {
+    let root = state.root();
+    if include_health.unwrap_or(false) {
+        root.buckets_with_health().await
+    } else {
+        root.buckets().await
+    }
+}
+
+/// Reads every bucket under `scoop_path`. `health` is left unset; see
+/// [`attach_health`].
+pub async fn list_buckets(scoop_path: &Path) -> Result<Vec<BucketInfo>, String> {
     log::info!("Fetching Scoop buckets from filesystem");
 
-    let buckets_path = state.scoop_path().join("buckets");
+    let buckets_path = scoop_path.join("buckets");
 
     if !buckets_path.is_dir() {
         log::warn!(
@@ -124,19 +169,134 @@ pub async fn get_buckets<R: Runtime>(
     Ok(buckets)
 }
 
-/// Gets detailed information about a specific bucket.
+/// Gets detailed information about a specific bucket, with its health when
+/// `include_health` is set.
 #[tauri::command]
 pub async fn get_bucket_info<R: Runtime>(
     _app: AppHandle<R>,
     state: State<'_, AppState>,
     bucket_name: String,
+    include_health: Option<bool>,
 ) -> Result<BucketInfo, String> {
     log::info!("Getting info for bucket: {}", bucket_name);
 
-    let bucket_path =
-        validate_scoop_child_dir(&state.scoop_path().join("buckets"), &bucket_name, "Bucket")?;
+    let buckets_path = state.scoop_path().join("buckets");
+    let bucket_path = validate_scoop_child_dir(&buckets_path, &bucket_name, "Bucket")?;
+
+    let mut bucket = load_bucket_info(&bucket_path)?;
+    if include_health.unwrap_or(false) {
+        attach_health(std::slice::from_mut(&mut bucket), &buckets_path).await;
+    }
+    Ok(bucket)
+}
+
+/// Computes health for all installed buckets off the async runtime.
+async fn compute_all_health(buckets_path: &Path) -> Vec<BucketHealth> {
+    let buckets_path = buckets_path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let installed = bucket_health::installed_buckets(&buckets_path);
+        bucket_health::compute_health(&installed, chrono::Utc::now())
+    })
+    .await
+    .unwrap_or_else(|e| {
+        log::warn!("Bucket health computation failed: {}", e);
+        Vec::new()
+    })
+}
+
+/// Fills `health` on each bucket. Duplicates are resolved against every
+/// installed bucket, not just the ones passed in.
+pub async fn attach_health(buckets: &mut [BucketInfo], buckets_path: &Path) {
+    let mut health_by_name: HashMap<String, BucketHealth> = compute_all_health(buckets_path)
+        .await
+        .into_iter()
+        .map(|health| (health.bucket_name.clone(), health))
+        .collect();
+    for bucket in buckets {
+        bucket.health = health_by_name.remove(&bucket.name);
+    }
+}
+
+/// Returns the hosts that did not answer at all. HTTP error statuses still
+/// count as alive; only connection failures and timeouts mark a host dead.
+async fn probe_hosts(hosts: BTreeSet<String>) -> BTreeSet<String> {
+    let client = match reqwest::Client::builder()
+        .timeout(HOST_PROBE_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            log::warn!("Failed to build HTTP client for host probing: {}", e);
+            return BTreeSet::new();
+        }
+    };
+
+    let mut probes = tokio::task::JoinSet::new();
+    for host in hosts {
+        let client = client.clone();
+        probes.spawn(async move {
+            let result = client.head(format!("https://{}/", host)).send().await;
+            let dead = matches!(&result, Err(e) if e.is_connect() || e.is_timeout());
+            (host, dead)
+        });
+    }
+
+    let mut dead_hosts = BTreeSet::new();
+    while let Some(probe) = probes.join_next().await {
+        if let Ok((host, true)) = probe {
+            dead_hosts.insert(host);
+        }
+    }
+    dead_hosts
+}
+
+/// Computes health for installed buckets. With `probe_hosts`, every download
+/// host referenced by the manifests is contacted once to find dead hosts.
+#[tauri::command]
+pub async fn get_bucket_health<R: Runtime>(
+    _app: AppHandle<R>,
+    state: State<'_, AppState>,
+    probe_hosts: Option<bool>,
+) -> Result<Vec<BucketHealth>, String> {
+    let buckets_path = state.scoop_path().join("buckets");
+    let mut health = compute_all_health(&buckets_path).await;
+
+    if !probe_hosts.unwrap_or(false) {
+        return Ok(health);
+    }
+
+    let hosts_by_bucket: HashMap<String, Vec<String>> = health
+        .iter()
+        .map(|entry| {
+            let path: PathBuf = buckets_path.join(&entry.bucket_name);
+            (
+                entry.bucket_name.clone(),
+                bucket_health::bucket_hosts(&path),
+            )
+        })
+        .collect();
+    let all_hosts: BTreeSet<String> = hosts_by_bucket.values().flatten().cloned().collect();
+    log::info!(
+        "Probing {} download hosts for bucket health",
+        all_hosts.len()
+    );
+    let dead = self::probe_hosts(all_hosts).await;
+
+    for entry in &mut health {
+        let bucket_dead = hosts_by_bucket
+            .get(&entry.bucket_name)
+            .map(|hosts| {
+                hosts
+                    .iter()
+                    .filter(|host| dead.contains(*host))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        bucket_health::apply_dead_hosts(entry, bucket_dead);
+    }
 
-    load_bucket_info(&bucket_path)
+    Ok(health)
 }
 
 /// Lists all manifest files in a specific bucket.
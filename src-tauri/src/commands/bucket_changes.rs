@@ -0,0 +1,363 @@
+//! Structured change reports for bucket updates.
+//!
+//! When a bucket moves from one commit to another, the two trees are diffed to
+//! find which manifests were added, removed or changed. Version bumps are then
+//! cross-referenced with installed packages so callers can tell the user how
+//! many of their apps have updates after a pull.
+use crate::manifest::Manifest;
+use crate::models::ScoopPackage;
+use git2::{Delta, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A manifest whose contents changed between two bucket revisions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ManifestChange {
+    pub name: String,
+    pub old_version: Option<String>,
+    pub new_version: Option<String>,
+}
+
+impl ManifestChange {
+    /// True when both revisions carry a version and they differ.
+    pub fn is_version_bump(&self) -> bool {
+        matches!(
+            (&self.old_version, &self.new_version),
+            (Some(old), Some(new)) if old != new
+        )
+    }
+}
+
+/// A version bump for a package that is installed from the updated bucket.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InstalledVersionBump {
+    pub name: String,
+    pub bucket: String,
+    pub installed_version: String,
+    pub available_version: String,
+}
+
+/// Everything that changed in a single bucket during one update.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct BucketChangeReport {
+    pub bucket_name: String,
+    pub old_commit: String,
+    pub new_commit: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<ManifestChange>,
+    pub installed_updates: Vec<InstalledVersionBump>,
+}
+
+/// Aggregated report for a multi-bucket update, stored by the scheduler so the
+/// UI can show what the last automatic pull brought in.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BucketUpdateReport {
+    pub finished_at: String,
+    pub buckets: Vec<BucketChangeReport>,
+    pub installed_updates: Vec<InstalledVersionBump>,
+}
+
+impl BucketUpdateReport {
+    /// Collects per-bucket reports into one summary. Installed updates are
+    /// flattened and sorted by package name.
+    pub fn from_reports<'a>(reports: impl IntoIterator<Item = &'a BucketChangeReport>) -> Self {
+        let buckets: Vec<BucketChangeReport> = reports.into_iter().cloned().collect();
+        let mut installed_updates: Vec<InstalledVersionBump> = buckets
+            .iter()
+            .flat_map(|report| report.installed_updates.iter().cloned())
+            .collect();
+        installed_updates.sort_by_key(|update| update.name.to_lowercase());
+
+        Self {
+            finished_at: chrono::Utc::now().to_rfc3339(),
+            buckets,
+            installed_updates,
+        }
+    }
+}
+
+/// Maps a path inside a bucket tree to a package name, using the same rules as
+/// `utils::bucket_manifest_paths`: visible `.json` files at the bucket root
+/// (except `bucket.json`) or anywhere below `bucket/`.
+fn manifest_name_from_tree_path(path: &Path) -> Option<String> {
+    if path.extension().and_then(|s| s.to_str()) != Some("json") {
+        return None;
+    }
+
+    let file_name = path.file_name().and_then(|n| n.to_str())?;
+    if file_name.starts_with('.') {
+        return None;
+    }
+
+    let mut components = path.components();
+    let first = components.next()?.as_os_str().to_str()?;
+    let is_root = components.next().is_none();
+
+    if is_root {
+        if file_name == "bucket.json" {
+            return None;
+        }
+    } else if first != "bucket" {
+        return None;
+    }
+
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_string())
+}
+
+/// Reads the `version` field from a manifest blob. Unparseable manifests yield
+/// `None` rather than failing the whole report.
+fn read_blob_version(repo: &Repository, id: Oid) -> Option<String> {
+    if id.is_zero() {
+        return None;
+    }
+    let blob = repo.find_blob(id).ok()?;
+    serde_json::from_slice::<Manifest>(blob.content())
+        .ok()
+        .map(|manifest| manifest.version)
+}
+
+/// Diffs the trees of two bucket commits and classifies every manifest change.
+pub fn diff_bucket_revisions(
+    repo: &Repository,
+    bucket_name: &str,
+    old_commit: Oid,
+    new_commit: Oid,
+) -> Result<BucketChangeReport, String> {
+    let tree_for = |oid: Oid| {
+        repo.find_commit(oid)
+            .and_then(|commit| commit.tree())
+            .map_err(|e| {
+                format!(
+                    "Failed to read tree for commit {} in bucket '{}': {}",
+                    oid, bucket_name, e
+                )
+            })
+    };
+
+    let old_tree = tree_for(old_commit)?;
+    let new_tree = tree_for(new_commit)?;
+
+    let diff = repo
+        .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)
+        .map_err(|e| format!("Failed to diff bucket '{}': {}", bucket_name, e))?;
+
+    let mut report = BucketChangeReport {
+        bucket_name: bucket_name.to_string(),
+        old_commit: old_commit.to_string(),
+        new_commit: new_commit.to_string(),
+        ..Default::default()
+    };
+
+    for delta in diff.deltas() {
+        match delta.status() {
+            Delta::Added | Delta::Copied => {
+                if let Some(name) = delta
+                    .new_file()
+                    .path()
+                    .and_then(manifest_name_from_tree_path)
+                {
+                    report.added.push(name);
+                }
+            }
+            Delta::Deleted => {
+                if let Some(name) = delta
+                    .old_file()
+                    .path()
+                    .and_then(manifest_name_from_tree_path)
+                {
+                    report.removed.push(name);
+                }
+            }
+            Delta::Modified | Delta::Typechange => {
+                if let Some(name) = delta
+                    .new_file()
+                    .path()
+                    .and_then(manifest_name_from_tree_path)
+                {
+                    report.changed.push(ManifestChange {
+                        name,
+                        old_version: read_blob_version(repo, delta.old_file().id()),
+                        new_version: read_blob_version(repo, delta.new_file().id()),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    report.added.sort();
+    report.removed.sort();
+    report.changed.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(report)
+}
+
+/// Fills `installed_updates` with the version bumps that affect packages
+/// installed from this bucket. Versioned installs are skipped because they are
+/// pinned on purpose.
+pub fn match_installed_updates(report: &mut BucketChangeReport, installed: &[ScoopPackage]) {
+    report.installed_updates = report
+        .changed
+        .iter()
+        .filter(|change| change.is_version_bump())
+        .filter_map(|change| {
+            let new_version = change.new_version.as_ref()?;
+            let package = installed.iter().find(|pkg| {
+                !pkg.is_versioned_install
+                    && pkg.name.eq_ignore_ascii_case(&change.name)
+                    && pkg.source.eq_ignore_ascii_case(&report.bucket_name)
+            })?;
+
+            (package.version != *new_version).then(|| InstalledVersionBump {
+                name: package.name.clone(),
+                bucket: report.bucket_name.clone(),
+                installed_version: package.version.clone(),
+                available_version: new_version.clone(),
+            })
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use git2::Signature;
+    use std::fs;
+
+    fn write_manifest(root: &Path, relative: &str, version: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).expect("create manifest parent");
+        fs::write(path, format!(r#"{{"version":"{}"}}"#, version)).expect("write manifest");
+    }
+
+    fn commit_all(repo: &Repository, message: &str) -> Oid {
+        let mut index = repo.index().expect("open index");
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .expect("stage files");
+        index
+            .update_all(["*"].iter(), None)
+            .expect("stage deletions");
+        index.write().expect("write index");
+        let tree_id = index.write_tree().expect("write tree");
+        let tree = repo.find_tree(tree_id).expect("find tree");
+        let signature = Signature::now("rscoop", "rscoop@example.com").expect("signature");
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .expect("commit")
+    }
+
+    fn installed(name: &str, source: &str, version: &str) -> ScoopPackage {
+        ScoopPackage {
+            name: name.to_string(),
+            source: source.to_string(),
+            version: version.to_string(),
+            is_installed: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn manifest_paths_follow_bucket_layout_rules() {
+        let name = |p: &str| manifest_name_from_tree_path(Path::new(p));
+
+        assert_eq!(name("root-app.json").as_deref(), Some("root-app"));
+        assert_eq!(name("bucket/app.json").as_deref(), Some("app"));
+        assert_eq!(name("bucket/a/nested.json").as_deref(), Some("nested"));
+        assert_eq!(name("bucket.json"), None);
+        assert_eq!(name("bucket/.hidden.json"), None);
+        assert_eq!(name("scripts/helper.json"), None);
+        assert_eq!(name("README.md"), None);
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_manifests() {
+        let temp = TempDir::new("bucket-changes");
+        let repo = Repository::init(&temp.path).expect("init repo");
+
+        write_manifest(&temp.path, "bucket/kept.json", "1.0.0");
+        write_manifest(&temp.path, "bucket/bumped.json", "1.0.0");
+        write_manifest(&temp.path, "bucket/dropped.json", "1.0.0");
+        fs::write(temp.path.join("README.md"), "docs").unwrap();
+        let old = commit_all(&repo, "initial");
+
+        write_manifest(&temp.path, "bucket/bumped.json", "2.0.0");
+        write_manifest(&temp.path, "bucket/fresh.json", "0.1.0");
+        fs::remove_file(temp.path.join("bucket/dropped.json")).unwrap();
+        fs::write(temp.path.join("README.md"), "more docs").unwrap();
+        let new = commit_all(&repo, "update");
+
+        let report = diff_bucket_revisions(&repo, "main", old, new).expect("diff");
+
+        assert_eq!(report.added, vec!["fresh".to_string()]);
+        assert_eq!(report.removed, vec!["dropped".to_string()]);
+        assert_eq!(
+            report.changed,
+            vec![ManifestChange {
+                name: "bumped".to_string(),
+                old_version: Some("1.0.0".to_string()),
+                new_version: Some("2.0.0".to_string()),
+            }]
+        );
+        assert_eq!(report.old_commit, old.to_string());
+        assert_eq!(report.new_commit, new.to_string());
+    }
+
+    #[test]
+    fn installed_updates_only_include_packages_from_the_same_bucket() {
+        let mut report = BucketChangeReport {
+            bucket_name: "extras".to_string(),
+            changed: vec![
+                ManifestChange {
+                    name: "vscode".to_string(),
+                    old_version: Some("1.0".to_string()),
+                    new_version: Some("1.1".to_string()),
+                },
+                ManifestChange {
+                    name: "git".to_string(),
+                    old_version: Some("2.0".to_string()),
+                    new_version: Some("2.1".to_string()),
+                },
+                ManifestChange {
+                    name: "notes-only".to_string(),
+                    old_version: Some("3.0".to_string()),
+                    new_version: Some("3.0".to_string()),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut pinned = installed("vscode", "extras", "0.9");
+        pinned.is_versioned_install = true;
+        let installed_packages = vec![
+            installed("VSCode", "extras", "1.0"),
+            installed("git", "main", "2.0"),
+            installed("notes-only", "extras", "3.0"),
+            pinned,
+        ];
+
+        match_installed_updates(&mut report, &installed_packages);
+
+        assert_eq!(
+            report.installed_updates,
+            vec![InstalledVersionBump {
+                name: "VSCode".to_string(),
+                bucket: "extras".to_string(),
+                installed_version: "1.0".to_string(),
+                available_version: "1.1".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Progress bookkeeping for concurrent bucket updates.
+//!
+//! Each bucket reports its own transfer progress; [`FetchProgressTracker`]
+//! folds those into a single fraction for the active operation and throttles
+//! per-bucket events to whole-percent or stage changes.
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Event carrying a [`BucketFetchProgress`] payload.
+pub const EVENT_BUCKET_FETCH_PROGRESS: &str = "bucket-fetch-progress";
+
+/// Worker count used when the setting is missing.
+const DEFAULT_WORKERS: usize = 4;
+/// Upper bound on concurrent fetches. Git hosts throttle aggressive clients.
+const MAX_WORKERS: usize = 8;
+/// Share of a bucket's progress that belongs to the network fetch; the rest
+/// covers syncing the working tree and building the change report.
+const FETCH_WEIGHT: f32 = 0.9;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FetchStage {
+    Queued,
+    Receiving,
+    Indexing,
+    Syncing,
+    Done,
+    Failed,
+}
+
+/// Progress of a single bucket, emitted as [`EVENT_BUCKET_FETCH_PROGRESS`].
+#[derive(Debug, Clone, Serialize)]
+pub struct BucketFetchProgress {
+    pub bucket_name: String,
+    pub stage: FetchStage,
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    pub total_objects: usize,
+    pub received_bytes: usize,
+    /// Overall progress for this bucket (0..=1).
+    pub fraction: f32,
+}
+
+impl BucketFetchProgress {
+    /// Progress at a stage that carries no transfer statistics.
+    pub fn stage(bucket_name: &str, stage: FetchStage) -> Self {
+        let fraction = match stage {
+            FetchStage::Queued | FetchStage::Receiving | FetchStage::Indexing => 0.0,
+            FetchStage::Syncing => FETCH_WEIGHT,
+            FetchStage::Done | FetchStage::Failed => 1.0,
+        };
+        Self {
+            bucket_name: bucket_name.to_string(),
+            stage,
+            received_objects: 0,
+            indexed_objects: 0,
+            total_objects: 0,
+            received_bytes: 0,
+            fraction,
+        }
+    }
+
+    /// Progress from git transfer statistics. Receiving and indexing each
+    /// count for half of the fetch share.
+    pub fn transfer(
+        bucket_name: &str,
+        received_objects: usize,
+        indexed_objects: usize,
+        total_objects: usize,
+        received_bytes: usize,
+    ) -> Self {
+        let (stage, fraction) = if total_objects == 0 {
+            (FetchStage::Receiving, 0.0)
+        } else {
+            let stage = if received_objects < total_objects {
+                FetchStage::Receiving
+            } else {
+                FetchStage::Indexing
+            };
+            let done = (received_objects + indexed_objects) as f32 / (2 * total_objects) as f32;
+            (stage, done.clamp(0.0, 1.0) * FETCH_WEIGHT)
+        };
+        Self {
+            bucket_name: bucket_name.to_string(),
+            stage,
+            received_objects,
+            indexed_objects,
+            total_objects,
+            received_bytes,
+            fraction,
+        }
+    }
+}
+
+/// Combined progress over a fixed set of buckets.
+#[derive(Debug, Default)]
+pub struct FetchProgressTracker {
+    buckets: HashMap<String, (FetchStage, f32)>,
+}
+
+impl FetchProgressTracker {
+    pub fn new<I, S>(bucket_names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            buckets: bucket_names
+                .into_iter()
+                .map(|name| (name.into(), (FetchStage::Queued, 0.0)))
+                .collect(),
+        }
+    }
+
+    /// Records progress for one bucket. Returns true when the change is worth
+    /// emitting: a new stage or a different whole percent.
+    pub fn record(&mut self, progress: &BucketFetchProgress) -> bool {
+        let entry = self
+            .buckets
+            .entry(progress.bucket_name.clone())
+            .or_insert((FetchStage::Queued, 0.0));
+        let old_pct = (entry.1 * 100.0).round() as i32;
+        let new_pct = (progress.fraction * 100.0).round() as i32;
+        let changed = entry.0 != progress.stage || old_pct != new_pct;
+        *entry = (progress.stage, progress.fraction);
+        changed
+    }
+
+    /// Mean progress over all tracked buckets (0..=1).
+    pub fn combined(&self) -> f32 {
+        if self.buckets.is_empty() {
+            return 1.0;
+        }
+        let sum: f32 = self.buckets.values().map(|(_, fraction)| fraction).sum();
+        (sum / self.buckets.len() as f32).clamp(0.0, 1.0)
+    }
+
+    /// Number of buckets that finished, successfully or not.
+    pub fn finished(&self) -> usize {
+        self.buckets
+            .values()
+            .filter(|(stage, _)| matches!(stage, FetchStage::Done | FetchStage::Failed))
+            .count()
+    }
+
+    pub fn total(&self) -> usize {
+        self.buckets.len()
+    }
+}
+
+/// Resolves the configured worker count, falling back to a small default
+/// bounded by the machine's parallelism.
+pub fn worker_count(configured: Option<u64>) -> usize {
+    let default = std::thread::available_parallelism()
+        .map(|n| n.get().min(DEFAULT_WORKERS))
+        .unwrap_or(DEFAULT_WORKERS);
+    configured
+        .filter(|workers| *workers > 0)
+        .map(|workers| workers.min(MAX_WORKERS as u64) as usize)
+        .unwrap_or(default)
+}
+
+/// Whether a fetch failed in the network or TLS layer, where trying again
+/// later can succeed. Missing remotes, bad refs and auth failures cannot.
+pub fn is_network_error(error: &git2::Error) -> bool {
+    matches!(error.class(), git2::ErrorClass::Net | git2::ErrorClass::Ssl)
+        || error.code() == git2::ErrorCode::Timeout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfer_progress_splits_receiving_and_indexing() {
+        let receiving = BucketFetchProgress::transfer("main", 50, 0, 100, 4096);
+        assert_eq!(receiving.stage, FetchStage::Receiving);
+        assert!((receiving.fraction - 0.225).abs() < f32::EPSILON);
+
+        let indexing = BucketFetchProgress::transfer("main", 100, 100, 100, 8192);
+        assert_eq!(indexing.stage, FetchStage::Indexing);
+        assert!((indexing.fraction - FETCH_WEIGHT).abs() < f32::EPSILON);
+
+        let empty = BucketFetchProgress::transfer("main", 0, 0, 0, 0);
+        assert_eq!(empty.fraction, 0.0);
+    }
+
+    #[test]
+    fn tracker_averages_buckets_and_throttles_updates() {
+        let mut tracker = FetchProgressTracker::new(["main", "extras"]);
+        assert_eq!(tracker.combined(), 0.0);
+
+        assert!(tracker.record(&BucketFetchProgress::transfer("main", 50, 0, 100, 0)));
+        // Same whole percent and stage: nothing new to emit.
+        assert!(!tracker.record(&BucketFetchProgress::transfer("main", 50, 0, 100, 10)));
+
+        assert!(tracker.record(&BucketFetchProgress::stage("main", FetchStage::Done)));
+        assert!(tracker.record(&BucketFetchProgress::stage("extras", FetchStage::Syncing)));
+        assert!((tracker.combined() - 0.95).abs() < 1e-6);
+        assert_eq!(tracker.finished(), 1);
+
+        assert!(tracker.record(&BucketFetchProgress::stage("extras", FetchStage::Failed)));
+        assert_eq!(tracker.combined(), 1.0);
+        assert_eq!(tracker.finished(), tracker.total());
+    }
+
+    #[test]
+    fn worker_count_is_bounded() {
+        assert_eq!(worker_count(Some(3)), 3);
+        assert_eq!(worker_count(Some(100)), MAX_WORKERS);
+        let default = worker_count(None);
+        assert!((1..=DEFAULT_WORKERS).contains(&default));
+        assert_eq!(worker_count(Some(0)), default);
+    }
+
+    #[test]
+    fn network_errors_are_classified_by_git_class() {
+        use git2::{Error, ErrorClass, ErrorCode};
+
+        let unresolved = Error::new(
+            ErrorCode::GenericError,
+            ErrorClass::Net,
+            "failed to resolve address for github.com: Name or service not known",
+        );
+        let tls = Error::new(
+            ErrorCode::Certificate,
+            ErrorClass::Ssl,
+            "the SSL certificate is invalid",
+        );
+        let timeout = Error::new(ErrorCode::Timeout, ErrorClass::Http, "timed out");
+        assert!(is_network_error(&unresolved));
+        assert!(is_network_error(&tls));
+        assert!(is_network_error(&timeout));
+
+        let missing_ref = Error::new(
+            ErrorCode::NotFound,
+            ErrorClass::Reference,
+            "reference 'refs/remotes/origin/master' not found",
+        );
+        let auth = Error::new(ErrorCode::Auth, ErrorClass::Http, "authentication required");
+        assert!(!is_network_error(&missing_ref));
+        assert!(!is_network_error(&auth));
+    }
+}
@@ -0,0 +1,372 @@
+//! Locally computed health signals for installed buckets.
+//!
+//! External directory buckets carry stars and forks; installed buckets get
+//! these instead: age of the last upstream commit, manifests without a usable
+//! `checkver`, malformed manifests, package names duplicated across buckets
+//! and, when probed over the network, download hosts that no longer resolve.
+use crate::commands::bucket_pin;
+use crate::manifest::{Checkver, Manifest};
+use crate::models::{BucketHealth, DuplicatePackage};
+use crate::utils;
+use chrono::{DateTime, TimeZone, Utc};
+use git2::{BranchType, Repository};
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Buckets without an upstream commit for this long are flagged as stale.
+pub const STALE_AFTER_DAYS: i64 = 180;
+
+/// Per-bucket manifest scan, cached until the bucket changes.
+#[derive(Debug, Clone, Default)]
+struct ManifestScan {
+    names: Vec<String>,
+    unusable_checkvers: usize,
+    malformed: Vec<String>,
+    hosts: BTreeSet<String>,
+}
+
+static SCAN_CACHE: Lazy<Mutex<HashMap<PathBuf, (String, ManifestScan)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Returns false when a manifest has no `checkver` or one Scoop cannot run.
+fn checkver_is_usable(manifest: &Manifest) -> bool {
+    manifest.checkver.as_ref().is_some_and(Checkver::is_usable)
+}
+
+/// Extracts the host of an http(s) or ftp URL, ignoring credentials, port and
+/// Scoop's `#/rename` suffix.
+pub fn url_host(url: &str) -> Option<String> {
+    let (scheme, rest) = url.trim().split_once("://")?;
+    if !matches!(
+        scheme.to_ascii_lowercase().as_str(),
+        "http" | "https" | "ftp"
+    ) {
+        return None;
+    }
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_port = authority.rsplit('@').next()?;
+    let host = if host_port.starts_with('[') {
+        host_port.split(']').next()?.trim_start_matches('[')
+    } else {
+        host_port.split(':').next()?
+    };
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+fn scan_fingerprint(bucket_path: &Path) -> String {
+    let head = Repository::open(bucket_path)
+        .ok()
+        .and_then(|repo| repo.head().ok().and_then(|head| head.target()))
+        .map(|oid| oid.to_string())
+        .unwrap_or_default();
+    let modified = fs::metadata(bucket_path.join("bucket"))
+        .or_else(|_| fs::metadata(bucket_path))
+        .and_then(|meta| meta.modified())
+        .map(|time| format!("{:?}", time))
+        .unwrap_or_default();
+    format!("{}:{}", head, modified)
+}
+
+fn scan_manifests(bucket_path: &Path) -> ManifestScan {
+    let mut scan = ManifestScan::default();
+
+    for path in utils::bucket_manifest_paths(bucket_path) {
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let relative = path
+            .strip_prefix(bucket_path)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let Ok(manifest) = Manifest::from_path(&path) else {
+            scan.malformed.push(relative);
+            continue;
+        };
+
+        scan.names.push(name.to_string());
+        if !checkver_is_usable(&manifest) {
+            scan.unusable_checkvers += 1;
+        }
+        scan.hosts.extend(manifest.urls().filter_map(url_host));
+    }
+
+    scan.malformed.sort();
+    scan
+}
+
+fn cached_scan(bucket_path: &Path) -> ManifestScan {
+    let fingerprint = scan_fingerprint(bucket_path);
+    if let Some((cached_fingerprint, scan)) = SCAN_CACHE.lock().unwrap().get(bucket_path) {
+        if *cached_fingerprint == fingerprint {
+            return scan.clone();
+        }
+    }
+
+    let scan = scan_manifests(bucket_path);
+    SCAN_CACHE
+        .lock()
+        .unwrap()
+        .insert(bucket_path.to_path_buf(), (fingerprint, scan.clone()));
+    scan
+}
+
+/// Commit time of the newest upstream commit the bucket knows about: the
+/// fetched `origin/<branch>` when present, otherwise HEAD.
+pub fn last_upstream_commit(bucket_path: &Path) -> Option<DateTime<Utc>> {
+    let repo = Repository::open(bucket_path).ok()?;
+    let head = repo.head().ok()?;
+
+    let branch = if head.is_branch() {
+        head.shorthand().ok().map(str::to_string)
+    } else {
+        bucket_pin::read_pin(bucket_path).map(|pin| pin.branch)
+    };
+    let upstream = branch.and_then(|branch| {
+        repo.find_branch(&format!("origin/{}", branch), BranchType::Remote)
+            .ok()
+            .and_then(|remote| remote.get().peel_to_commit().ok())
+    });
+    let commit = match upstream {
+        Some(commit) => commit,
+        None => head.peel_to_commit().ok()?,
+    };
+
+    Utc.timestamp_opt(commit.time().seconds(), 0).single()
+}
+
+/// Folds the individual signals into a 0-100 score.
+pub fn health_score(health: &BucketHealth) -> u8 {
+    let mut score = 100.0_f32;
+
+    score -= match health.days_since_last_commit {
+        Some(days) if days > 2 * STALE_AFTER_DAYS => 40.0,
+        Some(days) if days > STALE_AFTER_DAYS => 25.0,
+        Some(days) if days > STALE_AFTER_DAYS / 2 => 10.0,
+        _ => 0.0,
+    };
+    score -= health.unusable_checkver_ratio.clamp(0.0, 1.0) * 30.0;
+    score -= (health.malformed_manifests.len() as f32 * 5.0).min(20.0);
+    if health.manifest_count > 0 {
+        let duplicate_ratio = health.duplicate_packages.len() as f32 / health.manifest_count as f32;
+        score -= (duplicate_ratio * 20.0).min(10.0);
+    }
+    if let Some(dead) = &health.dead_hosts {
+        score -= (dead.len() as f32 * 5.0).min(20.0);
+    }
+
+    score.round().clamp(0.0, 100.0) as u8
+}
+
+/// Computes health for every bucket in `buckets`. Duplicates are resolved
+/// against the same set, so pass all installed buckets even when only one
+/// result is needed.
+pub fn compute_health(buckets: &[(String, PathBuf)], now: DateTime<Utc>) -> Vec<BucketHealth> {
+    let scans: Vec<(ManifestScan, Option<DateTime<Utc>>)> = buckets
+        .par_iter()
+        .map(|(_, path)| (cached_scan(path), last_upstream_commit(path)))
+        .collect();
+
+    let mut owners: HashMap<String, Vec<&str>> = HashMap::new();
+    for ((bucket_name, _), (scan, _)) in buckets.iter().zip(&scans) {
+        for name in &scan.names {
+            owners
+                .entry(name.to_lowercase())
+                .or_default()
+                .push(bucket_name.as_str());
+        }
+    }
+
+    buckets
+        .iter()
+        .zip(scans)
+        .map(|((bucket_name, _), (scan, last_commit))| {
+            let mut duplicate_packages: Vec<DuplicatePackage> = scan
+                .names
+                .iter()
+                .filter_map(|name| {
+                    let mut other_buckets: Vec<String> = owners
+                        .get(&name.to_lowercase())?
+                        .iter()
+                        .filter(|owner| **owner != bucket_name.as_str())
+                        .map(|owner| owner.to_string())
+                        .collect();
+                    if other_buckets.is_empty() {
+                        return None;
+                    }
+                    other_buckets.sort();
+                    other_buckets.dedup();
+                    Some(DuplicatePackage {
+                        name: name.clone(),
+                        other_buckets,
+                    })
+                })
+                .collect();
+            duplicate_packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let manifest_count = scan.names.len() + scan.malformed.len();
+            let days_since_last_commit = last_commit.map(|time| (now - time).num_days().max(0));
+            let mut health = BucketHealth {
+                bucket_name: bucket_name.clone(),
+                score: 0,
+                days_since_last_commit,
+                is_stale: days_since_last_commit
+                    .map(|days| days > STALE_AFTER_DAYS)
+                    .unwrap_or(false),
+                manifest_count,
+                unusable_checkvers: scan.unusable_checkvers,
+                unusable_checkver_ratio: if scan.names.is_empty() {
+                    0.0
+                } else {
+                    scan.unusable_checkvers as f32 / scan.names.len() as f32
+                },
+                malformed_manifests: scan.malformed,
+                duplicate_packages,
+                dead_hosts: None,
+            };
+            health.score = health_score(&health);
+            health
+        })
+        .collect()
+}
+
+/// Lists installed bucket directories as `(name, path)`, sorted by name.
+pub fn installed_buckets(buckets_dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut buckets: Vec<(String, PathBuf)> = fs::read_dir(buckets_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .filter_map(|path| Some((path.file_name()?.to_str()?.to_string(), path)))
+                .collect()
+        })
+        .unwrap_or_default();
+    buckets.sort();
+    buckets
+}
+
+/// Download hosts referenced by a bucket's manifests, for network probing.
+pub fn bucket_hosts(bucket_path: &Path) -> Vec<String> {
+    cached_scan(bucket_path).hosts.into_iter().collect()
+}
+
+/// Records probe results and rescores.
+pub fn apply_dead_hosts(health: &mut BucketHealth, dead_hosts: Vec<String>) {
+    health.dead_hosts = Some(dead_hosts);
+    health.score = health_score(health);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn parses_download_hosts() {
+        assert_eq!(
+            url_host("https://github.com/a/b/releases/x.zip#/dl.7z").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(
+            url_host("http://user:pw@Example.COM:8080/file").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(url_host("https://[::1]:443/x").as_deref(), Some("::1"));
+        assert_eq!(url_host("file:///C:/x.zip"), None);
+        assert_eq!(url_host("not a url"), None);
+    }
+
+    #[test]
+    fn checkver_usability_follows_scoop_shapes() {
+        let usable = |json: serde_json::Value| {
+            checkver_is_usable(&serde_json::from_value::<Manifest>(json).unwrap())
+        };
+        assert!(usable(
+            serde_json::json!({"version": "1.0", "checkver": "github"})
+        ));
+        assert!(usable(
+            serde_json::json!({"version": "1.0", "checkver": {"url": "https://x", "regex": "v([\\d.]+)"}})
+        ));
+        assert!(!usable(serde_json::json!({"version": "1.0"})));
+        assert!(!usable(
+            serde_json::json!({"version": "1.0", "checkver": {"replace": "$1"}})
+        ));
+        assert!(!usable(
+            serde_json::json!({"version": "1.0", "checkver": ""})
+        ));
+    }
+
+    #[test]
+    fn computes_issues_and_duplicates_across_buckets() {
+        let temp = TempDir::new("bucket-health");
+        let main = temp.path.join("main");
+        let extras = temp.path.join("extras");
+        temp.write(
+            "main/bucket/git.json",
+            r#"{"version":"2.0","checkver":"github","url":"https://github.com/git/git.zip"}"#,
+        );
+        temp.write("main/bucket/7zip.json", r#"{"version":"23.01"}"#);
+        temp.write(
+            "extras/bucket/Git.json",
+            r#"{"version":"2.0","checkver":{"github":"https://github.com/x/git"},"architecture":{"64bit":{"url":["https://dl.example.org/git.zip"]}}}"#,
+        );
+        temp.write("extras/bucket/broken.json", "{ not json");
+
+        let buckets = vec![
+            ("extras".to_string(), extras.clone()),
+            ("main".to_string(), main),
+        ];
+        let health = compute_health(&buckets, Utc::now());
+
+        let extras_health = &health[0];
+        assert_eq!(extras_health.bucket_name, "extras");
+        assert_eq!(extras_health.manifest_count, 2);
+        assert_eq!(
+            extras_health.malformed_manifests,
+            vec!["bucket/broken.json"]
+        );
+        assert_eq!(extras_health.unusable_checkvers, 0);
+        assert_eq!(
+            extras_health.duplicate_packages,
+            vec![DuplicatePackage {
+                name: "Git".to_string(),
+                other_buckets: vec!["main".to_string()],
+            }]
+        );
+        // Not a git repository: no commit age, never stale.
+        assert_eq!(extras_health.days_since_last_commit, None);
+        assert!(!extras_health.is_stale);
+        assert_eq!(bucket_hosts(&extras), vec!["dl.example.org".to_string()]);
+
+        let main_health = &health[1];
+        assert_eq!(main_health.unusable_checkvers, 1);
+        assert!((main_health.unusable_checkver_ratio - 0.5).abs() < f32::EPSILON);
+        assert_eq!(main_health.score, 75);
+        assert_eq!(extras_health.score, 85);
+    }
+
+    #[test]
+    fn score_penalises_stale_buckets_and_dead_hosts() {
+        let mut health = BucketHealth {
+            bucket_name: "old".to_string(),
+            days_since_last_commit: Some(3 * STALE_AFTER_DAYS),
+            is_stale: true,
+            manifest_count: 10,
+            ..Default::default()
+        };
+        assert_eq!(health_score(&health), 60);
+
+        apply_dead_hosts(&mut health, vec!["gone.example".to_string()]);
+        assert_eq!(health.score, 55);
+
+        health.days_since_last_commit = Some(1);
+        health.dead_hosts = None;
+        assert_eq!(health_score(&health), 100);
+    }
+}
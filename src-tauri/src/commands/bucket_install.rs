@@ -1,6 +1,7 @@
 use git2::{
     CheckoutNotificationType, Cred, CredentialType, FetchOptions, RemoteCallbacks, Repository,
 };
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -8,13 +9,26 @@ use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc, Mutex, OnceLock,
 };
-use tauri::{command, AppHandle, Manager, Runtime};
+use tauri::{command, AppHandle, Emitter, Manager, Runtime};
 
+use crate::commands::bucket_changes::{self, BucketChangeReport};
+use crate::commands::bucket_fetch::{
+    self, BucketFetchProgress, FetchProgressTracker, FetchStage, EVENT_BUCKET_FETCH_PROGRESS,
+};
+use crate::commands::bucket_pin;
+use crate::commands::bucket_sync::{self, DivergenceStrategy, SyncAction};
+use crate::commands::installed::get_installed_packages_full;
 use crate::commands::search::invalidate_manifest_cache;
+use crate::models::ScoopPackage;
 use crate::operations::{self, OperationKind};
 use crate::state::AppState;
 use crate::utils;
 
+/// Config key holding the default [`DivergenceStrategy`] for bucket updates.
+const STRATEGY_KEY: &str = "buckets.divergenceStrategy";
+/// Config key holding the number of buckets updated concurrently.
+const WORKERS_KEY: &str = "buckets.fetchWorkers";
+
 static BUCKET_INSTALL_CANCEL: OnceLock<Mutex<Option<Arc<AtomicBool>>>> = OnceLock::new();
 
 fn bucket_install_cancel_slot() -> &'static Mutex<Option<Arc<AtomicBool>>> {
@@ -146,6 +160,11 @@ pub struct BucketInstallOptions {
     pub name: String,
     pub url: String,
     pub force: bool, // Force reinstall if bucket already exists
+    /// Shallow clone depth for very large buckets. `None` clones full history.
+    /// libgit2 has no partial-clone (blob filter) support, so depth is the only
+    /// way to trim the download.
+    #[serde(default)]
+    pub depth: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -155,6 +174,17 @@ pub struct BucketInstallResult {
     pub bucket_name: String,
     pub bucket_path: Option<String>,
     pub manifest_count: Option<u32>,
+    /// Manifest-level changes pulled in by an update. Only set when the
+    /// bucket actually moved to a new commit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changes: Option<BucketChangeReport>,
+    /// Set when the bucket is pinned and the update left it in place.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_ref: Option<String>,
+    /// Set when fetching failed in the network or TLS layer, so trying again
+    /// later may succeed.
+    #[serde(skip)]
+    pub network_error: bool,
 }
 
 // Get the buckets directory path
@@ -180,10 +210,16 @@ fn get_bucket_path<R: Runtime>(app: &AppHandle<R>, bucket_name: &str) -> Result<
 fn clone_repository(
     url: &str,
     target_path: &Path,
+    depth: Option<u32>,
     progress_app: Option<AppHandle>,
     cancel_token: Option<Arc<AtomicBool>>,
 ) -> Result<Repository, String> {
-    log::info!("Cloning repository {} to {:?}", url, target_path);
+    log::info!(
+        "Cloning repository {} to {:?} (depth: {:?})",
+        url,
+        target_path,
+        depth
+    );
 
     // Create parent directory if it doesn't exist
     if let Some(parent) = target_path.parent() {
@@ -198,6 +234,9 @@ fn clone_repository(
     // Set up fetch options
     let mut fetch_options = FetchOptions::new();
     fetch_options.remote_callbacks(remote_callbacks);
+    if let Some(depth) = depth.filter(|depth| *depth > 0) {
+        fetch_options.depth(depth.min(i32::MAX as u32) as i32);
+    }
 
     // Clone the repository
     let mut builder = git2::build::RepoBuilder::new();
@@ -268,7 +307,12 @@ async fn install_bucket_internal(
     progress_app: Option<AppHandle>,
     cancel_token: Option<Arc<AtomicBool>>,
 ) -> Result<BucketInstallResult, String> {
-    let BucketInstallOptions { name, url, force } = options;
+    let BucketInstallOptions {
+        name,
+        url,
+        force,
+        depth,
+    } = options;
 
     // Validate and normalize URL
     let normalized_url = utils::validate_and_normalize_url(&url)?;
@@ -295,6 +339,9 @@ async fn install_bucket_internal(
                     .to_string(),
             ),
             manifest_count: None,
+            changes: None,
+            pinned_ref: None,
+            network_error: false,
         });
     }
 
@@ -318,6 +365,7 @@ async fn install_bucket_internal(
         clone_repository(
             &normalized_url_clone,
             &bucket_path_clone,
+            depth,
             progress_app,
             cancel_token_clone,
         )
@@ -348,6 +396,9 @@ async fn install_bucket_internal(
                 bucket_name: bucket_name.clone(),
                 bucket_path: Some(bucket_path.to_string_lossy().to_string()),
                 manifest_count: Some(manifest_count),
+                changes: None,
+                pinned_ref: None,
+                network_error: false,
             })
         }
         Err(e) => {
@@ -409,6 +460,9 @@ pub async fn install_bucket(
                 bucket_name: String::new(),
                 bucket_path: None,
                 manifest_count: None,
+                changes: None,
+                pinned_ref: None,
+                network_error: false,
             }
         }
     };
@@ -423,6 +477,52 @@ pub async fn install_bucket(
     Ok(result)
 }
 
+/// Adds a bucket from inside a queued operation (`EnqueueAction::AddBucket`).
+/// Clone progress streams into the running operation. An existing bucket of
+/// the same name counts as success so a chained install can proceed.
+pub(crate) async fn add_bucket_for_operation(
+    app: &AppHandle,
+    bucket: &str,
+    url: &str,
+) -> Result<(), String> {
+    if bucket_exists(app, bucket)? {
+        operations::append_output(
+            app,
+            format!("Bucket '{}' is already added", bucket),
+            "stdout",
+        );
+        return Ok(());
+    }
+
+    operations::append_output(app, format!("Cloning {}...", url), "stdout");
+    let cancel_token = Arc::new(AtomicBool::new(false));
+    set_bucket_install_cancel_token(Some(cancel_token.clone()));
+
+    let result = install_bucket_internal(
+        app,
+        BucketInstallOptions {
+            name: bucket.to_string(),
+            url: url.to_string(),
+            force: false,
+            depth: None,
+        },
+        Some(app.clone()),
+        Some(cancel_token),
+    )
+    .await;
+    set_bucket_install_cancel_token(None);
+    operations::set_current_phase(app, None);
+    operations::set_progress_fraction(app, None);
+
+    let result = result?;
+    operations::append_output(app, result.message.clone(), "stdout");
+    if result.success {
+        Ok(())
+    } else {
+        Err(result.message)
+    }
+}
+
 // Command to check if a bucket can be installed (validation only)
 #[command]
 pub async fn validate_bucket_install<R: Runtime>(
@@ -442,6 +542,9 @@ pub async fn validate_bucket_install<R: Runtime>(
                 bucket_name: name,
                 bucket_path: None,
                 manifest_count: None,
+                changes: None,
+                pinned_ref: None,
+                network_error: false,
             })
         }
     };
@@ -459,6 +562,9 @@ pub async fn validate_bucket_install<R: Runtime>(
                 bucket_name: name,
                 bucket_path: None,
                 manifest_count: None,
+                changes: None,
+                pinned_ref: None,
+                network_error: false,
             })
         }
     };
@@ -487,219 +593,385 @@ pub async fn validate_bucket_install<R: Runtime>(
         bucket_name,
         bucket_path,
         manifest_count: None,
+        changes: None,
+        pinned_ref: None,
+        network_error: false,
     })
 }
 
+/// Loads the installed package list used to cross-reference bucket change
+/// reports. A failed scan only costs the report its installed-update section.
+async fn installed_packages_for_report(app: &AppHandle) -> Vec<ScoopPackage> {
+    match get_installed_packages_full(app.clone(), app.state::<AppState>()).await {
+        Ok(packages) => packages,
+        Err(e) => {
+            log::warn!(
+                "Could not load installed packages for bucket change report: {}",
+                e
+            );
+            Vec::new()
+        }
+    }
+}
+
 // Command to update a bucket (git pull)
 #[command]
 pub async fn update_bucket(
     app: AppHandle,
     bucket_name: String,
+    strategy: Option<DivergenceStrategy>,
 ) -> Result<BucketInstallResult, String> {
-    log::info!("Updating bucket: {}", bucket_name);
-    let bucket_path = get_bucket_path(&app, &bucket_name)?;
+    let strategy = strategy.unwrap_or_else(|| configured_divergence_strategy(&app));
+    let installed = installed_packages_for_report(&app).await;
+    update_bucket_with_installed(&app, bucket_name, installed, strategy).await
+}
+
+/// Reads the divergence strategy used when the caller does not pick one.
+fn configured_divergence_strategy(app: &AppHandle) -> DivergenceStrategy {
+    crate::commands::settings::get_config_value(app.clone(), STRATEGY_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+async fn update_bucket_with_installed(
+    app: &AppHandle,
+    bucket_name: String,
+    installed: Vec<ScoopPackage>,
+    strategy: DivergenceStrategy,
+) -> Result<BucketInstallResult, String> {
+    let bucket_path = get_bucket_path(app, &bucket_name)?;
+
+    tokio::task::spawn_blocking(move || {
+        update_bucket_blocking(&bucket_name, &bucket_path, &installed, strategy, None)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn update_bucket_blocking(
+    bucket_name: &str,
+    bucket_path: &Path,
+    installed: &[ScoopPackage],
+    strategy: DivergenceStrategy,
+    progress: Option<&Arc<FetchReporter>>,
+) -> Result<BucketInstallResult, String> {
+    log::info!("Updating bucket: {} ({:?})", bucket_name, strategy);
 
     if !bucket_path.exists() {
         return Ok(BucketInstallResult {
             success: false,
             message: format!("Bucket '{}' does not exist", bucket_name),
-            bucket_name,
+            bucket_name: bucket_name.to_string(),
             bucket_path: None,
             manifest_count: None,
+            changes: None,
+            pinned_ref: None,
+            network_error: false,
         });
     }
 
     // Check if it's a git repository
     if !bucket_path.join(".git").exists() {
-        return Ok(BucketInstallResult {
-            success: false,
-            message: format!(
+        return Ok(failed_result(
+            bucket_name,
+            bucket_path,
+            format!(
                 "Bucket '{}' is not a git repository and cannot be updated",
                 bucket_name
             ),
-            bucket_name,
-            bucket_path: Some(bucket_path.to_string_lossy().to_string()),
-            manifest_count: None,
+        ));
+    }
+
+    update_bucket_sync(bucket_name, bucket_path, installed, strategy, progress)
+}
+
+/// Forwards per-bucket fetch progress to the frontend and folds it into the
+/// active operation's progress bar.
+struct FetchReporter {
+    app: AppHandle,
+    tracker: Mutex<FetchProgressTracker>,
+}
+
+impl FetchReporter {
+    fn report(&self, progress: BucketFetchProgress) {
+        let summary = {
+            let mut tracker = self.tracker.lock().unwrap();
+            tracker
+                .record(&progress)
+                .then(|| (tracker.combined(), tracker.finished(), tracker.total()))
+        };
+        let Some((combined, finished, total)) = summary else {
+            return;
+        };
+
+        let _ = self.app.emit(EVENT_BUCKET_FETCH_PROGRESS, &progress);
+        operations::set_progress_fraction(&self.app, Some(combined));
+        if matches!(progress.stage, FetchStage::Done | FetchStage::Failed) {
+            operations::update_top_phase(
+                &self.app,
+                format!("Updating buckets ({}/{})", finished, total),
+            );
+        }
+    }
+}
+
+fn failed_result(bucket_name: &str, bucket_path: &Path, message: String) -> BucketInstallResult {
+    BucketInstallResult {
+        success: false,
+        message,
+        bucket_name: bucket_name.to_string(),
+        bucket_path: Some(bucket_path.to_string_lossy().to_string()),
+        manifest_count: None,
+        changes: None,
+        pinned_ref: None,
+        network_error: false,
+    }
+}
+
+/// A failed fetch of a bucket's `origin`.
+struct FetchFailure {
+    message: String,
+    network: bool,
+}
+
+impl From<FetchFailure> for String {
+    fn from(failure: FetchFailure) -> Self {
+        failure.message
+    }
+}
+
+/// Fetches `origin` into an open bucket repository. Shallow buckets stay shallow.
+fn fetch_origin(
+    repo: &Repository,
+    bucket_name: &str,
+    progress: Option<&Arc<FetchReporter>>,
+) -> Result<(), FetchFailure> {
+    let mut remote = repo.find_remote("origin").map_err(|_| FetchFailure {
+        message: format!("Bucket '{}' has no origin remote", bucket_name),
+        network: false,
+    })?;
+
+    let mut callbacks = create_remote_callbacks();
+    if let Some(reporter) = progress.cloned() {
+        let name = bucket_name.to_string();
+        callbacks.transfer_progress(move |stats| {
+            reporter.report(BucketFetchProgress::transfer(
+                &name,
+                stats.received_objects(),
+                stats.indexed_objects(),
+                stats.total_objects(),
+                stats.received_bytes(),
+            ));
+            true
         });
     }
 
-    let bucket_name_clone = bucket_name.clone();
-    let bucket_path_clone = bucket_path.clone();
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+    if let Some(depth) = bucket_sync::fetch_depth(repo) {
+        fetch_options.depth(depth);
+    }
 
-    tokio::task::spawn_blocking(move || update_bucket_sync(&bucket_name_clone, &bucket_path_clone))
-        .await
-        .map_err(|e| e.to_string())?
+    remote
+        .fetch(&[] as &[&str], Some(&mut fetch_options), None)
+        .map_err(|e| FetchFailure {
+            message: format!(
+                "Failed to fetch updates for bucket '{}': {}",
+                bucket_name, e
+            ),
+            network: bucket_fetch::is_network_error(&e),
+        })
 }
 
 fn update_bucket_sync(
     bucket_name: &str,
     bucket_path: &Path,
+    installed: &[ScoopPackage],
+    strategy: DivergenceStrategy,
+    progress: Option<&Arc<FetchReporter>>,
 ) -> Result<BucketInstallResult, String> {
-    // Try to update the repository using git2
-    match Repository::open(bucket_path) {
-        Ok(repo) => {
-            // Fetch from origin
-            let mut remote = match repo.find_remote("origin") {
-                Ok(remote) => remote,
-                Err(_) => {
-                    return Ok(BucketInstallResult {
-                        success: false,
-                        message: format!("Bucket '{}' has no origin remote", bucket_name),
-                        bucket_name: bucket_name.to_string(),
-                        bucket_path: Some(bucket_path.to_string_lossy().to_string()),
-                        manifest_count: None,
-                    });
-                }
-            };
+    let mut repo = match Repository::open(bucket_path) {
+        Ok(repo) => repo,
+        Err(e) => {
+            return Ok(failed_result(
+                bucket_name,
+                bucket_path,
+                format!(
+                    "Failed to open bucket '{}' as git repository: {}. Try repairing the bucket.",
+                    bucket_name, e
+                ),
+            ))
+        }
+    };
 
-            let callbacks = create_remote_callbacks();
-
-            let mut fetch_options = FetchOptions::new();
-            fetch_options.remote_callbacks(callbacks);
-
-            // Fetch latest changes
-            match remote.fetch(&[] as &[&str], Some(&mut fetch_options), None) {
-                Ok(_) => {
-                    // Get current branch
-                    let head = match repo.head() {
-                        Ok(head) => head,
-                        Err(_) => {
-                            return Ok(BucketInstallResult {
-                                success: false,
-                                message: format!(
-                                    "Could not get current branch for bucket '{}'",
-                                    bucket_name
-                                ),
-                                bucket_name: bucket_name.to_string(),
-                                bucket_path: Some(bucket_path.to_string_lossy().to_string()),
-                                manifest_count: None,
-                            });
-                        }
-                    };
+    if let Err(failure) = fetch_origin(&repo, bucket_name, progress) {
+        return Ok(BucketInstallResult {
+            network_error: failure.network,
+            ..failed_result(bucket_name, bucket_path, failure.message)
+        });
+    }
+    if let Some(reporter) = progress {
+        reporter.report(BucketFetchProgress::stage(bucket_name, FetchStage::Syncing));
+    }
 
-                    if let Ok(branch_name) = head.shorthand() {
-                        // Try to merge origin/branch into current branch
-                        let remote_branch_name = format!("origin/{}", branch_name);
-                        match repo.find_branch(&remote_branch_name, git2::BranchType::Remote) {
-                            Ok(remote_branch) => {
-                                let remote_commit =
-                                    remote_branch.get().peel_to_commit().map_err(|e| {
-                                        format!(
-                                            "Failed to resolve remote commit for bucket '{}': {}",
-                                            bucket_name, e
-                                        )
-                                    })?;
-                                let local_commit = head.peel_to_commit().map_err(|e| {
-                                    format!(
-                                        "Failed to resolve local commit for bucket '{}': {}",
-                                        bucket_name, e
-                                    )
-                                })?;
-
-                                // Check if update is needed
-                                if remote_commit.id() == local_commit.id() {
-                                    let manifest_count = utils::count_manifests(bucket_path);
-                                    return Ok(BucketInstallResult {
-                                        success: true,
-                                        message: format!(
-                                            "Bucket '{}' is already up to date",
-                                            bucket_name
-                                        ),
-                                        bucket_name: bucket_name.to_string(),
-                                        bucket_path: Some(
-                                            bucket_path.to_string_lossy().to_string(),
-                                        ),
-                                        manifest_count: Some(manifest_count),
-                                    });
-                                }
-
-                                // Perform fast-forward merge
-                                let mut checkout_builder = git2::build::CheckoutBuilder::new();
-                                checkout_builder.force();
-
-                                repo.reset(
-                                    remote_commit.as_object(),
-                                    git2::ResetType::Hard,
-                                    Some(&mut checkout_builder),
-                                )
-                                .map_err(|e| {
-                                    format!("Failed to update bucket '{}': {}", bucket_name, e)
-                                })?;
-
-                                let manifest_count = utils::count_manifests(bucket_path);
-
-                                log::info!(
-                                    "Successfully updated bucket '{}' with {} manifests",
-                                    bucket_name,
-                                    manifest_count
-                                );
-
-                                Ok(BucketInstallResult {
-                                    success: true,
-                                    message: format!(
-                                        "Successfully updated bucket '{}' with {} manifests",
-                                        bucket_name, manifest_count
-                                    ),
-                                    bucket_name: bucket_name.to_string(),
-                                    bucket_path: Some(bucket_path.to_string_lossy().to_string()),
-                                    manifest_count: Some(manifest_count),
-                                })
-                            }
-                            Err(_) => Ok(BucketInstallResult {
-                                success: false,
-                                message: format!(
-                                    "Could not find remote branch for bucket '{}'",
-                                    bucket_name
-                                ),
-                                bucket_name: bucket_name.to_string(),
-                                bucket_path: Some(bucket_path.to_string_lossy().to_string()),
-                                manifest_count: None,
-                            }),
-                        }
-                    } else {
-                        Ok(BucketInstallResult {
-                            success: false,
-                            message: format!(
-                                "Could not determine current branch for bucket '{}'",
-                                bucket_name
-                            ),
-                            bucket_name: bucket_name.to_string(),
-                            bucket_path: Some(bucket_path.to_string_lossy().to_string()),
-                            manifest_count: None,
-                        })
-                    }
-                }
-                Err(e) => Ok(BucketInstallResult {
-                    success: false,
-                    message: format!(
-                        "Failed to fetch updates for bucket '{}': {}",
-                        bucket_name, e
+    // Pinned buckets only refresh their remote refs so the behind count stays
+    // accurate. Moving them requires an explicit `pin_bucket` call.
+    if let Some(pin) = bucket_pin::read_pin(bucket_path) {
+        let behind = bucket_pin::commits_behind(&repo, &pin)
+            .map(|count| format!("{} commits behind origin/{}", count, pin.branch))
+            .unwrap_or_else(|| format!("origin/{} unavailable", pin.branch));
+        let message = format!(
+            "Bucket '{}' is pinned to '{}' ({}); skipped update",
+            bucket_name, pin.reference, behind
+        );
+        log::info!("{}", message);
+        return Ok(BucketInstallResult {
+            success: true,
+            message,
+            bucket_name: bucket_name.to_string(),
+            bucket_path: Some(bucket_path.to_string_lossy().to_string()),
+            manifest_count: Some(utils::count_manifests(bucket_path)),
+            changes: None,
+            pinned_ref: Some(pin.reference),
+            network_error: false,
+        });
+    }
+
+    let branch_name = match repo.head() {
+        Ok(head) => match head.shorthand() {
+            Ok(name) => name.to_string(),
+            Err(_) => {
+                return Ok(failed_result(
+                    bucket_name,
+                    bucket_path,
+                    format!(
+                        "Could not determine current branch for bucket '{}'",
+                        bucket_name
                     ),
-                    bucket_name: bucket_name.to_string(),
-                    bucket_path: Some(bucket_path.to_string_lossy().to_string()),
-                    manifest_count: None,
-                }),
+                ))
             }
+        },
+        Err(_) => {
+            return Ok(failed_result(
+                bucket_name,
+                bucket_path,
+                format!("Could not get current branch for bucket '{}'", bucket_name),
+            ))
         }
-        Err(e) => Ok(BucketInstallResult {
-            success: false,
-            message: format!(
-                "Failed to open bucket '{}' as git repository: {}",
-                bucket_name, e
-            ),
-            bucket_name: bucket_name.to_string(),
-            bucket_path: Some(bucket_path.to_string_lossy().to_string()),
-            manifest_count: None,
-        }),
+    };
+
+    let outcome = match bucket_sync::sync_to_upstream(&mut repo, &branch_name, strategy) {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            return Ok(failed_result(
+                bucket_name,
+                bucket_path,
+                format!("Failed to update bucket '{}': {}", bucket_name, e),
+            ))
+        }
+    };
+
+    if let SyncAction::Blocked { .. } = outcome.action {
+        let message = outcome.describe(bucket_name, &branch_name);
+        log::warn!("{}", message);
+        return Ok(failed_result(bucket_name, bucket_path, message));
     }
+
+    let manifest_count = utils::count_manifests(bucket_path);
+    let message = match outcome.action {
+        SyncAction::UpToDate => outcome.describe(bucket_name, &branch_name),
+        SyncAction::FastForward if !outcome.stash_conflict => format!(
+            "Successfully updated bucket '{}' with {} manifests",
+            bucket_name, manifest_count
+        ),
+        _ => format!(
+            "{} ({} manifests)",
+            outcome.describe(bucket_name, &branch_name),
+            manifest_count
+        ),
+    };
+    let changes = outcome.moved().then(|| {
+        build_change_report(
+            &repo,
+            bucket_name,
+            outcome.old_head,
+            outcome.new_head,
+            installed,
+        )
+    });
+
+    log::info!("{}", message);
+
+    Ok(BucketInstallResult {
+        success: true,
+        message,
+        bucket_name: bucket_name.to_string(),
+        bucket_path: Some(bucket_path.to_string_lossy().to_string()),
+        manifest_count: Some(manifest_count),
+        changes: changes.flatten(),
+        pinned_ref: None,
+        network_error: false,
+    })
 }
 
-/// Command to update all buckets sequentially.
-/// Returns a list of per-bucket results. Non-fatal errors are captured in each result.
+/// Diffs the old and new bucket heads and marks version bumps that affect
+/// installed packages. Failures are logged; the update itself already succeeded.
+fn build_change_report(
+    repo: &Repository,
+    bucket_name: &str,
+    old_commit: git2::Oid,
+    new_commit: git2::Oid,
+    installed: &[ScoopPackage],
+) -> Option<BucketChangeReport> {
+    match bucket_changes::diff_bucket_revisions(repo, bucket_name, old_commit, new_commit) {
+        Ok(mut report) => {
+            bucket_changes::match_installed_updates(&mut report, installed);
+            log::info!(
+                "Bucket '{}' changes: {} added, {} removed, {} changed, {} installed updates",
+                bucket_name,
+                report.added.len(),
+                report.removed.len(),
+                report.changed.len(),
+                report.installed_updates.len()
+            );
+            Some(report)
+        }
+        Err(e) => {
+            log::warn!(
+                "Could not build change report for bucket '{}': {}",
+                bucket_name,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Command to update all buckets, fetching several at once.
+/// Returns a list of per-bucket results. Non-fatal errors are captured in each result,
+/// and buckets that moved carry their own change report.
 #[command]
 pub async fn update_all_buckets(app: AppHandle) -> Result<Vec<BucketInstallResult>, String> {
     log::info!("Updating all buckets (auto-update task)");
-    let buckets_dir = match get_buckets_dir(&app) {
+    update_buckets_matching(&app, |_| true).await
+}
+
+/// Updates only the named buckets, e.g. to retry the ones whose fetch failed.
+pub async fn update_named_buckets(
+    app: AppHandle,
+    names: &[String],
+) -> Result<Vec<BucketInstallResult>, String> {
+    log::info!("Updating buckets: {}", names.join(", "));
+    update_buckets_matching(&app, |name| names.iter().any(|n| n == name)).await
+}
+
+async fn update_buckets_matching(
+    app: &AppHandle,
+    include: impl Fn(&str) -> bool,
+) -> Result<Vec<BucketInstallResult>, String> {
+    let buckets_dir = match get_buckets_dir(app) {
         Ok(p) => p,
         Err(e) => return Err(format!("Failed to resolve buckets directory: {}", e)),
     };
@@ -712,34 +984,352 @@ pub async fn update_all_buckets(app: AppHandle) -> Result<Vec<BucketInstallResul
         return Ok(vec![]);
     }
 
-    let mut results = Vec::new();
+    let installed = installed_packages_for_report(app).await;
+    let strategy = configured_divergence_strategy(app);
+    let workers = bucket_fetch::worker_count(
+        crate::commands::settings::get_config_value(app.clone(), WORKERS_KEY.to_string())
+            .ok()
+            .flatten()
+            .and_then(|value| value.as_u64()),
+    );
 
     let entries = match fs::read_dir(&buckets_dir) {
         Ok(e) => e,
         Err(e) => return Err(format!("Failed to read buckets directory: {}", e)),
     };
 
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if !path.is_dir() {
-            continue;
+    let mut buckets = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.to_string();
+            include(&name).then_some((name, path))
+        })
+        .collect::<Vec<_>>();
+    buckets.sort();
+
+    log::info!(
+        "Updating {} buckets with {} workers",
+        buckets.len(),
+        workers
+    );
+
+    let reporter = Arc::new(FetchReporter {
+        app: app.clone(),
+        tracker: Mutex::new(FetchProgressTracker::new(
+            buckets.iter().map(|(name, _)| name.clone()),
+        )),
+    });
+    operations::push_phase(app, format!("Updating buckets (0/{})", buckets.len()));
+    operations::set_progress_fraction(app, Some(0.0));
+
+    let results = tokio::task::spawn_blocking(move || {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(workers)
+            .build()
+            .map_err(|e| format!("Failed to start bucket update workers: {}", e))?;
+
+        Ok::<_, String>(pool.install(|| {
+            buckets
+                .par_iter()
+                .map(|(name, path)| {
+                    let result =
+                        update_bucket_blocking(name, path, &installed, strategy, Some(&reporter))
+                            .unwrap_or_else(|e| failed_result(name, path, e));
+                    let stage = if result.success {
+                        FetchStage::Done
+                    } else {
+                        FetchStage::Failed
+                    };
+                    reporter.report(BucketFetchProgress::stage(name, stage));
+                    result
+                })
+                .collect::<Vec<_>>()
+        }))
+    })
+    .await
+    .map_err(|e| e.to_string());
+
+    operations::pop_phase(app);
+    operations::set_progress_fraction(app, None);
+
+    let results = results??;
+    log::info!("Completed updating {} buckets", results.len());
+    Ok(results)
+}
+
+/// Command to move a bucket onto another upstream branch (e.g. `develop`).
+#[command]
+pub async fn switch_bucket_branch(
+    app: AppHandle,
+    bucket_name: String,
+    branch: String,
+) -> Result<BucketInstallResult, String> {
+    log::info!("Switching bucket '{}' to branch '{}'", bucket_name, branch);
+    let bucket_path = get_bucket_path(&app, &bucket_name)?;
+
+    if !bucket_path.join(".git").exists() {
+        return Ok(failed_result(
+            &bucket_name,
+            &bucket_path,
+            format!("Bucket '{}' is not a git repository", bucket_name),
+        ));
+    }
+
+    if let Some(pin) = bucket_pin::read_pin(&bucket_path) {
+        return Ok(failed_result(
+            &bucket_name,
+            &bucket_path,
+            format!(
+                "Bucket '{}' is pinned to '{}'; unpin it before switching branches",
+                bucket_name, pin.reference
+            ),
+        ));
+    }
+
+    let name = bucket_name.clone();
+    let path = bucket_path.clone();
+    let target_branch = branch.clone();
+    let switched = tokio::task::spawn_blocking(move || -> Result<(), String> {
+        let repo = Repository::open(&path)
+            .map_err(|e| format!("Failed to open bucket '{}': {}", name, e))?;
+        fetch_origin(&repo, &name, None)?;
+        bucket_sync::switch_branch(&repo, &target_branch)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to switch bucket '{}': {}", name, e))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if let Err(message) = switched {
+        log::warn!("{}", message);
+        return Ok(failed_result(&bucket_name, &bucket_path, message));
+    }
+
+    invalidate_manifest_cache(&app.state::<AppState>().scoop_path()).await;
+    let manifest_count = utils::count_manifests(&bucket_path);
+
+    Ok(BucketInstallResult {
+        success: true,
+        message: format!(
+            "Switched bucket '{}' to branch '{}' with {} manifests",
+            bucket_name, branch, manifest_count
+        ),
+        bucket_name,
+        bucket_path: Some(bucket_path.to_string_lossy().to_string()),
+        manifest_count: Some(manifest_count),
+        changes: None,
+        pinned_ref: None,
+        network_error: false,
+    })
+}
+
+/// Command to pin a bucket to a tag, commit or `origin/<branch>`. Pinning an
+/// already pinned bucket moves it to the new ref.
+#[command]
+pub async fn pin_bucket(
+    app: AppHandle,
+    bucket_name: String,
+    reference: String,
+) -> Result<BucketInstallResult, String> {
+    log::info!("Pinning bucket '{}' to '{}'", bucket_name, reference);
+    let bucket_path = get_bucket_path(&app, &bucket_name)?;
+
+    if !bucket_path.join(".git").exists() {
+        return Ok(failed_result(
+            &bucket_name,
+            &bucket_path,
+            format!("Bucket '{}' is not a git repository", bucket_name),
+        ));
+    }
+
+    let name = bucket_name.clone();
+    let path = bucket_path.clone();
+    let target = reference.clone();
+    let pinned = tokio::task::spawn_blocking(move || {
+        let repo = Repository::open(&path)
+            .map_err(|e| format!("Failed to open bucket '{}': {}", name, e))?;
+        // Fetch first so new tags and commits can be pinned, but a pinned
+        // bucket can still be re-pinned offline to refs it already has.
+        if let Err(failure) = fetch_origin(&repo, &name, None) {
+            log::warn!("{}", failure.message);
         }
-        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-            match update_bucket(app.clone(), name.to_string()).await {
-                Ok(res) => results.push(res),
-                Err(e) => results.push(BucketInstallResult {
-                    success: false,
-                    message: e,
-                    bucket_name: name.to_string(),
-                    bucket_path: Some(path.to_string_lossy().to_string()),
-                    manifest_count: None,
-                }),
-            }
+        bucket_pin::pin_to_ref(&repo, &target)
+            .map_err(|e| format!("Failed to pin bucket '{}': {}", name, e))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    match pinned {
+        Ok(pin) => {
+            invalidate_manifest_cache(&app.state::<AppState>().scoop_path()).await;
+            let manifest_count = utils::count_manifests(&bucket_path);
+            Ok(BucketInstallResult {
+                success: true,
+                message: format!(
+                    "Pinned bucket '{}' to '{}' ({})",
+                    bucket_name,
+                    pin.reference,
+                    &pin.commit[..pin.commit.len().min(7)]
+                ),
+                bucket_name,
+                bucket_path: Some(bucket_path.to_string_lossy().to_string()),
+                manifest_count: Some(manifest_count),
+                changes: None,
+                pinned_ref: Some(pin.reference),
+                network_error: false,
+            })
+        }
+        Err(message) => {
+            log::warn!("{}", message);
+            Ok(failed_result(&bucket_name, &bucket_path, message))
         }
     }
+}
 
-    log::info!("Completed updating {} buckets", results.len());
-    Ok(results)
+/// Command to release a pinned bucket back to its branch. The next update
+/// brings it up to date.
+#[command]
+pub async fn unpin_bucket(
+    app: AppHandle,
+    bucket_name: String,
+) -> Result<BucketInstallResult, String> {
+    log::info!("Unpinning bucket: {}", bucket_name);
+    let bucket_path = get_bucket_path(&app, &bucket_name)?;
+
+    let name = bucket_name.clone();
+    let path = bucket_path.clone();
+    let unpinned = tokio::task::spawn_blocking(move || {
+        let repo = Repository::open(&path)
+            .map_err(|e| format!("Failed to open bucket '{}': {}", name, e))?;
+        bucket_pin::unpin(&repo).map_err(|e| format!("Failed to unpin bucket '{}': {}", name, e))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let message = match unpinned {
+        Ok(Some(pin)) => {
+            invalidate_manifest_cache(&app.state::<AppState>().scoop_path()).await;
+            format!(
+                "Unpinned bucket '{}'; it follows '{}' again",
+                bucket_name, pin.branch
+            )
+        }
+        Ok(None) => format!("Bucket '{}' is not pinned", bucket_name),
+        Err(message) => {
+            log::warn!("{}", message);
+            return Ok(failed_result(&bucket_name, &bucket_path, message));
+        }
+    };
+
+    Ok(BucketInstallResult {
+        success: true,
+        message,
+        bucket_name,
+        bucket_path: Some(bucket_path.to_string_lossy().to_string()),
+        manifest_count: Some(utils::count_manifests(&bucket_path)),
+        changes: None,
+        pinned_ref: None,
+        network_error: false,
+    })
+}
+
+/// Command to re-clone a bucket whose `.git` directory is corrupt. Manifests
+/// that only exist locally are copied into the fresh clone.
+#[command]
+pub async fn repair_bucket(
+    app: AppHandle,
+    bucket_name: String,
+) -> Result<BucketInstallResult, String> {
+    log::info!("Repairing bucket: {}", bucket_name);
+    let bucket_path = get_bucket_path(&app, &bucket_name)?;
+
+    if !bucket_path.exists() {
+        return Ok(BucketInstallResult {
+            success: false,
+            message: format!("Bucket '{}' does not exist", bucket_name),
+            bucket_name,
+            bucket_path: None,
+            manifest_count: None,
+            changes: None,
+            pinned_ref: None,
+            network_error: false,
+        });
+    }
+
+    if bucket_sync::is_repository_healthy(&bucket_path) {
+        return Ok(BucketInstallResult {
+            success: true,
+            message: format!("Bucket '{}' repository is healthy", bucket_name),
+            bucket_name,
+            bucket_path: Some(bucket_path.to_string_lossy().to_string()),
+            manifest_count: Some(utils::count_manifests(&bucket_path)),
+            changes: None,
+            pinned_ref: None,
+            network_error: false,
+        });
+    }
+
+    let Some(url) = bucket_sync::origin_url(&bucket_path) else {
+        return Ok(failed_result(
+            &bucket_name,
+            &bucket_path,
+            format!(
+                "Cannot repair bucket '{}': its origin URL is unknown. Remove and re-add it instead.",
+                bucket_name
+            ),
+        ));
+    };
+
+    let path = bucket_path.clone();
+    let recovered = tokio::task::spawn_blocking(move || {
+        bucket_sync::recover_bucket(&path, &url, |url, staging| {
+            clone_repository(url, staging, None, None, None).map(|_| ())
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    match recovered {
+        Ok(preserved) => {
+            invalidate_manifest_cache(&app.state::<AppState>().scoop_path()).await;
+            let manifest_count = utils::count_manifests(&bucket_path);
+            let message = if preserved.is_empty() {
+                format!(
+                    "Re-cloned bucket '{}' with {} manifests",
+                    bucket_name, manifest_count
+                )
+            } else {
+                format!(
+                    "Re-cloned bucket '{}' with {} manifests, keeping local manifests: {}",
+                    bucket_name,
+                    manifest_count,
+                    preserved.join(", ")
+                )
+            };
+            log::info!("{}", message);
+
+            Ok(BucketInstallResult {
+                success: true,
+                message,
+                bucket_name,
+                bucket_path: Some(bucket_path.to_string_lossy().to_string()),
+                manifest_count: Some(manifest_count),
+                changes: None,
+                pinned_ref: None,
+                network_error: false,
+            })
+        }
+        Err(e) => {
+            log::error!("Failed to repair bucket '{}': {}", bucket_name, e);
+            Ok(failed_result(
+                &bucket_name,
+                &bucket_path,
+                format!("Failed to repair bucket '{}': {}", bucket_name, e),
+            ))
+        }
+    }
 }
 
 // Command to remove a bucket
@@ -759,6 +1349,9 @@ pub async fn remove_bucket<R: Runtime>(
             bucket_name,
             bucket_path: None,
             manifest_count: None,
+            changes: None,
+            pinned_ref: None,
+            network_error: false,
         });
     }
 
@@ -774,6 +1367,9 @@ pub async fn remove_bucket<R: Runtime>(
                 bucket_name,
                 bucket_path: None,
                 manifest_count: None,
+                changes: None,
+                pinned_ref: None,
+                network_error: false,
             })
         }
         Err(e) => {
@@ -784,6 +1380,9 @@ pub async fn remove_bucket<R: Runtime>(
                 bucket_name,
                 bucket_path: Some(bucket_path.to_string_lossy().to_string()),
                 manifest_count: None,
+                changes: None,
+                pinned_ref: None,
+                network_error: false,
             })
         }
     }
@@ -3,11 +3,12 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
 use super::bucket_search::SearchableBucket;
+use super::bucket_sources::{self, DirectorySource, SourceCacheEntry, SourceKind};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BucketFilterOptions {
@@ -24,12 +25,27 @@ impl Default for BucketFilterOptions {
     }
 }
 
-// Global HashMap to cache parsed buckets
-static BUCKET_CACHE: Lazy<tokio::sync::RwLock<HashMap<String, SearchableBucket>>> =
-    Lazy::new(|| tokio::sync::RwLock::new(HashMap::new()));
+/// Merged, unfiltered buckets together with the sources they came from.
+struct CachedDirectory {
+    sources: Vec<DirectorySource>,
+    entries: HashMap<String, SourceCacheEntry>,
+    buckets: HashMap<String, SearchableBucket>,
+}
+
+// Global cache of the merged directory
+static BUCKET_CACHE: Lazy<tokio::sync::RwLock<Option<CachedDirectory>>> =
+    Lazy::new(|| tokio::sync::RwLock::new(None));
+
+/// Page size used when paging through a ScoopSearch index.
+const SCOOP_SEARCH_PAGE_SIZE: usize = 1000;
+/// Upper bound on ScoopSearch pages so a misbehaving endpoint cannot loop forever.
+const SCOOP_SEARCH_MAX_PAGES: usize = 100;
+/// Cache file written by versions that only knew the markdown directory.
+const LEGACY_CACHE_FILE: &str = "bucket_cache.csv";
+const SOURCE_INDEX_FILE: &str = "bucket_sources.json";
 
-// Get the cache file path in the app data directory
-fn get_cache_file_path() -> Result<PathBuf, String> {
+// Get the cache directory in the app data directory
+fn get_cache_dir() -> Result<PathBuf, String> {
     let app_data_dir = dirs::data_dir()
         .ok_or("Failed to get app data directory")?
         .join("rscoop")
@@ -38,22 +54,21 @@ fn get_cache_file_path() -> Result<PathBuf, String> {
     std::fs::create_dir_all(&app_data_dir)
         .map_err(|e| format!("Failed to create cache directory: {}", e))?;
 
-    Ok(app_data_dir.join("bucket_cache.csv"))
+    Ok(app_data_dir)
 }
 
-// Save bucket cache to disk
-async fn save_cache_to_disk(buckets: &HashMap<String, SearchableBucket>) -> Result<(), String> {
-    let cache_file = get_cache_file_path()?;
+fn source_cache_file(cache_dir: &Path, source: &DirectorySource) -> PathBuf {
+    cache_dir.join(format!("bucket_cache_{}.csv", source.cache_key()))
+}
 
+// Save one source's buckets to disk
+async fn save_cache_to_disk(cache_file: &Path, buckets: &[SearchableBucket]) -> Result<(), String> {
     log::info!(
         "Saving {} buckets to cache file: {:?}",
         buckets.len(),
         cache_file
     );
 
-    // Convert HashMap to Vec for CSV serialization
-    let bucket_list: Vec<&SearchableBucket> = buckets.values().collect();
-
     // Create CSV writer
     let mut csv_data = Vec::new();
     {
@@ -61,7 +76,7 @@ async fn save_cache_to_disk(buckets: &HashMap<String, SearchableBucket>) -> Resu
             .has_headers(true)
             .from_writer(&mut csv_data);
 
-        for bucket in &bucket_list {
+        for bucket in buckets {
             writer
                 .serialize(bucket)
                 .map_err(|e| format!("Failed to serialize bucket to CSV: {}", e))?;
@@ -72,7 +87,7 @@ async fn save_cache_to_disk(buckets: &HashMap<String, SearchableBucket>) -> Resu
             .map_err(|e| format!("Failed to flush CSV writer: {}", e))?;
     }
 
-    let mut file = fs::File::create(&cache_file)
+    let mut file = fs::File::create(cache_file)
         .await
         .map_err(|e| format!("Failed to create cache file: {}", e))?;
 
@@ -84,113 +99,252 @@ async fn save_cache_to_disk(buckets: &HashMap<String, SearchableBucket>) -> Resu
         .await
         .map_err(|e| format!("Failed to flush cache file: {}", e))?;
 
-    // Calculate and log file size
-    let metadata = fs::metadata(&cache_file)
-        .await
-        .map_err(|e| format!("Failed to get cache file metadata: {}", e))?;
-
-    let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
+    let size_mb = csv_data.len() as f64 / (1024.0 * 1024.0);
     log::info!("Cache saved successfully: {:.2} MB", size_mb);
 
     Ok(())
 }
 
-// Load bucket cache from disk
-async fn load_cache_from_disk() -> Result<HashMap<String, SearchableBucket>, String> {
-    let cache_file = get_cache_file_path()?;
+fn parse_csv_buckets(csv_data: &str) -> Result<Vec<SearchableBucket>, String> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(csv_data.as_bytes());
+
+    reader
+        .deserialize()
+        .map(|result| result.map_err(|e| format!("Failed to deserialize bucket from CSV: {}", e)))
+        .collect()
+}
 
+// Load one source's buckets from disk
+async fn load_cache_from_disk(cache_file: &Path) -> Result<Vec<SearchableBucket>, String> {
     if !cache_file.exists() {
         log::info!("No cache file found at: {:?}", cache_file);
-        return Ok(HashMap::new());
+        return Ok(Vec::new());
     }
 
     log::info!("Loading cache from: {:?}", cache_file);
 
-    let csv_data = fs::read_to_string(&cache_file)
+    let csv_data = fs::read_to_string(cache_file)
         .await
         .map_err(|e| format!("Failed to read cache file: {}", e))?;
-
-    // Parse CSV data
-    let mut reader = ReaderBuilder::new()
-        .has_headers(true)
-        .from_reader(csv_data.as_bytes());
-
-    let mut buckets = HashMap::new();
-    for result in reader.deserialize() {
-        let bucket: SearchableBucket =
-            result.map_err(|e| format!("Failed to deserialize bucket from CSV: {}", e))?;
-        buckets.insert(bucket.full_name.clone(), bucket);
-    }
+    let buckets = parse_csv_buckets(&csv_data)?;
 
     log::info!("Loaded {} buckets from cache", buckets.len());
 
     Ok(buckets)
 }
 
-// Convert markdown table to CSV format with file cleanup
-pub async fn fetch_and_parse_bucket_directory(
-    filters: Option<BucketFilterOptions>,
-) -> Result<HashMap<String, SearchableBucket>, String> {
-    let filters = filters.unwrap_or_default();
-    let url = "https://github.com/rasa/scoop-directory/raw/refs/heads/master/by-stars.md";
+async fn load_source_index(cache_dir: &Path) -> HashMap<String, SourceCacheEntry> {
+    let Ok(content) = fs::read_to_string(cache_dir.join(SOURCE_INDEX_FILE)).await else {
+        return HashMap::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        log::warn!("Ignoring unreadable bucket source index: {}", e);
+        HashMap::new()
+    })
+}
 
-    log::info!("Fetching bucket directory from: {}", url);
+async fn save_source_index(
+    cache_dir: &Path,
+    entries: &HashMap<String, SourceCacheEntry>,
+) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize bucket source index: {}", e))?;
+    fs::write(cache_dir.join(SOURCE_INDEX_FILE), content)
+        .await
+        .map_err(|e| format!("Failed to write bucket source index: {}", e))
+}
 
+async fn fetch_text(url: &str) -> Result<String, String> {
     let response = reqwest::get(url)
         .await
+        .and_then(|response| response.error_for_status())
         .map_err(|e| format!("Failed to fetch bucket directory: {}", e))?;
 
-    let content = response
+    response
         .text()
         .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
+        .map_err(|e| format!("Failed to read response: {}", e))
+}
 
-    let original_size_mb = content.len() as f64 / (1024.0 * 1024.0);
-    log::info!(
-        "Downloaded {:.2} MB, parsing markdown table...",
-        original_size_mb
-    );
+async fn fetch_scoop_search(
+    url: &str,
+    api_key: Option<&str>,
+) -> Result<Vec<SearchableBucket>, String> {
+    let client = reqwest::Client::new();
+    let mut buckets = HashMap::new();
 
-    let buckets = parse_markdown_to_buckets(&content)?;
+    for page in 0..SCOOP_SEARCH_MAX_PAGES {
+        let body = serde_json::json!({
+            "search": "*",
+            "top": SCOOP_SEARCH_PAGE_SIZE,
+            "skip": page * SCOOP_SEARCH_PAGE_SIZE,
+            "select": "Metadata/Repository,Metadata/RepositoryStars,Metadata/OfficialRepository,Metadata/Committed",
+        });
+        let mut request = client.post(url).json(&body);
+        if let Some(key) = api_key {
+            request = request.header("api-key", key);
+        }
 
-    log::info!("Parsed {} buckets from directory", buckets.len());
+        let content = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Failed to query ScoopSearch: {}", e))?
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+
+        let count = bucket_sources::parse_scoop_search_page(&content, &mut buckets)?;
+        if count < SCOOP_SEARCH_PAGE_SIZE {
+            break;
+        }
+    }
 
-    // Convert to HashMap keyed by full_name (owner/repo) to avoid deduplication of bucket names
-    let mut bucket_map = HashMap::new();
-    let mut filtered_count = 0;
-    let original_count = buckets.len();
+    Ok(buckets.into_values().collect())
+}
 
-    for bucket in buckets {
-        if apply_bucket_filters(&bucket, &filters) {
-            bucket_map.insert(bucket.full_name.clone(), bucket);
-        } else {
-            filtered_count += 1;
+/// Downloads or reads a source and parses it into buckets.
+async fn fetch_source(source: &DirectorySource) -> Result<Vec<SearchableBucket>, String> {
+    match &source.kind {
+        SourceKind::Markdown { url } => {
+            log::info!("Fetching bucket directory from: {}", url);
+            let content = fetch_text(url).await?;
+            log::info!(
+                "Downloaded {:.2} MB, parsing markdown table...",
+                content.len() as f64 / (1024.0 * 1024.0)
+            );
+            parse_markdown_to_buckets(&content)
+        }
+        SourceKind::JsonIndex { url } => {
+            log::info!("Fetching bucket index from: {}", url);
+            bucket_sources::parse_json_index(&fetch_text(url).await?)
+        }
+        SourceKind::ScoopSearch { url, api_key } => {
+            log::info!("Querying ScoopSearch index at: {}", url);
+            fetch_scoop_search(url, api_key.as_deref()).await
+        }
+        SourceKind::LocalFile { path } => {
+            log::info!("Reading bucket directory from file: {}", path);
+            let content = fs::read_to_string(path)
+                .await
+                .map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+            let extension = Path::new(path)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(str::to_lowercase);
+            match extension.as_deref() {
+                Some("json") => bucket_sources::parse_json_index(&content),
+                Some("csv") => parse_csv_buckets(&content),
+                _ => parse_markdown_to_buckets(&content),
+            }
         }
     }
+}
 
-    log::info!(
-        "Applied filters: {} buckets filtered out, {} remaining (original: {})",
-        filtered_count,
-        bucket_map.len(),
-        original_count
-    );
-    if filters.disable_chinese_buckets {
-        log::info!("Chinese bucket filtering was enabled");
+/// Returns a source's buckets, refetching when its cache is stale. A failed
+/// refresh falls back to the previous cache and records the error.
+async fn load_source(
+    cache_dir: &Path,
+    source: &DirectorySource,
+    entries: &mut HashMap<String, SourceCacheEntry>,
+    now: i64,
+) -> Result<Vec<SearchableBucket>, String> {
+    let cache_file = source_cache_file(cache_dir, source);
+    let key = source.cache_key();
+
+    if bucket_sources::is_cache_fresh(source, entries.get(&key), now, source.local_modified()) {
+        match load_cache_from_disk(&cache_file).await {
+            Ok(buckets) if !buckets.is_empty() => return Ok(buckets),
+            Ok(_) => log::info!("Cache for source '{}' is empty", source.id),
+            Err(e) => log::warn!("Failed to load cache for source '{}': {}", source.id, e),
+        }
+    }
+
+    match fetch_source(source).await {
+        Ok(buckets) => {
+            log::info!(
+                "Parsed {} buckets from source '{}'",
+                buckets.len(),
+                source.id
+            );
+            if let Err(e) = save_cache_to_disk(&cache_file, &buckets).await {
+                log::warn!("Failed to cache source '{}': {}", source.id, e);
+            }
+            entries.insert(
+                key,
+                SourceCacheEntry {
+                    fingerprint: source.fingerprint(),
+                    fetched_at: now,
+                    bucket_count: buckets.len(),
+                    last_error: None,
+                },
+            );
+            Ok(buckets)
+        }
+        Err(e) => {
+            log::warn!("Failed to refresh bucket source '{}': {}", source.id, e);
+            let stale = load_cache_from_disk(&cache_file).await.unwrap_or_default();
+            if stale.is_empty() {
+                return Err(e);
+            }
+            if let Some(entry) = entries.get_mut(&key) {
+                entry.last_error = Some(e);
+            }
+            log::info!(
+                "Serving {} stale buckets for source '{}'",
+                stale.len(),
+                source.id
+            );
+            Ok(stale)
+        }
+    }
+}
+
+/// Merged buckets and the freshness records they were loaded with.
+pub struct LoadedDirectory {
+    pub entries: HashMap<String, SourceCacheEntry>,
+    pub buckets: HashMap<String, SearchableBucket>,
+}
+
+/// Loads every source, refreshing stale ones, and merges the results in
+/// source order. Fails only when no source produced any buckets.
+pub async fn fetch_and_parse_bucket_directory(
+    sources: &[DirectorySource],
+) -> Result<LoadedDirectory, String> {
+    let cache_dir = get_cache_dir()?;
+    let mut entries = load_source_index(&cache_dir).await;
+    let now = chrono::Utc::now().timestamp();
+
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    for source in sources {
+        match load_source(&cache_dir, source, &mut entries, now).await {
+            Ok(buckets) => results.push((source.id.clone(), buckets)),
+            Err(e) => errors.push(format!("{}: {}", source.id, e)),
+        }
     }
-    if filters.minimum_stars > 0 {
-        log::info!("Minimum star filter: {} stars", filters.minimum_stars);
+
+    if let Err(e) = save_source_index(&cache_dir, &entries).await {
+        log::warn!("{}", e);
     }
 
-    // Save optimized cache to disk
-    save_cache_to_disk(&bucket_map).await?;
+    if results.is_empty() && !errors.is_empty() {
+        return Err(format!(
+            "Failed to load any bucket directory source ({})",
+            errors.join("; ")
+        ));
+    }
 
-    // The original markdown content is now dropped and will be garbage collected
+    let buckets = bucket_sources::merge_sources(results);
     log::info!(
-        "Original {:.2} MB markdown file processed and cleaned up from memory",
-        original_size_mb
+        "Merged {} buckets from {} source(s)",
+        buckets.len(),
+        sources.len()
     );
 
-    Ok(bucket_map)
+    Ok(LoadedDirectory { entries, buckets })
 }
 
 static COMPLEX_REGEX: Lazy<Regex> = Lazy::new(|| {
@@ -235,7 +389,7 @@ fn try_parse_complex(line: &str) -> Option<SearchableBucket> {
     let date_str = captures.get(8).map_or("Unknown", |m| m.as_str()).trim();
 
     if !owner.is_empty() && !repo.is_empty() {
-        let bucket_name = extract_bucket_name(repo);
+        let bucket_name = bucket_sources::bucket_name_from_repo(repo);
         let last_updated = parse_encoded_date(date_str);
 
         Some(SearchableBucket {
@@ -248,6 +402,7 @@ fn try_parse_complex(line: &str) -> Option<SearchableBucket> {
             apps,
             last_updated,
             is_verified: false,
+            source: None,
         })
     } else {
         None
@@ -285,7 +440,7 @@ fn try_parse_simple(line: &str) -> Option<SearchableBucket> {
             )
         };
 
-        let clean_bucket_name = extract_bucket_name(&bucket_name);
+        let clean_bucket_name = bucket_sources::bucket_name_from_repo(bucket_name);
 
         Some(SearchableBucket {
             name: clean_bucket_name,
@@ -297,6 +452,7 @@ fn try_parse_simple(line: &str) -> Option<SearchableBucket> {
             apps: 1,
             last_updated: "Unknown".to_string(),
             is_verified: false,
+            source: None,
         })
     } else {
         None
@@ -333,7 +489,7 @@ fn try_parse_basic(line: &str) -> Option<SearchableBucket> {
             )
         };
 
-        let clean_bucket_name = extract_bucket_name(&repo_name);
+        let clean_bucket_name = bucket_sources::bucket_name_from_repo(&repo_name);
 
         Some(SearchableBucket {
             name: clean_bucket_name,
@@ -345,6 +501,7 @@ fn try_parse_basic(line: &str) -> Option<SearchableBucket> {
             apps: 1,
             last_updated: "Unknown".to_string(),
             is_verified: false,
+            source: None,
         })
     } else {
         None
@@ -379,14 +536,6 @@ fn parse_markdown_to_buckets(content: &str) -> Result<Vec<SearchableBucket>, Str
     Ok(buckets)
 }
 
-fn extract_bucket_name(repo: &str) -> String {
-    // Remove common prefixes and convert to lowercase
-    repo.replace("scoop-", "")
-        .replace("Scoop-", "")
-        .replace("scoop_", "")
-        .to_lowercase()
-}
-
 // Check if text contains Chinese characters
 fn contains_chinese_characters(text: &str) -> bool {
     text.chars().any(|c| {
@@ -452,97 +601,117 @@ fn parse_encoded_date(date_str: &str) -> String {
     "Unknown".to_string()
 }
 
+fn apply_filters(
+    buckets: &HashMap<String, SearchableBucket>,
+    filters: Option<&BucketFilterOptions>,
+) -> HashMap<String, SearchableBucket> {
+    let Some(filters) = filters else {
+        return buckets.clone();
+    };
+
+    let filtered: HashMap<String, SearchableBucket> = buckets
+        .iter()
+        .filter(|(_, bucket)| apply_bucket_filters(bucket, filters))
+        .map(|(key, bucket)| (key.clone(), bucket.clone()))
+        .collect();
+
+    log::info!(
+        "Applied filters: {} buckets filtered out, {} remaining (original: {})",
+        buckets.len() - filtered.len(),
+        filtered.len(),
+        buckets.len()
+    );
+    filtered
+}
+
 // Get cached buckets or fetch if not cached
 pub async fn get_cached_buckets(
+    sources: &[DirectorySource],
     filters: Option<BucketFilterOptions>,
 ) -> Result<HashMap<String, SearchableBucket>, String> {
+    let now = chrono::Utc::now().timestamp();
+
     // First check memory cache
     {
         let cache = BUCKET_CACHE.read().await;
-        if !cache.is_empty() {
-            log::debug!("Returning {} cached buckets from memory", cache.len());
-            return Ok(cache.clone());
-        }
-    }
-
-    // Try to load from disk cache
-    match load_cache_from_disk().await {
-        Ok(disk_cache) if !disk_cache.is_empty() => {
-            log::info!("Loaded {} buckets from disk cache", disk_cache.len());
-
-            // If filters are provided, apply them to cached data
-            let filtered_cache = if let Some(ref filter_opts) = filters {
-                if filter_opts.disable_chinese_buckets || filter_opts.minimum_stars > 0 {
-                    log::info!("Applying filters to cached data");
-                    let mut filtered = HashMap::new();
-                    let mut filtered_count = 0;
-                    let original_count = disk_cache.len();
-
-                    for (key, bucket) in disk_cache {
-                        if apply_bucket_filters(&bucket, filter_opts) {
-                            filtered.insert(key, bucket);
-                        } else {
-                            filtered_count += 1;
-                        }
-                    }
-
-                    log::info!(
-                        "Filtered cache: {} buckets filtered out, {} remaining (original: {})",
-                        filtered_count,
-                        filtered.len(),
-                        original_count
-                    );
-                    filtered
-                } else {
-                    disk_cache
-                }
-            } else {
-                disk_cache
-            };
-
-            // Update memory cache
-            {
-                let mut cache = BUCKET_CACHE.write().await;
-                *cache = filtered_cache.clone();
+        if let Some(cached) = cache.as_ref() {
+            let fresh = cached.sources == sources
+                && sources.iter().all(|source| {
+                    bucket_sources::is_cache_fresh(
+                        source,
+                        cached.entries.get(&source.cache_key()),
+                        now,
+                        source.local_modified(),
+                    )
+                });
+            if fresh {
+                log::debug!(
+                    "Returning {} cached buckets from memory",
+                    cached.buckets.len()
+                );
+                return Ok(apply_filters(&cached.buckets, filters.as_ref()));
             }
-
-            return Ok(filtered_cache);
         }
-        Ok(_) => log::info!("Disk cache is empty or doesn't exist"),
-        Err(e) => log::warn!("Failed to load disk cache: {}", e),
     }
 
-    log::info!("No cache found, fetching bucket directory...");
-    let buckets = fetch_and_parse_bucket_directory(filters).await?;
+    let LoadedDirectory { entries, buckets } = fetch_and_parse_bucket_directory(sources).await?;
+    let filtered = apply_filters(&buckets, filters.as_ref());
 
     // Update memory cache
     {
         let mut cache = BUCKET_CACHE.write().await;
-        *cache = buckets.clone();
+        *cache = Some(CachedDirectory {
+            sources: sources.to_vec(),
+            entries,
+            buckets,
+        });
     }
 
-    Ok(buckets)
+    Ok(filtered)
+}
+
+// Check if any source has a cache file
+pub async fn cache_exists(sources: &[DirectorySource]) -> Result<bool, String> {
+    let cache_dir = get_cache_dir()?;
+    Ok(sources
+        .iter()
+        .any(|source| source_cache_file(&cache_dir, source).exists()))
 }
 
-// Check if cache file exists
-pub async fn cache_exists() -> Result<bool, String> {
-    let cache_file = get_cache_file_path()?;
-    Ok(cache_file.exists())
+/// Freshness of each configured source's cache, in source order.
+pub async fn source_cache_status(
+    sources: &[DirectorySource],
+) -> Result<Vec<(DirectorySource, Option<SourceCacheEntry>)>, String> {
+    let cache_dir = get_cache_dir()?;
+    let mut entries = load_source_index(&cache_dir).await;
+    Ok(sources
+        .iter()
+        .map(|source| (source.clone(), entries.remove(&source.cache_key())))
+        .collect())
 }
 
 // Clear cache (useful for testing or forced refresh)
 pub async fn clear_cache() {
     // Clear memory cache
     let mut cache = BUCKET_CACHE.write().await;
-    cache.clear();
-
-    // Clear disk cache
-    if let Ok(cache_file) = get_cache_file_path() {
-        if cache_file.exists() {
-            if let Err(e) = fs::remove_file(&cache_file).await {
-                log::warn!("Failed to remove cache file: {}", e);
-            } else {
-                log::info!("Disk cache file removed: {:?}", cache_file);
+    *cache = None;
+
+    // Clear disk cache: every source file, the freshness index and the legacy file
+    if let Ok(cache_dir) = get_cache_dir() {
+        if let Ok(mut dir) = fs::read_dir(&cache_dir).await {
+            while let Ok(Some(entry)) = dir.next_entry().await {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let is_bucket_cache = name == LEGACY_CACHE_FILE
+                    || name == SOURCE_INDEX_FILE
+                    || (name.starts_with("bucket_cache_") && name.ends_with(".csv"));
+                if !is_bucket_cache {
+                    continue;
+                }
+                if let Err(e) = fs::remove_file(entry.path()).await {
+                    log::warn!("Failed to remove cache file: {}", e);
+                } else {
+                    log::info!("Disk cache file removed: {:?}", entry.path());
+                }
             }
         }
     }
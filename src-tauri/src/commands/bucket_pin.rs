@@ -0,0 +1,260 @@
+//! Pinning buckets to a fixed commit or tag.
+//!
+//! A pinned bucket is checked out on a detached HEAD and skipped by regular
+//! updates. The pin is recorded in `.git/rscoop-pin.json` so it travels with
+//! the bucket and disappears when the bucket is removed.
+use crate::commands::bucket_sync;
+use git2::{build::CheckoutBuilder, BranchType, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PIN_FILE: &str = "rscoop-pin.json";
+
+/// A bucket frozen at a specific revision.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BucketPin {
+    /// The ref the user asked for: a tag, commit hash or `origin/<branch>`.
+    pub reference: String,
+    /// Full commit hash the ref resolved to when pinned.
+    pub commit: String,
+    /// Branch the bucket tracked before pinning. Used for the behind count and
+    /// restored on unpin.
+    pub branch: String,
+    pub pinned_at: String,
+}
+
+fn pin_file(repo: &Repository) -> PathBuf {
+    repo.path().join(PIN_FILE)
+}
+
+/// Reads the pin of a bucket directory, if any.
+pub fn read_pin(bucket_path: &Path) -> Option<BucketPin> {
+    let content = fs::read_to_string(bucket_path.join(".git").join(PIN_FILE)).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(pin) => Some(pin),
+        Err(e) => {
+            log::warn!(
+                "Ignoring unreadable bucket pin in '{}': {}",
+                bucket_path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+fn write_pin(repo: &Repository, pin: &BucketPin) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(pin)
+        .map_err(|e| format!("Failed to serialize bucket pin: {}", e))?;
+    fs::write(pin_file(repo), content).map_err(|e| format!("Failed to write bucket pin: {}", e))
+}
+
+fn resolve_commit(repo: &Repository, reference: &str) -> Result<Oid, String> {
+    let candidates = [reference.to_string(), format!("origin/{}", reference)];
+    candidates
+        .iter()
+        .find_map(|candidate| {
+            repo.revparse_single(candidate)
+                .and_then(|object| object.peel_to_commit())
+                .ok()
+        })
+        .map(|commit| commit.id())
+        .ok_or_else(|| format!("Could not resolve '{}' to a commit", reference))
+}
+
+/// Checks out `reference` on a detached HEAD and records the pin. Pinning an
+/// already pinned bucket moves it to the new ref and keeps the original branch.
+pub fn pin_to_ref(repo: &Repository, reference: &str) -> Result<BucketPin, String> {
+    if bucket_sync::has_local_edits(repo)? {
+        return Err("Cannot pin a bucket with uncommitted local edits".to_string());
+    }
+
+    let branch = match repo.workdir().and_then(read_pin) {
+        Some(existing) => existing.branch,
+        None => {
+            let head = repo
+                .head()
+                .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+            if !head.is_branch() {
+                return Err("Bucket is not on a branch; cannot determine upstream".to_string());
+            }
+            head.shorthand()
+                .map_err(|e| format!("Invalid branch name: {}", e))?
+                .to_string()
+        }
+    };
+
+    let commit_id = resolve_commit(repo, reference)?;
+    let commit = repo
+        .find_commit(commit_id)
+        .map_err(|e| format!("Failed to find commit {}: {}", commit_id, e))?;
+
+    let mut checkout_builder = CheckoutBuilder::new();
+    checkout_builder.safe();
+    repo.checkout_tree(commit.as_object(), Some(&mut checkout_builder))
+        .map_err(|e| format!("Failed to check out '{}': {}", reference, e))?;
+    repo.set_head_detached(commit_id)
+        .map_err(|e| format!("Failed to detach HEAD at '{}': {}", reference, e))?;
+
+    let pin = BucketPin {
+        reference: reference.to_string(),
+        commit: commit_id.to_string(),
+        branch,
+        pinned_at: chrono::Utc::now().to_rfc3339(),
+    };
+    write_pin(repo, &pin)?;
+    Ok(pin)
+}
+
+/// Removes the pin and returns the bucket to the branch it tracked before.
+/// The branch is left where it was; the next update moves it forward.
+pub fn unpin(repo: &Repository) -> Result<Option<BucketPin>, String> {
+    let Some(pin) = repo.workdir().and_then(read_pin) else {
+        return Ok(None);
+    };
+
+    if repo.find_branch(&pin.branch, BranchType::Local).is_ok()
+        || repo
+            .find_branch(&format!("origin/{}", pin.branch), BranchType::Remote)
+            .is_ok()
+    {
+        bucket_sync::switch_branch(repo, &pin.branch)?;
+    } else {
+        return Err(format!(
+            "Branch '{}' no longer exists; switch the bucket to another branch first",
+            pin.branch
+        ));
+    }
+
+    fs::remove_file(pin_file(repo)).map_err(|e| format!("Failed to remove bucket pin: {}", e))?;
+    Ok(Some(pin))
+}
+
+/// Number of upstream commits the pinned revision is missing, computed from
+/// already fetched refs. `None` when upstream cannot be resolved locally.
+pub fn commits_behind(repo: &Repository, pin: &BucketPin) -> Option<usize> {
+    let pinned = Oid::from_str(&pin.commit).ok()?;
+    let upstream = repo
+        .find_branch(&format!("origin/{}", pin.branch), BranchType::Remote)
+        .ok()?
+        .get()
+        .target()?;
+    repo.graph_ahead_behind(pinned, upstream)
+        .ok()
+        .map(|(_, behind)| behind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use git2::{IndexAddOption, RepositoryInitOptions, Signature};
+
+    fn commit_file(repo: &Repository, relative: &str, contents: &str) -> Oid {
+        let root = repo.workdir().unwrap();
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+        let sig = Signature::now("rscoop", "rscoop@example.com").unwrap();
+        let id = repo
+            .commit(Some("HEAD"), &sig, &sig, "change", &tree, &parents)
+            .unwrap();
+        repo.find_remote("origin")
+            .unwrap()
+            .push(&["refs/heads/main:refs/heads/main"], None)
+            .unwrap();
+        id
+    }
+
+    /// Returns (upstream working repo, bucket clone) sharing a bare remote.
+    fn setup(temp: &TempDir) -> (Repository, Repository) {
+        let remote_path = temp.path.join("remote.git");
+        let mut bare = RepositoryInitOptions::new();
+        bare.bare(true).initial_head("main");
+        Repository::init_opts(&remote_path, &bare).unwrap();
+
+        let mut working = RepositoryInitOptions::new();
+        working.initial_head("main");
+        let upstream = Repository::init_opts(temp.path.join("upstream"), &working).unwrap();
+        let url = remote_path.to_string_lossy().to_string();
+        upstream.remote("origin", &url).unwrap();
+        commit_file(&upstream, "bucket/app.json", r#"{"version":"1.0"}"#);
+
+        let bucket = Repository::clone(&url, temp.path.join("bucket")).unwrap();
+        (upstream, bucket)
+    }
+
+    fn fetch(repo: &Repository) {
+        repo.find_remote("origin")
+            .unwrap()
+            .fetch(&[] as &[&str], None, None)
+            .unwrap();
+    }
+
+    #[test]
+    fn pin_detaches_head_and_reports_commits_behind() {
+        let temp = TempDir::new("pin-behind");
+        let (upstream, bucket) = setup(&temp);
+        let pinned = bucket.head().unwrap().target().unwrap();
+        let bucket_path = bucket.workdir().unwrap().to_path_buf();
+
+        let pin = pin_to_ref(&bucket, &pinned.to_string()).unwrap();
+        assert_eq!(pin.branch, "main");
+        assert!(bucket.head_detached().unwrap());
+        assert_eq!(read_pin(&bucket_path), Some(pin.clone()));
+        assert_eq!(commits_behind(&bucket, &pin), Some(0));
+
+        commit_file(&upstream, "bucket/app.json", r#"{"version":"2.0"}"#);
+        commit_file(&upstream, "bucket/tool.json", r#"{"version":"1.0"}"#);
+        fetch(&bucket);
+
+        assert_eq!(commits_behind(&bucket, &pin), Some(2));
+        assert_eq!(bucket.head().unwrap().target(), Some(pinned));
+    }
+
+    #[test]
+    fn pin_resolves_tags_and_unpin_restores_branch() {
+        let temp = TempDir::new("pin-tag");
+        let (upstream, _) = setup(&temp);
+        let tagged = upstream.head().unwrap().peel_to_commit().unwrap();
+        upstream
+            .tag_lightweight("v1.0", tagged.as_object(), false)
+            .unwrap();
+        upstream
+            .find_remote("origin")
+            .unwrap()
+            .push(&["refs/tags/v1.0:refs/tags/v1.0"], None)
+            .unwrap();
+        commit_file(&upstream, "bucket/app.json", r#"{"version":"2.0"}"#);
+
+        let bucket = Repository::clone(
+            temp.path.join("remote.git").to_str().unwrap(),
+            temp.path.join("tagged"),
+        )
+        .unwrap();
+        let bucket_path = bucket.workdir().unwrap().to_path_buf();
+
+        let pin = pin_to_ref(&bucket, "v1.0").unwrap();
+        assert_eq!(pin.commit, tagged.id().to_string());
+        assert_eq!(
+            fs::read_to_string(bucket_path.join("bucket/app.json")).unwrap(),
+            r#"{"version":"1.0"}"#
+        );
+
+        let removed = unpin(&bucket).unwrap();
+        assert_eq!(removed.map(|p| p.reference), Some("v1.0".to_string()));
+        assert!(read_pin(&bucket_path).is_none());
+        assert_eq!(bucket.head().unwrap().shorthand().unwrap(), "main");
+        assert!(pin_to_ref(&bucket, "does-not-exist").is_err());
+    }
+}
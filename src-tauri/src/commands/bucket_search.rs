@@ -1,7 +1,8 @@
 use super::bucket_parser::{self, BucketFilterOptions};
+use super::bucket_sources::{self, DirectorySource};
 use crate::state::AppState;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, State};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchableBucket {
@@ -14,6 +15,9 @@ pub struct SearchableBucket {
     pub apps: u32,
     pub last_updated: String,
     pub is_verified: bool,
+    /// Id of the directory source that listed this bucket.
+    #[serde(default)]
+    pub source: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -41,6 +45,18 @@ pub struct ExpandedSearchInfo {
     pub description: String,
 }
 
+/// A configured directory source and the freshness of its cache.
+#[derive(Debug, Serialize)]
+pub struct BucketSourceStatus {
+    #[serde(flatten)]
+    pub source: DirectorySource,
+    /// RFC 3339 time of the last successful fetch.
+    pub fetched_at: Option<String>,
+    pub bucket_count: Option<usize>,
+    pub is_fresh: bool,
+    pub last_error: Option<String>,
+}
+
 // Default verified buckets - these show automatically
 static VERIFIED_BUCKETS_DATA: &[(&str, &str, &str, &str, u32, u32, u32, &str)] = &[
     (
@@ -160,19 +176,35 @@ fn get_verified_buckets() -> Vec<SearchableBucket> {
                     apps,
                     last_updated: last_updated.to_string(),
                     is_verified: true,
+                    source: Some("built-in".to_string()),
                 }
             },
         )
         .collect()
 }
 
-// Parse the massive bucket list from GitHub using efficient parser
+/// Directory sources from the settings, defaulting to rasa/scoop-directory.
+pub(crate) fn configured_sources(app: &AppHandle) -> Vec<DirectorySource> {
+    let value = crate::commands::settings::get_config_value(
+        app.clone(),
+        bucket_sources::SOURCES_KEY.to_string(),
+    )
+    .ok()
+    .flatten();
+    bucket_sources::parse_sources(value)
+}
+
+// Load the merged bucket list from all configured directory sources
 async fn fetch_expanded_bucket_list(
+    sources: &[DirectorySource],
     filters: Option<BucketFilterOptions>,
 ) -> Result<Vec<SearchableBucket>, String> {
-    log::info!("Fetching expanded bucket list using efficient parser...");
+    log::info!(
+        "Fetching expanded bucket list from {} source(s)...",
+        sources.len()
+    );
 
-    let bucket_map = bucket_parser::get_cached_buckets(filters).await?;
+    let bucket_map = bucket_parser::get_cached_buckets(sources, filters).await?;
     let buckets: Vec<SearchableBucket> = bucket_map.into_values().collect();
 
     log::info!("Retrieved {} buckets from cache/parser", buckets.len());
@@ -217,6 +249,7 @@ fn sort_buckets(buckets: &mut [SearchableBucket], sort_by: &str) {
 
 #[tauri::command]
 pub async fn search_buckets(
+    app: AppHandle,
     request: BucketSearchRequest,
     _state: State<'_, AppState>,
 ) -> Result<BucketSearchResponse, String> {
@@ -249,7 +282,8 @@ pub async fn search_buckets(
             verified_buckets.iter().map(|b| b.name.clone()).collect();
 
         // Get expanded buckets from cache/parser with filters
-        let mut expanded_buckets = fetch_expanded_bucket_list(filters).await?;
+        let sources = configured_sources(&app);
+        let mut expanded_buckets = fetch_expanded_bucket_list(&sources, filters).await?;
 
         // Mark verified buckets in the expanded list
         for bucket in &mut expanded_buckets {
@@ -340,8 +374,8 @@ pub async fn clear_bucket_cache() -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn check_bucket_cache_exists() -> Result<bool, String> {
-    match bucket_parser::cache_exists().await {
+pub async fn check_bucket_cache_exists(app: AppHandle) -> Result<bool, String> {
+    match bucket_parser::cache_exists(&configured_sources(&app)).await {
         Ok(exists) => {
             log::debug!("Bucket cache exists: {}", exists);
             Ok(exists)
@@ -353,6 +387,52 @@ pub async fn check_bucket_cache_exists() -> Result<bool, String> {
     }
 }
 
+#[tauri::command]
+pub async fn get_bucket_directory_sources(
+    app: AppHandle,
+) -> Result<Vec<BucketSourceStatus>, String> {
+    let sources = configured_sources(&app);
+    let now = chrono::Utc::now().timestamp();
+    let statuses = bucket_parser::source_cache_status(&sources).await?;
+
+    Ok(statuses
+        .into_iter()
+        .map(|(source, entry)| {
+            let is_fresh = bucket_sources::is_cache_fresh(
+                &source,
+                entry.as_ref(),
+                now,
+                source.local_modified(),
+            );
+            BucketSourceStatus {
+                fetched_at: entry.as_ref().and_then(|entry| {
+                    chrono::DateTime::from_timestamp(entry.fetched_at, 0)
+                        .map(|time| time.to_rfc3339())
+                }),
+                bucket_count: entry.as_ref().map(|entry| entry.bucket_count),
+                last_error: entry.and_then(|entry| entry.last_error),
+                is_fresh,
+                source,
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn set_bucket_directory_sources(
+    app: AppHandle,
+    sources: Vec<DirectorySource>,
+) -> Result<(), String> {
+    if sources.iter().any(|source| source.id.trim().is_empty()) {
+        return Err("Every bucket directory source needs an id".to_string());
+    }
+    let value = serde_json::to_value(&sources)
+        .map_err(|e| format!("Failed to serialize bucket directory sources: {}", e))?;
+    // The memory cache is keyed by the source list, so the next search
+    // reloads on its own.
+    crate::commands::settings::set_config_value(app, bucket_sources::SOURCES_KEY.to_string(), value)
+}
+
 fn calculate_bucket_score(bucket: &SearchableBucket, query_lower: &str) -> f64 {
     let mut score = 0.0;
 
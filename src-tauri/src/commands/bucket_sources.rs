@@ -0,0 +1,528 @@
+//! Bucket directory sources for the community bucket search.
+//!
+//! A source is anything that can list buckets: the rasa/scoop-directory
+//! markdown table, a JSON index, a ScoopSearch-style search API or a local
+//! file for air-gapped networks. Results from all enabled sources are merged
+//! by `full_name`; earlier sources win and later ones only fill gaps.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use super::bucket_search::SearchableBucket;
+
+/// Config key holding the list of [`DirectorySource`]s.
+pub const SOURCES_KEY: &str = "buckets.directorySources";
+pub const DEFAULT_SOURCE_ID: &str = "scoop-directory";
+pub const DEFAULT_MARKDOWN_URL: &str =
+    "https://github.com/rasa/scoop-directory/raw/refs/heads/master/by-stars.md";
+/// Remote sources are refetched once their cache is older than this.
+pub const DEFAULT_MAX_AGE_HOURS: u64 = 24 * 7;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum SourceKind {
+    /// A markdown table in the rasa/scoop-directory layout.
+    Markdown { url: String },
+    /// A JSON array of buckets, or an object with a `buckets` array.
+    JsonIndex { url: String },
+    /// A ScoopSearch-style (Azure Cognitive Search) apps index. Buckets are
+    /// derived from the repositories of the indexed manifests.
+    ScoopSearch {
+        url: String,
+        #[serde(default)]
+        api_key: Option<String>,
+    },
+    /// A markdown, JSON or CSV file on disk, picked by extension.
+    LocalFile { path: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DirectorySource {
+    pub id: String,
+    #[serde(flatten)]
+    pub kind: SourceKind,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub max_age_hours: Option<u64>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl DirectorySource {
+    /// Identifier safe to use in cache file names.
+    pub fn cache_key(&self) -> String {
+        self.id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+
+    /// Changes whenever the source definition changes, so a cache built from
+    /// an old URL or path is not reused. It is stored with the cache, so it
+    /// hashes the serialized definition with SHA-256, which unlike the std
+    /// hashers gives the same value on every build.
+    pub fn fingerprint(&self) -> String {
+        let definition = serde_json::to_vec(&self.kind).unwrap_or_default();
+        Sha256::digest(&definition)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Modification time of a local-file source, in Unix seconds.
+    pub fn local_modified(&self) -> Option<i64> {
+        let SourceKind::LocalFile { path } = &self.kind else {
+            return None;
+        };
+        let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+        Some(chrono::DateTime::<chrono::Utc>::from(modified).timestamp())
+    }
+
+    pub fn max_age_secs(&self) -> i64 {
+        (self.max_age_hours.unwrap_or(DEFAULT_MAX_AGE_HOURS) * 3600) as i64
+    }
+}
+
+pub fn default_sources() -> Vec<DirectorySource> {
+    vec![DirectorySource {
+        id: DEFAULT_SOURCE_ID.to_string(),
+        kind: SourceKind::Markdown {
+            url: DEFAULT_MARKDOWN_URL.to_string(),
+        },
+        enabled: true,
+        max_age_hours: None,
+    }]
+}
+
+/// Reads the configured sources. A missing or invalid setting falls back to
+/// the default directory; disabled sources and duplicate ids are dropped.
+pub fn parse_sources(value: Option<Value>) -> Vec<DirectorySource> {
+    let Some(value) = value else {
+        return default_sources();
+    };
+    let sources: Vec<DirectorySource> = match serde_json::from_value(value) {
+        Ok(sources) => sources,
+        Err(e) => {
+            log::warn!("Invalid bucket directory sources, using default: {}", e);
+            return default_sources();
+        }
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    sources
+        .into_iter()
+        .filter(|source| source.enabled && seen.insert(source.cache_key()))
+        .collect()
+}
+
+/// Derives a bucket name from a repository name (`scoop-extras` -> `extras`).
+pub fn bucket_name_from_repo(repo: &str) -> String {
+    repo.replace("scoop-", "")
+        .replace("Scoop-", "")
+        .replace("scoop_", "")
+        .to_lowercase()
+}
+
+/// Splits `https://github.com/owner/repo(.git)` into `owner/repo`.
+fn full_name_from_url(url: &str) -> Option<String> {
+    let path = url.trim().split_once("://")?.1;
+    let mut parts = path.split('/').skip(1).filter(|part| !part.is_empty());
+    let owner = parts.next()?;
+    let repo = parts.next()?.trim_end_matches(".git");
+    Some(format!("{}/{}", owner, repo))
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonIndexEntry {
+    #[serde(default)]
+    name: Option<String>,
+    full_name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    stars: u32,
+    #[serde(default)]
+    forks: u32,
+    #[serde(default)]
+    apps: u32,
+    #[serde(default)]
+    last_updated: Option<String>,
+    #[serde(default)]
+    is_verified: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonIndex {
+    List(Vec<JsonIndexEntry>),
+    Wrapped { buckets: Vec<JsonIndexEntry> },
+}
+
+/// Parses the JSON index format.
+pub fn parse_json_index(content: &str) -> Result<Vec<SearchableBucket>, String> {
+    let index: JsonIndex =
+        serde_json::from_str(content).map_err(|e| format!("Invalid bucket index: {}", e))?;
+    let entries = match index {
+        JsonIndex::List(entries) | JsonIndex::Wrapped { buckets: entries } => entries,
+    };
+
+    Ok(entries
+        .into_iter()
+        .filter(|entry| entry.full_name.contains('/'))
+        .map(|entry| {
+            let repo = entry.full_name.split('/').nth(1).unwrap_or_default();
+            SearchableBucket {
+                name: entry.name.unwrap_or_else(|| bucket_name_from_repo(repo)),
+                url: entry
+                    .url
+                    .unwrap_or_else(|| format!("https://github.com/{}", entry.full_name)),
+                full_name: entry.full_name,
+                description: entry.description,
+                stars: entry.stars,
+                forks: entry.forks,
+                apps: entry.apps,
+                last_updated: entry.last_updated.unwrap_or_else(|| "Unknown".to_string()),
+                is_verified: entry.is_verified,
+                source: None,
+            }
+        })
+        .collect())
+}
+
+#[derive(Debug, Deserialize)]
+struct ScoopSearchResponse {
+    #[serde(default)]
+    value: Vec<ScoopSearchDoc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ScoopSearchDoc {
+    metadata: ScoopSearchMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ScoopSearchMetadata {
+    repository: String,
+    #[serde(default)]
+    repository_stars: u32,
+    #[serde(default)]
+    official_repository: bool,
+    #[serde(default)]
+    committed: Option<String>,
+}
+
+/// Parses one page of a ScoopSearch response. Returns the number of documents
+/// on the page so callers know when paging is done.
+pub fn parse_scoop_search_page(
+    content: &str,
+    buckets: &mut HashMap<String, SearchableBucket>,
+) -> Result<usize, String> {
+    let response: ScoopSearchResponse = serde_json::from_str(content)
+        .map_err(|e| format!("Invalid ScoopSearch response: {}", e))?;
+    let count = response.value.len();
+
+    for doc in response.value {
+        let metadata = doc.metadata;
+        let Some(full_name) = full_name_from_url(&metadata.repository) else {
+            continue;
+        };
+        let committed = metadata
+            .committed
+            .as_deref()
+            .and_then(|date| date.get(..10))
+            .map(str::to_string);
+
+        let bucket = buckets.entry(full_name.to_lowercase()).or_insert_with(|| {
+            let repo = full_name.split('/').nth(1).unwrap_or_default();
+            SearchableBucket {
+                name: bucket_name_from_repo(repo),
+                full_name: full_name.clone(),
+                description: String::new(),
+                url: metadata.repository.trim_end_matches(".git").to_string(),
+                stars: 0,
+                forks: 0,
+                apps: 0,
+                last_updated: "Unknown".to_string(),
+                is_verified: false,
+                source: None,
+            }
+        });
+        bucket.apps += 1;
+        bucket.stars = bucket.stars.max(metadata.repository_stars);
+        bucket.is_verified |= metadata.official_repository;
+        if let Some(date) = committed {
+            if bucket.last_updated == "Unknown" || date > bucket.last_updated {
+                bucket.last_updated = date;
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+/// Merges per-source results in priority order, keyed by lowercase
+/// `full_name`. The first source to list a bucket owns it and is recorded as
+/// its `source`; later sources only fill empty fields.
+pub fn merge_sources(
+    results: Vec<(String, Vec<SearchableBucket>)>,
+) -> HashMap<String, SearchableBucket> {
+    let mut merged: HashMap<String, SearchableBucket> = HashMap::new();
+
+    for (source_id, buckets) in results {
+        for mut bucket in buckets {
+            match merged.entry(bucket.full_name.to_lowercase()) {
+                Entry::Vacant(slot) => {
+                    bucket.source = Some(source_id.clone());
+                    slot.insert(bucket);
+                }
+                Entry::Occupied(mut slot) => {
+                    let existing = slot.get_mut();
+                    if existing.description.is_empty() {
+                        existing.description = bucket.description;
+                    }
+                    if existing.stars == 0 {
+                        existing.stars = bucket.stars;
+                    }
+                    if existing.forks == 0 {
+                        existing.forks = bucket.forks;
+                    }
+                    if existing.apps == 0 {
+                        existing.apps = bucket.apps;
+                    }
+                    if existing.last_updated == "Unknown" {
+                        existing.last_updated = bucket.last_updated;
+                    }
+                    existing.is_verified |= bucket.is_verified;
+                }
+            }
+        }
+    }
+
+    merged
+}
+
+/// Freshness record for one source's on-disk cache.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SourceCacheEntry {
+    pub fingerprint: String,
+    /// Unix seconds of the last successful fetch.
+    pub fetched_at: i64,
+    pub bucket_count: usize,
+    /// Error of the last failed refresh; the previous cache is still served.
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// True when the cache can be served without refetching. Local files are
+/// fresh until modified; remote sources until `max_age_hours` passes.
+pub fn is_cache_fresh(
+    source: &DirectorySource,
+    entry: Option<&SourceCacheEntry>,
+    now: i64,
+    local_modified: Option<i64>,
+) -> bool {
+    let Some(entry) = entry else {
+        return false;
+    };
+    if entry.fingerprint != source.fingerprint() {
+        return false;
+    }
+    match source.kind {
+        SourceKind::LocalFile { .. } => local_modified
+            .map(|modified| modified <= entry.fetched_at)
+            .unwrap_or(true),
+        _ => now.saturating_sub(entry.fetched_at) < source.max_age_secs(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bucket(full_name: &str, stars: u32, description: &str) -> SearchableBucket {
+        SearchableBucket {
+            name: bucket_name_from_repo(full_name.split('/').nth(1).unwrap()),
+            full_name: full_name.to_string(),
+            description: description.to_string(),
+            url: format!("https://github.com/{}", full_name),
+            stars,
+            forks: 0,
+            apps: 0,
+            last_updated: "Unknown".to_string(),
+            is_verified: false,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn parses_configured_sources_with_fallback() {
+        assert_eq!(parse_sources(None), default_sources());
+        assert_eq!(
+            parse_sources(Some(serde_json::json!("nonsense"))),
+            default_sources()
+        );
+
+        let sources = parse_sources(Some(serde_json::json!([
+            {"id": "corp", "kind": "json-index", "url": "https://intranet/buckets.json"},
+            {"id": "offline", "kind": "local-file", "path": "C:\\buckets.md", "enabled": false},
+            {"id": "search", "kind": "scoop-search", "url": "https://search/api", "api_key": "k"},
+            {"id": "CORP", "kind": "markdown", "url": "https://dup"}
+        ])));
+        assert_eq!(
+            sources.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(),
+            vec!["corp", "search"]
+        );
+        assert!(matches!(
+            &sources[1].kind,
+            SourceKind::ScoopSearch { api_key: Some(key), .. } if key == "k"
+        ));
+    }
+
+    #[test]
+    fn fingerprint_is_stable_across_builds() {
+        let source = DirectorySource {
+            id: "directory".to_string(),
+            kind: SourceKind::Markdown {
+                url: "https://example.org/by-stars.md".to_string(),
+            },
+            enabled: true,
+            max_age_hours: None,
+        };
+        assert_eq!(
+            source.fingerprint(),
+            "50083ccd98d5e85a40c9da3938e4ad4bf6dfc47e6c91b2e7527ee3179d30a7d1"
+        );
+    }
+
+    #[test]
+    fn parses_json_index_in_both_shapes() {
+        let wrapped =
+            r#"{"buckets":[{"full_name":"owner/scoop-tools","stars":5},{"full_name":"invalid"}]}"#;
+        let buckets = parse_json_index(wrapped).unwrap();
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].name, "tools");
+        assert_eq!(buckets[0].url, "https://github.com/owner/scoop-tools");
+        assert_eq!(buckets[0].last_updated, "Unknown");
+
+        let list =
+            r#"[{"name":"x","full_name":"a/b","url":"https://git.corp/a/b","is_verified":true}]"#;
+        let buckets = parse_json_index(list).unwrap();
+        assert_eq!(buckets[0].name, "x");
+        assert!(buckets[0].is_verified);
+
+        assert!(parse_json_index("{").is_err());
+    }
+
+    #[test]
+    fn aggregates_scoop_search_documents_by_repository() {
+        let page = r#"{"value":[
+            {"Metadata":{"Repository":"https://github.com/ScoopInstaller/Main","RepositoryStars":1500,"OfficialRepository":true,"Committed":"2025-01-02T10:00:00Z"}},
+            {"Metadata":{"Repository":"https://github.com/ScoopInstaller/Main","RepositoryStars":1500,"OfficialRepository":true,"Committed":"2025-03-04T10:00:00Z"}},
+            {"Metadata":{"Repository":"https://github.com/someone/scoop-stuff.git","RepositoryStars":3}}
+        ]}"#;
+        let mut buckets = HashMap::new();
+        assert_eq!(parse_scoop_search_page(page, &mut buckets).unwrap(), 3);
+
+        let main = &buckets["scoopinstaller/main"];
+        assert_eq!(main.apps, 2);
+        assert_eq!(main.stars, 1500);
+        assert!(main.is_verified);
+        assert_eq!(main.last_updated, "2025-03-04");
+
+        let stuff = &buckets["someone/scoop-stuff"];
+        assert_eq!(stuff.name, "stuff");
+        assert_eq!(stuff.url, "https://github.com/someone/scoop-stuff");
+    }
+
+    #[test]
+    fn merge_prefers_earlier_sources_and_fills_gaps() {
+        let merged = merge_sources(vec![
+            ("corp".to_string(), vec![bucket("Owner/Repo", 0, "")]),
+            (
+                "directory".to_string(),
+                vec![
+                    bucket("owner/repo", 42, "From the directory"),
+                    bucket("other/scoop-x", 1, "x"),
+                ],
+            ),
+        ]);
+
+        assert_eq!(merged.len(), 2);
+        let repo = &merged["owner/repo"];
+        assert_eq!(repo.full_name, "Owner/Repo");
+        assert_eq!(repo.source.as_deref(), Some("corp"));
+        assert_eq!(repo.stars, 42);
+        assert_eq!(repo.description, "From the directory");
+        assert_eq!(merged["other/scoop-x"].source.as_deref(), Some("directory"));
+    }
+
+    #[test]
+    fn cache_freshness_depends_on_source_kind() {
+        let remote = default_sources().remove(0);
+        let entry = SourceCacheEntry {
+            fingerprint: remote.fingerprint(),
+            fetched_at: 1_000,
+            bucket_count: 10,
+            last_error: None,
+        };
+        assert!(is_cache_fresh(&remote, Some(&entry), 1_000 + 3600, None));
+        assert!(!is_cache_fresh(
+            &remote,
+            Some(&entry),
+            1_000 + remote.max_age_secs(),
+            None
+        ));
+        assert!(!is_cache_fresh(&remote, None, 1_000, None));
+
+        let stale_definition = SourceCacheEntry {
+            fingerprint: "other".to_string(),
+            ..entry.clone()
+        };
+        assert!(!is_cache_fresh(
+            &remote,
+            Some(&stale_definition),
+            1_000,
+            None
+        ));
+
+        let local = DirectorySource {
+            id: "offline".to_string(),
+            kind: SourceKind::LocalFile {
+                path: "buckets.json".to_string(),
+            },
+            enabled: true,
+            max_age_hours: None,
+        };
+        let local_entry = SourceCacheEntry {
+            fingerprint: local.fingerprint(),
+            ..entry
+        };
+        assert!(is_cache_fresh(
+            &local,
+            Some(&local_entry),
+            99_999_999,
+            Some(900)
+        ));
+        assert!(!is_cache_fresh(
+            &local,
+            Some(&local_entry),
+            1_001,
+            Some(1_001)
+        ));
+    }
+}
@@ -0,0 +1,796 @@
+//! Git synchronisation for bucket repositories beyond the fast-forward case.
+//!
+//! Handles diverged or dirty working trees, branch switching, shallow clones
+//! and recovery of buckets whose `.git` directory is corrupt. Everything here
+//! works on plain paths and repositories so it can be exercised against local
+//! bare repositories in tests.
+use crate::utils;
+use git2::{
+    build::CheckoutBuilder, BranchType, ErrorCode, Oid, Repository, ResetType, Signature,
+    StatusOptions,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BACKUP_BRANCH_PREFIX: &str = "rscoop-backup/";
+const RECOVER_DIR_SUFFIX: &str = ".rscoop-recover";
+const BROKEN_DIR_SUFFIX: &str = ".rscoop-broken";
+
+/// What to do when a bucket has local edits or commits that upstream does not.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum DivergenceStrategy {
+    /// Leave the bucket untouched and report why it was not updated.
+    Abort,
+    /// Stash uncommitted edits, fast-forward, then re-apply them. Local
+    /// commits block the update. The default, since like Scoop's `git pull`
+    /// it never throws work away.
+    #[default]
+    Stash,
+    /// Move to `origin/<branch>`. Local commits are kept on an
+    /// `rscoop-backup/*` branch and uncommitted edits are left in the stash.
+    Reset,
+    /// Replay local commits on top of `origin/<branch>`, stashing edits
+    /// around the rebase.
+    Rebase,
+}
+
+/// How a bucket was brought up to date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncAction {
+    UpToDate,
+    FastForward,
+    Reset {
+        backup_branch: Option<String>,
+        edits_stashed: bool,
+    },
+    Rebased {
+        commits: usize,
+    },
+    Blocked {
+        reason: String,
+    },
+}
+
+/// Result of [`sync_to_upstream`].
+#[derive(Debug, Clone)]
+pub struct SyncOutcome {
+    pub old_head: Oid,
+    pub new_head: Oid,
+    pub action: SyncAction,
+    /// True when stashed edits could not be re-applied cleanly. They remain
+    /// in the stash for the user to resolve.
+    pub stash_conflict: bool,
+}
+
+impl SyncOutcome {
+    /// One-line description used as the bucket update message.
+    pub fn describe(&self, bucket_name: &str, branch: &str) -> String {
+        let mut message = match &self.action {
+            SyncAction::UpToDate => format!("Bucket '{}' is already up to date", bucket_name),
+            SyncAction::FastForward => format!("Updated bucket '{}'", bucket_name),
+            SyncAction::Reset {
+                backup_branch,
+                edits_stashed,
+            } => {
+                let mut message = format!("Reset bucket '{}' to origin/{}", bucket_name, branch);
+                if let Some(backup) = backup_branch {
+                    message.push_str(&format!("; local commits saved on '{}'", backup));
+                }
+                if *edits_stashed {
+                    message.push_str("; local edits saved in the stash");
+                }
+                message
+            }
+            SyncAction::Rebased { commits } => format!(
+                "Rebased {} local commit(s) in bucket '{}' onto origin/{}",
+                commits, bucket_name, branch
+            ),
+            SyncAction::Blocked { reason } => {
+                format!("Bucket '{}' was not updated: {}", bucket_name, reason)
+            }
+        };
+        if self.stash_conflict {
+            message.push_str("; local edits conflicted and were left in the stash");
+        }
+        message
+    }
+
+    pub fn moved(&self) -> bool {
+        self.old_head != self.new_head
+    }
+}
+
+/// Returns true when tracked files have uncommitted changes. Untracked files
+/// (e.g. local-only manifests) survive every strategy and are ignored here.
+pub fn has_local_edits(repo: &Repository) -> Result<bool, String> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .map_err(|e| format!("Failed to read repository status: {}", e))?;
+    Ok(!statuses.is_empty())
+}
+
+/// Fetch depth to reuse for an existing repository. Shallow buckets keep
+/// fetching shallowly so an update does not pull the full history.
+pub fn fetch_depth(repo: &Repository) -> Option<i32> {
+    repo.is_shallow().then_some(1)
+}
+
+fn signature(repo: &Repository) -> Result<Signature<'static>, String> {
+    repo.signature()
+        .or_else(|_| Signature::now("rScoop", "rscoop@localhost"))
+        .map_err(|e| format!("Failed to create commit signature: {}", e))
+}
+
+fn head_commit_id(repo: &Repository) -> Result<Oid, String> {
+    repo.head()
+        .and_then(|head| head.peel_to_commit())
+        .map(|commit| commit.id())
+        .map_err(|e| format!("Failed to resolve local commit: {}", e))
+}
+
+fn upstream_commit_id(repo: &Repository, branch: &str) -> Result<Oid, String> {
+    repo.find_branch(&format!("origin/{}", branch), BranchType::Remote)
+        .and_then(|remote_branch| remote_branch.get().peel_to_commit())
+        .map(|commit| commit.id())
+        .map_err(|e| format!("Could not find remote branch origin/{}: {}", branch, e))
+}
+
+fn hard_reset(repo: &Repository, target: Oid) -> Result<(), String> {
+    let object = repo
+        .find_object(target, None)
+        .map_err(|e| format!("Failed to resolve {}: {}", target, e))?;
+    let mut checkout_builder = CheckoutBuilder::new();
+    checkout_builder.force();
+    repo.reset(&object, ResetType::Hard, Some(&mut checkout_builder))
+        .map_err(|e| format!("Failed to reset to {}: {}", target, e))
+}
+
+fn create_backup_branch(repo: &Repository, commit_id: Oid) -> Result<String, String> {
+    let name = format!(
+        "{}{}",
+        BACKUP_BRANCH_PREFIX,
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    let commit = repo
+        .find_commit(commit_id)
+        .map_err(|e| format!("Failed to find local commit: {}", e))?;
+    repo.branch(&name, &commit, true)
+        .map_err(|e| format!("Failed to create backup branch '{}': {}", name, e))?;
+    Ok(name)
+}
+
+/// Replays local commits onto `upstream`. Conflicts abort the rebase and leave
+/// the branch where it was.
+fn rebase_onto(repo: &Repository, upstream: Oid) -> Result<SyncAction, String> {
+    let head = repo
+        .head()
+        .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+    let branch = repo
+        .reference_to_annotated_commit(&head)
+        .map_err(|e| format!("Failed to prepare rebase: {}", e))?;
+    let onto = repo
+        .find_annotated_commit(upstream)
+        .map_err(|e| format!("Failed to prepare rebase: {}", e))?;
+    let committer = signature(repo)?;
+    let mut rebase = repo
+        .rebase(Some(&branch), Some(&onto), None, None)
+        .map_err(|e| format!("Failed to start rebase: {}", e))?;
+
+    let mut commits = 0;
+
+    while let Some(operation) = rebase.next() {
+        if let Err(e) = operation {
+            let _ = rebase.abort();
+            return Err(format!("Rebase failed: {}", e));
+        }
+
+        let has_conflicts = repo
+            .index()
+            .map(|index| index.has_conflicts())
+            .unwrap_or(true);
+        if has_conflicts {
+            let _ = rebase.abort();
+            return Ok(SyncAction::Blocked {
+                reason: "rebasing local commits onto upstream produced conflicts".to_string(),
+            });
+        }
+
+        match rebase.commit(None, &committer, None) {
+            Ok(_) => commits += 1,
+            // Upstream already contains this change.
+            Err(e) if e.code() == ErrorCode::Applied => {}
+            Err(e) => {
+                let _ = rebase.abort();
+                return Err(format!("Rebase failed: {}", e));
+            }
+        }
+    }
+
+    rebase
+        .finish(Some(&committer))
+        .map_err(|e| format!("Failed to finish rebase: {}", e))?;
+
+    Ok(SyncAction::Rebased { commits })
+}
+
+/// Moves the current branch to `origin/<branch>` after a fetch.
+///
+/// A clean bucket that is only behind is fast-forwarded. Anything else is
+/// resolved according to `strategy`.
+pub fn sync_to_upstream(
+    repo: &mut Repository,
+    branch: &str,
+    strategy: DivergenceStrategy,
+) -> Result<SyncOutcome, String> {
+    let old_head = head_commit_id(repo)?;
+    let upstream = upstream_commit_id(repo, branch)?;
+
+    if old_head == upstream {
+        return Ok(SyncOutcome {
+            old_head,
+            new_head: old_head,
+            action: SyncAction::UpToDate,
+            stash_conflict: false,
+        });
+    }
+
+    let dirty = has_local_edits(repo)?;
+    let ahead = match repo.graph_ahead_behind(old_head, upstream) {
+        Ok((ahead, _)) => ahead,
+        // Shallow buckets may lack the merge base. Local commits in a shallow
+        // bucket are not supported, so treat it as behind only.
+        Err(_) if repo.is_shallow() => 0,
+        Err(e) => return Err(format!("Failed to compare with upstream: {}", e)),
+    };
+
+    let blocked = |reason: String| SyncOutcome {
+        old_head,
+        new_head: old_head,
+        action: SyncAction::Blocked { reason },
+        stash_conflict: false,
+    };
+
+    if !dirty && ahead == 0 {
+        hard_reset(repo, upstream)?;
+        return Ok(SyncOutcome {
+            old_head,
+            new_head: upstream,
+            action: SyncAction::FastForward,
+            stash_conflict: false,
+        });
+    }
+
+    match strategy {
+        DivergenceStrategy::Abort => Ok(blocked(match (dirty, ahead) {
+            (true, 0) => "the bucket has uncommitted local edits".to_string(),
+            (false, n) => format!("the bucket has {} local commit(s) not in upstream", n),
+            (true, n) => format!(
+                "the bucket has uncommitted edits and {} local commit(s) not in upstream",
+                n
+            ),
+        })),
+        DivergenceStrategy::Stash if ahead > 0 => Ok(blocked(format!(
+            "the bucket has {} local commit(s); use reset or rebase",
+            ahead
+        ))),
+        DivergenceStrategy::Reset => {
+            let backup_branch = if ahead > 0 {
+                Some(create_backup_branch(repo, old_head)?)
+            } else {
+                None
+            };
+            if dirty {
+                let stasher = signature(repo)?;
+                repo.stash_save(&stasher, "rscoop: edits set aside by bucket reset", None)
+                    .map_err(|e| format!("Failed to stash local edits: {}", e))?;
+            }
+            hard_reset(repo, upstream)?;
+            Ok(SyncOutcome {
+                old_head,
+                new_head: upstream,
+                action: SyncAction::Reset {
+                    backup_branch,
+                    edits_stashed: dirty,
+                },
+                stash_conflict: false,
+            })
+        }
+        DivergenceStrategy::Stash | DivergenceStrategy::Rebase => {
+            let stasher = signature(repo)?;
+            if dirty {
+                repo.stash_save(&stasher, "rscoop: bucket update", None)
+                    .map_err(|e| format!("Failed to stash local edits: {}", e))?;
+            }
+
+            let action = if ahead == 0 {
+                hard_reset(repo, upstream).map(|_| SyncAction::FastForward)
+            } else {
+                rebase_onto(repo, upstream)
+            };
+
+            let stash_conflict = dirty && repo.stash_pop(0, None).is_err();
+            let action = action?;
+            let new_head = head_commit_id(repo)?;
+
+            Ok(SyncOutcome {
+                old_head,
+                new_head,
+                action,
+                stash_conflict,
+            })
+        }
+    }
+}
+
+/// Checks out `branch`, creating a local tracking branch from
+/// `origin/<branch>` when needed. Requires a prior fetch and refuses to run
+/// over uncommitted edits.
+pub fn switch_branch(repo: &Repository, branch: &str) -> Result<Oid, String> {
+    if has_local_edits(repo)? {
+        return Err("Cannot switch branches with uncommitted local edits".to_string());
+    }
+
+    let local_branch = match repo.find_branch(branch, BranchType::Local) {
+        Ok(existing) => existing,
+        Err(_) => {
+            let upstream = upstream_commit_id(repo, branch)?;
+            let commit = repo
+                .find_commit(upstream)
+                .map_err(|e| format!("Failed to find origin/{}: {}", branch, e))?;
+            let mut created = repo
+                .branch(branch, &commit, false)
+                .map_err(|e| format!("Failed to create branch '{}': {}", branch, e))?;
+            created
+                .set_upstream(Some(&format!("origin/{}", branch)))
+                .map_err(|e| format!("Failed to track origin/{}: {}", branch, e))?;
+            created
+        }
+    };
+
+    let refname = local_branch
+        .get()
+        .name()
+        .map_err(|e| format!("Invalid branch reference: {}", e))?
+        .to_string();
+    let target = local_branch
+        .get()
+        .peel_to_commit()
+        .map_err(|e| format!("Failed to resolve branch '{}': {}", branch, e))?;
+
+    let mut checkout_builder = CheckoutBuilder::new();
+    checkout_builder.safe();
+    repo.checkout_tree(target.as_object(), Some(&mut checkout_builder))
+        .map_err(|e| format!("Failed to check out '{}': {}", branch, e))?;
+    repo.set_head(&refname)
+        .map_err(|e| format!("Failed to switch HEAD to '{}': {}", branch, e))?;
+
+    Ok(target.id())
+}
+
+/// True when the bucket has a readable repository with a resolvable HEAD.
+pub fn is_repository_healthy(bucket_path: &Path) -> bool {
+    Repository::open(bucket_path)
+        .and_then(|repo| {
+            repo.head()
+                .and_then(|head| head.peel_to_commit())
+                .map(|_| ())
+        })
+        .is_ok()
+}
+
+/// Reads the `origin` URL straight from `.git/config` text. Used when the
+/// repository is too damaged for libgit2 to open.
+pub fn parse_origin_url(config: &str) -> Option<String> {
+    let mut in_origin = false;
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_origin = line.replace(' ', "") == "[remote\"origin\"]";
+            continue;
+        }
+        if !in_origin {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            if key.trim() == "url" {
+                let url = value.trim();
+                if !url.is_empty() {
+                    return Some(url.to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Best-effort origin URL for a bucket, falling back to parsing the config
+/// file when the repository cannot be opened.
+pub fn origin_url(bucket_path: &Path) -> Option<String> {
+    if let Ok(repo) = Repository::open(bucket_path) {
+        if let Ok(remote) = repo.find_remote("origin") {
+            if let Ok(url) = remote.url() {
+                return Some(url.to_string());
+            }
+        }
+    }
+
+    fs::read_to_string(bucket_path.join(".git").join("config"))
+        .ok()
+        .and_then(|config| parse_origin_url(&config))
+}
+
+fn sibling_dir(bucket_path: &Path, suffix: &str) -> Result<PathBuf, String> {
+    let name = bucket_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid bucket path: {}", bucket_path.display()))?;
+    Ok(bucket_path.with_file_name(format!(".{}{}", name, suffix)))
+}
+
+/// Re-clones a bucket whose repository is corrupt, carrying over manifests
+/// that exist only in the old working tree.
+///
+/// `clone` performs the actual clone into the staging directory so callers
+/// can plug in progress reporting and shallow depth. Returns the manifest
+/// paths (relative to the bucket) that were preserved.
+pub fn recover_bucket<F>(bucket_path: &Path, url: &str, clone: F) -> Result<Vec<String>, String>
+where
+    F: FnOnce(&str, &Path) -> Result<(), String>,
+{
+    let staging = sibling_dir(bucket_path, RECOVER_DIR_SUFFIX)?;
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .map_err(|e| format!("Failed to clear stale recovery directory: {}", e))?;
+    }
+
+    if let Err(e) = clone(url, &staging) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    let mut preserved = Vec::new();
+    for manifest in utils::bucket_manifest_paths(bucket_path) {
+        let Ok(relative) = manifest.strip_prefix(bucket_path) else {
+            continue;
+        };
+        let destination = staging.join(relative);
+        if destination.exists() {
+            continue;
+        }
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::copy(&manifest, &destination)
+            .map_err(|e| format!("Failed to preserve {}: {}", relative.display(), e))?;
+        preserved.push(relative.to_string_lossy().replace('\\', "/"));
+    }
+
+    let broken = sibling_dir(bucket_path, BROKEN_DIR_SUFFIX)?;
+    if broken.exists() {
+        fs::remove_dir_all(&broken)
+            .map_err(|e| format!("Failed to clear stale backup directory: {}", e))?;
+    }
+    fs::rename(bucket_path, &broken)
+        .map_err(|e| format!("Failed to move damaged bucket aside: {}", e))?;
+    if let Err(e) = fs::rename(&staging, bucket_path) {
+        let _ = fs::rename(&broken, bucket_path);
+        return Err(format!("Failed to move recovered bucket into place: {}", e));
+    }
+    if let Err(e) = fs::remove_dir_all(&broken) {
+        log::warn!(
+            "Recovered bucket but could not remove '{}': {}",
+            broken.display(),
+            e
+        );
+    }
+
+    preserved.sort();
+    Ok(preserved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use git2::{IndexAddOption, RepositoryInitOptions};
+
+    /// A bare "remote" plus a working "upstream" clone that pushes to it.
+    struct Remote {
+        temp: TempDir,
+        upstream: Repository,
+        url: String,
+    }
+
+    fn test_signature() -> Signature<'static> {
+        Signature::now("rscoop", "rscoop@example.com").unwrap()
+    }
+
+    fn write(root: &Path, relative: &str, contents: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn read(root: &Path, relative: &str) -> String {
+        fs::read_to_string(root.join(relative)).unwrap()
+    }
+
+    fn commit_all(repo: &Repository, message: &str) -> Oid {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+        let sig = test_signature();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    fn push(repo: &Repository, branch: &str) {
+        let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch);
+        repo.find_remote("origin")
+            .unwrap()
+            .push(&[refspec.as_str()], None)
+            .unwrap();
+    }
+
+    fn fetch(repo: &Repository) {
+        repo.find_remote("origin")
+            .unwrap()
+            .fetch(&[] as &[&str], None, None)
+            .unwrap();
+    }
+
+    fn remote(name: &str) -> Remote {
+        let temp = TempDir::new(name);
+        let remote_path = temp.path.join("remote.git");
+        let mut bare = RepositoryInitOptions::new();
+        bare.bare(true).initial_head("main");
+        Repository::init_opts(&remote_path, &bare).unwrap();
+
+        let mut working = RepositoryInitOptions::new();
+        working.initial_head("main");
+        let upstream = Repository::init_opts(temp.path.join("upstream"), &working).unwrap();
+        let url = remote_path.to_string_lossy().to_string();
+        upstream.remote("origin", &url).unwrap();
+
+        let root = upstream.workdir().unwrap().to_path_buf();
+        write(&root, "bucket/app.json", r#"{"version":"1.0"}"#);
+        write(&root, "bucket/tool.json", r#"{"version":"1.0"}"#);
+        commit_all(&upstream, "initial");
+        push(&upstream, "main");
+
+        Remote {
+            temp,
+            upstream,
+            url,
+        }
+    }
+
+    impl Remote {
+        fn root(&self) -> PathBuf {
+            self.upstream.workdir().unwrap().to_path_buf()
+        }
+
+        fn clone_bucket(&self, name: &str) -> Repository {
+            Repository::clone(&self.url, self.temp.path.join("buckets").join(name)).unwrap()
+        }
+
+        fn publish(&self, relative: &str, contents: &str) -> Oid {
+            write(&self.root(), relative, contents);
+            let id = commit_all(&self.upstream, "upstream change");
+            push(&self.upstream, "main");
+            id
+        }
+    }
+
+    #[test]
+    fn clean_bucket_fast_forwards() {
+        let remote = remote("sync-ff");
+        let mut bucket = remote.clone_bucket("main");
+        let target = remote.publish("bucket/app.json", r#"{"version":"2.0"}"#);
+        fetch(&bucket);
+
+        let outcome = sync_to_upstream(&mut bucket, "main", DivergenceStrategy::Abort).unwrap();
+
+        assert_eq!(outcome.action, SyncAction::FastForward);
+        assert_eq!(outcome.new_head, target);
+        let workdir = bucket.workdir().unwrap().to_path_buf();
+        assert_eq!(read(&workdir, "bucket/app.json"), r#"{"version":"2.0"}"#);
+    }
+
+    #[test]
+    fn abort_leaves_dirty_bucket_untouched() {
+        let remote = remote("sync-abort");
+        let mut bucket = remote.clone_bucket("main");
+        let workdir = bucket.workdir().unwrap().to_path_buf();
+        write(&workdir, "bucket/app.json", r#"{"version":"local"}"#);
+        remote.publish("bucket/tool.json", r#"{"version":"2.0"}"#);
+        fetch(&bucket);
+
+        let outcome = sync_to_upstream(&mut bucket, "main", DivergenceStrategy::Abort).unwrap();
+
+        assert!(matches!(outcome.action, SyncAction::Blocked { .. }));
+        assert!(!outcome.moved());
+        assert_eq!(read(&workdir, "bucket/app.json"), r#"{"version":"local"}"#);
+        assert_eq!(read(&workdir, "bucket/tool.json"), r#"{"version":"1.0"}"#);
+    }
+
+    #[test]
+    fn stash_reapplies_local_edits_after_update() {
+        let remote = remote("sync-stash");
+        let mut bucket = remote.clone_bucket("main");
+        let workdir = bucket.workdir().unwrap().to_path_buf();
+        write(&workdir, "bucket/app.json", r#"{"version":"local"}"#);
+        remote.publish("bucket/tool.json", r#"{"version":"2.0"}"#);
+        fetch(&bucket);
+
+        let outcome = sync_to_upstream(&mut bucket, "main", DivergenceStrategy::Stash).unwrap();
+
+        assert_eq!(outcome.action, SyncAction::FastForward);
+        assert!(!outcome.stash_conflict);
+        assert_eq!(read(&workdir, "bucket/app.json"), r#"{"version":"local"}"#);
+        assert_eq!(read(&workdir, "bucket/tool.json"), r#"{"version":"2.0"}"#);
+    }
+
+    #[test]
+    fn reset_keeps_local_commits_on_backup_branch() {
+        let remote = remote("sync-reset");
+        let mut bucket = remote.clone_bucket("main");
+        let workdir = bucket.workdir().unwrap().to_path_buf();
+        write(&workdir, "bucket/local.json", r#"{"version":"0.1"}"#);
+        let local_commit = commit_all(&bucket, "local change");
+        let target = remote.publish("bucket/tool.json", r#"{"version":"2.0"}"#);
+        fetch(&bucket);
+
+        let outcome = sync_to_upstream(&mut bucket, "main", DivergenceStrategy::Reset).unwrap();
+
+        assert_eq!(outcome.new_head, target);
+        let SyncAction::Reset {
+            backup_branch: Some(backup),
+            ..
+        } = outcome.action
+        else {
+            panic!("expected a reset with backup, got {:?}", outcome.action);
+        };
+        let backup_target = bucket
+            .find_branch(&backup, BranchType::Local)
+            .unwrap()
+            .get()
+            .target();
+        assert_eq!(backup_target, Some(local_commit));
+        assert!(!workdir.join("bucket/local.json").exists());
+    }
+
+    #[test]
+    fn reset_keeps_local_edits_in_the_stash() {
+        let remote = remote("sync-reset-edits");
+        let mut bucket = remote.clone_bucket("main");
+        let workdir = bucket.workdir().unwrap().to_path_buf();
+        write(&workdir, "bucket/app.json", r#"{"version":"local"}"#);
+        let target = remote.publish("bucket/tool.json", r#"{"version":"2.0"}"#);
+        fetch(&bucket);
+
+        let outcome = sync_to_upstream(&mut bucket, "main", DivergenceStrategy::Reset).unwrap();
+
+        assert_eq!(outcome.new_head, target);
+        assert_eq!(
+            outcome.action,
+            SyncAction::Reset {
+                backup_branch: None,
+                edits_stashed: true,
+            }
+        );
+        assert_ne!(read(&workdir, "bucket/app.json"), r#"{"version":"local"}"#);
+        let mut stashes = Vec::new();
+        bucket
+            .stash_foreach(|_, message, _| {
+                stashes.push(message.to_string());
+                true
+            })
+            .unwrap();
+        assert_eq!(stashes.len(), 1);
+    }
+
+    #[test]
+    fn rebase_replays_local_commits_on_upstream() {
+        let remote = remote("sync-rebase");
+        let mut bucket = remote.clone_bucket("main");
+        let workdir = bucket.workdir().unwrap().to_path_buf();
+        write(&workdir, "bucket/local.json", r#"{"version":"0.1"}"#);
+        commit_all(&bucket, "local change");
+        let target = remote.publish("bucket/tool.json", r#"{"version":"2.0"}"#);
+        fetch(&bucket);
+
+        let outcome = sync_to_upstream(&mut bucket, "main", DivergenceStrategy::Rebase).unwrap();
+
+        assert_eq!(outcome.action, SyncAction::Rebased { commits: 1 });
+        let head = bucket.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.parent_id(0).unwrap(), target);
+        assert_eq!(read(&workdir, "bucket/local.json"), r#"{"version":"0.1"}"#);
+        assert_eq!(read(&workdir, "bucket/tool.json"), r#"{"version":"2.0"}"#);
+        assert_eq!(bucket.head().unwrap().shorthand().unwrap(), "main");
+    }
+
+    #[test]
+    fn switch_branch_tracks_remote_branch() {
+        let remote = remote("sync-switch");
+        let mut bucket = remote.clone_bucket("main");
+
+        let upstream_commit = remote.upstream.head().unwrap().peel_to_commit().unwrap();
+        remote
+            .upstream
+            .branch("develop", &upstream_commit, false)
+            .unwrap();
+        remote.upstream.set_head("refs/heads/develop").unwrap();
+        let develop = remote.publish("bucket/dev.json", r#"{"version":"0.9"}"#);
+        push(&remote.upstream, "develop");
+        fetch(&bucket);
+
+        let head = switch_branch(&bucket, "develop").unwrap();
+
+        assert_eq!(head, develop);
+        assert_eq!(bucket.head().unwrap().shorthand().unwrap(), "develop");
+        let workdir = bucket.workdir().unwrap().to_path_buf();
+        assert!(workdir.join("bucket/dev.json").exists());
+
+        let outcome = sync_to_upstream(&mut bucket, "develop", DivergenceStrategy::Abort).unwrap();
+        assert_eq!(outcome.action, SyncAction::UpToDate);
+    }
+
+    #[test]
+    fn recover_reclones_and_keeps_local_only_manifests() {
+        let remote = remote("sync-recover");
+        let bucket = remote.clone_bucket("main");
+        let bucket_path = bucket.workdir().unwrap().to_path_buf();
+        drop(bucket);
+
+        write(&bucket_path, "bucket/private.json", r#"{"version":"1.0"}"#);
+        fs::write(bucket_path.join(".git").join("HEAD"), "garbage").unwrap();
+        assert!(!is_repository_healthy(&bucket_path));
+        assert_eq!(
+            origin_url(&bucket_path).as_deref(),
+            Some(remote.url.as_str())
+        );
+
+        let preserved = recover_bucket(&bucket_path, &remote.url, |url, path| {
+            Repository::clone(url, path)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .unwrap();
+
+        assert_eq!(preserved, vec!["bucket/private.json".to_string()]);
+        assert!(is_repository_healthy(&bucket_path));
+        assert!(bucket_path.join("bucket/private.json").exists());
+        assert!(bucket_path.join("bucket/app.json").exists());
+        assert!(!sibling_dir(&bucket_path, BROKEN_DIR_SUFFIX)
+            .unwrap()
+            .exists());
+    }
+
+    #[test]
+    fn parses_origin_url_from_config_text() {
+        let config = r#"
+[core]
+	bare = false
+[remote "upstream"]
+	url = https://example.com/other.git
+[remote "origin"]
+	url = https://github.com/ScoopInstaller/Extras
+	fetch = +refs/heads/*:refs/remotes/origin/*
+"#;
+        assert_eq!(
+            parse_origin_url(config).as_deref(),
+            Some("https://github.com/ScoopInstaller/Extras")
+        );
+        assert_eq!(parse_origin_url("[core]\n\tbare = false\n"), None);
+    }
+}
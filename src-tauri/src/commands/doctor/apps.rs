@@ -0,0 +1,86 @@
+//! Installed apps and their manifests, as the doctor checks need them.
+use crate::manifest::Manifest;
+use crate::models::InstallManifest;
+use rayon::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An app under `apps/` or `global/apps/` with a readable `current` manifest.
+#[derive(Debug, Clone)]
+pub struct InstalledApp {
+    pub name: String,
+    pub version: String,
+    /// Bucket from `install.json`; `None` for versioned or manual installs.
+    pub bucket: Option<String>,
+    /// Architecture from `install.json`, or Scoop's `64bit` default when it
+    /// is not recorded.
+    pub architecture: String,
+    pub is_global: bool,
+    /// The `apps/<name>/current` directory.
+    pub current_dir: PathBuf,
+    pub manifest: Manifest,
+}
+
+/// The local and global apps directories, local first.
+pub fn apps_dirs(scoop_path: &Path) -> [(PathBuf, bool); 2] {
+    [
+        (scoop_path.join("apps"), false),
+        (scoop_path.join("global").join("apps"), true),
+    ]
+}
+
+/// Key for comparing paths the way Windows does: case-insensitively, with
+/// either separator and no trailing one.
+pub(crate) fn path_key(path: &str) -> String {
+    path.trim()
+        .replace('/', "\\")
+        .trim_end_matches('\\')
+        .to_lowercase()
+}
+
+fn load_app(app_dir: &Path, is_global: bool) -> Option<InstalledApp> {
+    let name = app_dir.file_name()?.to_str()?.to_string();
+    let current_dir = app_dir.join("current");
+    let manifest = Manifest::from_path(&current_dir.join("manifest.json")).ok()?;
+    let install = fs::read_to_string(current_dir.join("install.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<InstallManifest>(&content).ok())
+        .unwrap_or_default();
+
+    Some(InstalledApp {
+        name,
+        version: manifest.version.clone(),
+        bucket: install.bucket,
+        architecture: install.architecture.unwrap_or_else(|| "64bit".to_string()),
+        is_global,
+        current_dir,
+        manifest,
+    })
+}
+
+/// Every installed app, local apps first, each group sorted by name.
+pub fn installed_apps(scoop_path: &Path) -> Vec<InstalledApp> {
+    let mut apps = Vec::new();
+    for (dir, is_global) in apps_dirs(scoop_path) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let app_dirs: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        let mut group: Vec<InstalledApp> = app_dirs
+            .par_iter()
+            .filter_map(|path| load_app(path, is_global))
+            .collect();
+        group.sort_by_key(|app| app.name.to_lowercase());
+        apps.extend(group);
+    }
+    apps
+}
+
+/// The installed app `name`, preferring a local install over a global one.
+pub fn find_app<'a>(apps: &'a [InstalledApp], name: &str) -> Option<&'a InstalledApp> {
+    apps.iter().find(|app| app.name.eq_ignore_ascii_case(name))
+}
@@ -1,5 +1,6 @@
 //! Commands for managing the Scoop cache.
-use crate::commands::installed::get_installed_packages_full;
+use crate::commands::installed::{get_installed_packages_full, resolved_root};
+use crate::models::ScoopPackage;
 use crate::state::AppState;
 use rayon::prelude::*;
 use serde::Serialize;
@@ -70,7 +71,15 @@ pub async fn list_cache_contents<R: Runtime>(
 ) -> Result<Vec<CacheEntry>, String> {
     log::info!("Listing cache contents from filesystem with version-awareness");
 
-    let scoop_path = state.scoop_path();
+    resolved_root(app, &state).await.cache_entries().await
+}
+
+/// Reads the cache directory under `scoop_path`, marking downloads of
+/// versioned installs in `installed` as unsafe to delete.
+pub fn cache_entries(
+    scoop_path: &Path,
+    installed: &[ScoopPackage],
+) -> Result<Vec<CacheEntry>, String> {
     let cache_path = scoop_path.join("cache");
 
     if !cache_path.is_dir() {
@@ -78,9 +87,8 @@ pub async fn list_cache_contents<R: Runtime>(
         return Ok(vec![]);
     }
 
-    // Get all installed packages to identify versioned installs
-    let installed_packages = get_installed_packages_full(app, state).await?;
-    let versioned_packages: HashSet<String> = installed_packages
+    // Identify versioned installs, whose cached downloads must be kept.
+    let versioned_packages: HashSet<String> = installed
         .iter()
         .filter(|pkg| pkg.is_versioned_install)
         .map(|pkg| pkg.name.clone())
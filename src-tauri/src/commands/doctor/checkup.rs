@@ -4,6 +4,7 @@
 //! We are grateful to the SFSU team for their original work and logic.
 //! Original source: https://github.com/winpax/sfsu/blob/trunk/src/commands/checkup.rs
 
+use crate::commands::bucket_health;
 use crate::state::AppState;
 use execra::tauri::ExecraExt;
 use serde::Serialize;
@@ -65,7 +66,10 @@ async fn check_git_installed(app: AppHandle) -> CheckupItem {
         .label("Checking Git")
         .await
         .is_success();
+    git_item(git_installed)
+}
 
+fn git_item(git_installed: bool) -> CheckupItem {
     CheckupItem {
         id: None,
         status: git_installed,
@@ -148,6 +152,80 @@ fn check_missing_helpers(scoop_path: &Path) -> Vec<CheckupItem> {
         .collect()
 }
 
+/// Reports locally computed health for each installed bucket. Host probing
+/// needs the network and is left to the bucket page.
+fn check_bucket_health(scoop_path: &Path) -> Vec<CheckupItem> {
+    let buckets = bucket_health::installed_buckets(&scoop_path.join("buckets"));
+
+    bucket_health::compute_health(&buckets, chrono::Utc::now())
+        .into_iter()
+        .map(|health| {
+            let mut issues = Vec::new();
+            if health.is_stale {
+                if let Some(days) = health.days_since_last_commit {
+                    issues.push(format!("no upstream commits for {} days", days));
+                }
+            }
+            if !health.malformed_manifests.is_empty() {
+                issues.push(format!(
+                    "{} malformed manifest(s): {}",
+                    health.malformed_manifests.len(),
+                    health.malformed_manifests.join(", ")
+                ));
+            }
+            if health.unusable_checkvers > 0 {
+                issues.push(format!(
+                    "{} manifest(s) without a usable checkver",
+                    health.unusable_checkvers
+                ));
+            }
+            if !health.duplicate_packages.is_empty() {
+                issues.push(format!(
+                    "{} package(s) also provided by other buckets",
+                    health.duplicate_packages.len()
+                ));
+            }
+
+            // Missing checkvers and duplicates are common in healthy buckets;
+            // only staleness and broken manifests fail the check.
+            let status = !health.is_stale && health.malformed_manifests.is_empty();
+            let text = if status {
+                format!(
+                    "Bucket '{}' is healthy (score {}/100)",
+                    health.bucket_name, health.score
+                )
+            } else {
+                format!(
+                    "Bucket '{}' has problems (score {}/100): {}",
+                    health.bucket_name,
+                    health.score,
+                    issues.join("; ")
+                )
+            };
+            CheckupItem {
+                id: Some(format!("bucket-health:{}", health.bucket_name)),
+                status,
+                text,
+                suggestion: if !status {
+                    Some(format!(
+                        "Check whether the bucket is still maintained, or remove it with: scoop bucket rm {}",
+                        health.bucket_name
+                    ))
+                } else if issues.is_empty() {
+                    None
+                } else {
+                    Some(format!(
+                        "Bucket '{}': {}.",
+                        health.bucket_name,
+                        issues.join("; ")
+                    ))
+                },
+                fix: None,
+            }
+        })
+        .collect()
+}
+
 #[tauri::command]
 pub fn open_windows_settings_page(page: String) -> Result<(), String> {
     let uri = match page.as_str() {
@@ -181,27 +259,38 @@ pub async fn run_scoop_checkup(
     log::info!("Running native system checkup");
 
     let scoop_path = state.scoop_path();
+    let git_item = check_git_installed(app).await;
+    // The bucket health check parses every manifest, so keep it off the
+    // async runtime.
+    tokio::task::spawn_blocking(move || run_checks(&scoop_path, git_item))
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    // Run the async git check concurrently with the sync checks.
-    let git_check_future = check_git_installed(app);
-
-    // Run synchronous checks.
-    let mut items = vec![];
-    items.push(check_main_bucket_installed(&scoop_path));
+/// Runs every filesystem and system check against `scoop_path`. The Git check
+/// is passed in since callers run `git` through different process runners.
+pub fn run_checks(scoop_path: &Path, git_item: CheckupItem) -> Vec<CheckupItem> {
+    let mut items = vec![git_item];
+    items.push(check_main_bucket_installed(scoop_path));
 
     // Add Windows-specific checks.
     #[cfg(windows)]
     {
         items.push(windows_checks::check_windows_developer_mode());
         items.push(windows_checks::check_long_paths_enabled());
-        items.push(windows_checks::check_scoop_on_ntfs(&scoop_path));
+        items.push(windows_checks::check_scoop_on_ntfs(scoop_path));
     }
 
-    items.extend(check_missing_helpers(&scoop_path));
-
-    // Await the async check and prepend its result to the list.
-    let git_check_result = git_check_future.await;
-    items.insert(0, git_check_result);
+    items.extend(check_missing_helpers(scoop_path));
+    items.extend(check_bucket_health(scoop_path));
+    items
+}
 
-    Ok(items)
+/// The Git check for callers outside the app, running `git --version` directly.
+pub fn check_git_installed_blocking() -> CheckupItem {
+    let git_installed = std::process::Command::new("git")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success());
+    git_item(git_installed)
 }
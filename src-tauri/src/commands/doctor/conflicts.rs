@@ -0,0 +1,315 @@
+//! Doctor check for shims claimed by several packages or shadowed on PATH.
+//!
+//! Scoop keeps one shim per command name, so when two installed packages both
+//! declare `python.exe` the one installed last silently wins. A shim can also
+//! be shadowed by a directory outside Scoop that comes earlier on PATH.
+use crate::commands::doctor::apps::{self, InstalledApp};
+use crate::commands::doctor::owner::{self, command_name};
+use crate::commands::doctor::shim;
+use crate::state::AppState;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// One package's claim on a command.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShimCandidate {
+    pub package: String,
+    pub version: String,
+    pub is_global: bool,
+    /// The executable the shim would run for this package.
+    pub target: String,
+    pub args: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShimConflict {
+    pub command: String,
+    pub candidates: Vec<ShimCandidate>,
+    /// The package the shim currently runs, if it points at one of them.
+    pub winner: Option<String>,
+}
+
+/// A Scoop shim that an earlier PATH directory outside Scoop shadows.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShadowedShim {
+    pub command: String,
+    pub shims_dir: String,
+    pub shadowed_by: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShimConflictReport {
+    pub conflicts: Vec<ShimConflict>,
+    pub shadowed: Vec<ShadowedShim>,
+}
+
+/// Every command declared by an installed package's `bin` for its installed
+/// architecture, keyed by the lowercase shim name. Local and global installs
+/// are kept apart since they use different shim directories.
+pub(crate) fn declared_bins(apps: &[InstalledApp]) -> BTreeMap<(bool, String), Vec<ShimCandidate>> {
+    let mut bins: BTreeMap<(bool, String), Vec<ShimCandidate>> = BTreeMap::new();
+    for app in apps {
+        for bin in app.manifest.bins_for(&app.architecture) {
+            let candidates = bins
+                .entry((app.is_global, bin.shim_name().to_lowercase()))
+                .or_default();
+            candidates.push(ShimCandidate {
+                package: app.name.clone(),
+                version: app.version.clone(),
+                is_global: app.is_global,
+                target: app
+                    .current_dir
+                    .join(bin.path())
+                    .to_string_lossy()
+                    .to_string(),
+                args: bin.args().map(str::to_string),
+            });
+        }
+    }
+    bins
+}
+
+/// Commands declared by more than one installed package.
+pub fn find_conflicts(scoop_path: &Path, apps: &[InstalledApp]) -> Vec<ShimConflict> {
+    declared_bins(apps)
+        .into_iter()
+        .filter(|(_, candidates)| candidates.len() > 1)
+        .map(|((is_global, command), candidates)| {
+            let winner = owner::resolve_shim(scoop_path, apps, &command)
+                .filter(|resolved| resolved.is_global == is_global)
+                .and_then(|resolved| resolved.package)
+                .and_then(|package| {
+                    candidates
+                        .iter()
+                        .find(|c| c.package.eq_ignore_ascii_case(&package))
+                        .map(|c| c.package.clone())
+                });
+            ShimConflict {
+                command,
+                candidates,
+                winner,
+            }
+        })
+        .collect()
+}
+
+/// Shims that a directory listed before Scoop's shims on PATH shadows.
+/// Directories inside the Scoop root are Scoop's own and are skipped.
+pub fn find_shadowed(
+    scoop_path: &Path,
+    path_dirs: &[PathBuf],
+    path_exts: &[String],
+) -> Vec<ShadowedShim> {
+    let scoop_root = format!("{}\\", apps::path_key(&scoop_path.to_string_lossy()));
+    let mut shadowed = Vec::new();
+
+    for (shims_dir, _) in shim::shim_dirs(scoop_path) {
+        let shims_key = apps::path_key(&shims_dir.to_string_lossy());
+        let Some(position) = path_dirs
+            .iter()
+            .position(|d| apps::path_key(&d.to_string_lossy()) == shims_key)
+        else {
+            continue;
+        };
+        let earlier: Vec<&PathBuf> = path_dirs[..position]
+            .iter()
+            .filter(|dir| {
+                !format!("{}\\", apps::path_key(&dir.to_string_lossy())).starts_with(&scoop_root)
+            })
+            .collect();
+
+        for command in shim::shim_names(&shims_dir) {
+            let hit = earlier.iter().find_map(|dir| {
+                path_exts
+                    .iter()
+                    .map(|ext| dir.join(format!("{}{}", command, ext.to_lowercase())))
+                    .find(|candidate| candidate.is_file())
+            });
+            if let Some(hit) = hit {
+                shadowed.push(ShadowedShim {
+                    command,
+                    shims_dir: shims_dir.to_string_lossy().to_string(),
+                    shadowed_by: hit.to_string_lossy().to_string(),
+                });
+            }
+        }
+    }
+    shadowed
+}
+
+/// PATH directories and executable extensions of the current process.
+fn process_path() -> (Vec<PathBuf>, Vec<String>) {
+    let dirs = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    let exts = std::env::var("PATHEXT")
+        .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(str::to_string)
+        .collect();
+    (dirs, exts)
+}
+
+/// Re-points the shim for `command` at `package`'s declared `bin` entry.
+pub fn repoint(
+    scoop_path: &Path,
+    apps: &[InstalledApp],
+    command: &str,
+    package: &str,
+) -> Result<Vec<PathBuf>, String> {
+    let command = command_name(command);
+    let (is_global, candidate) = declared_bins(apps)
+        .into_iter()
+        .filter(|((_, name), _)| *name == command)
+        .find_map(|((is_global, _), candidates)| {
+            candidates
+                .into_iter()
+                .find(|c| c.package.eq_ignore_ascii_case(package))
+                .map(|c| (is_global, c))
+        })
+        .ok_or_else(|| format!("'{}' does not declare a '{}' shim.", package, command))?;
+
+    let shims_dir = shim::shim_dirs(scoop_path)
+        .into_iter()
+        .find(|(_, global)| *global == is_global)
+        .map(|(dir, _)| dir)
+        .ok_or("Shims directory not found")?;
+    shim::write_shim(
+        scoop_path,
+        &shims_dir,
+        &command,
+        Path::new(&candidate.target),
+        candidate.args.as_deref(),
+    )
+}
+
+/// Lists commands claimed by several installed packages, with the current
+/// winner, and shims shadowed by earlier PATH entries outside Scoop.
+#[tauri::command]
+pub async fn check_shim_conflicts(
+    state: State<'_, AppState>,
+) -> Result<ShimConflictReport, String> {
+    let scoop_path = state.scoop_path();
+    tokio::task::spawn_blocking(move || {
+        let apps = apps::installed_apps(&scoop_path);
+        let (path_dirs, path_exts) = process_path();
+        ShimConflictReport {
+            conflicts: find_conflicts(&scoop_path, &apps),
+            shadowed: find_shadowed(&scoop_path, &path_dirs, &path_exts),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Points the shim for `command` at the chosen package's executable.
+#[tauri::command]
+pub async fn repoint_shim(
+    state: State<'_, AppState>,
+    command: String,
+    package: String,
+) -> Result<(), String> {
+    log::info!("Re-pointing shim '{}' at package '{}'", command, package);
+    let scoop_path = state.scoop_path();
+    tokio::task::spawn_blocking(move || {
+        let apps = apps::installed_apps(&scoop_path);
+        repoint(&scoop_path, &apps, &command, &package).map(|_| ())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::fs;
+
+    /// Two Pythons that both ship `python.exe`; the shim points at `python311`.
+    fn scoop_tree() -> TempDir {
+        let temp = TempDir::new("shim-conflicts");
+        temp.write(
+            "scoop/apps/python/current/manifest.json",
+            r#"{ "version": "3.12.0", "bin": ["python.exe", ["python.exe", "py", "-3"]] }"#,
+        );
+        temp.write(
+            "scoop/apps/python311/current/manifest.json",
+            r#"{ "version": "3.11.9", "bin": "python.exe" }"#,
+        );
+        temp.write("scoop/apps/scoop/current/shim.exe", "template");
+        let target = temp.path.join("scoop/apps/python311/current/python.exe");
+        temp.write(
+            "scoop/shims/python.shim",
+            &format!("path = \"{}\"\n", target.display()),
+        );
+        temp.write("scoop/shims/python.exe", "template");
+        temp
+    }
+
+    #[test]
+    fn reports_conflicts_with_the_current_winner() {
+        let temp = scoop_tree();
+        let scoop = temp.path.join("scoop");
+        let apps = apps::installed_apps(&scoop);
+
+        let conflicts = find_conflicts(&scoop, &apps);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].command, "python");
+        let packages: Vec<&str> = conflicts[0]
+            .candidates
+            .iter()
+            .map(|c| c.package.as_str())
+            .collect();
+        assert_eq!(packages, vec!["python", "python311"]);
+        assert_eq!(conflicts[0].winner.as_deref(), Some("python311"));
+    }
+
+    #[test]
+    fn repoints_shim_at_chosen_package() {
+        let temp = scoop_tree();
+        let scoop = temp.path.join("scoop");
+        let apps = apps::installed_apps(&scoop);
+
+        repoint(&scoop, &apps, "python", "python").unwrap();
+        let conflicts = find_conflicts(&scoop, &apps);
+        assert_eq!(conflicts[0].winner.as_deref(), Some("python"));
+
+        // Alias tuples keep their arguments.
+        let written = repoint(&scoop, &apps, "py", "python").unwrap();
+        assert!(written.iter().any(|p| p.ends_with("py.exe")));
+        let shim = fs::read_to_string(scoop.join("shims/py.shim")).unwrap();
+        assert!(shim.contains("args = -3"));
+
+        assert!(repoint(&scoop, &apps, "python", "ruby").is_err());
+    }
+
+    #[test]
+    fn flags_shims_shadowed_by_earlier_path_entries() {
+        let temp = scoop_tree();
+        let scoop = temp.path.join("scoop");
+        temp.write("windowsapps/python.exe", "");
+        temp.write("scoop/apps/python/current/Scripts/python.exe", "");
+
+        let path = vec![
+            temp.path.join("scoop/apps/python/current/Scripts"),
+            temp.path.join("windowsapps"),
+            scoop.join("shims"),
+        ];
+        let exts = vec![".EXE".to_string(), ".CMD".to_string()];
+        let shadowed = find_shadowed(&scoop, &path, &exts);
+        assert_eq!(shadowed.len(), 1);
+        assert_eq!(shadowed[0].command, "python");
+        assert!(shadowed[0].shadowed_by.contains("windowsapps"));
+
+        // Nothing is shadowed once Scoop's shims come first.
+        let path = vec![scoop.join("shims"), temp.path.join("windowsapps")];
+        assert!(find_shadowed(&scoop, &path, &exts).is_empty());
+    }
+}
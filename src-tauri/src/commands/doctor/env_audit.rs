@@ -0,0 +1,682 @@
+//! Audit of the PATH entries and variables Scoop manages in the environment.
+//!
+//! Installed manifests declare `env_add_path` and `env_set`; Scoop also puts
+//! its shims directory on PATH. The expected state is compared with what the
+//! user (local apps) and machine (global apps) environments actually hold.
+//! Uninstalls that fail halfway often leave entries pointing into
+//! `apps/<removed>` behind.
+//!
+//! Everything except reading and writing the registry is platform-neutral.
+use crate::commands::doctor::apps::{self, InstalledApp};
+use crate::commands::doctor::owner;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+#[cfg(windows)]
+use winreg::{enums::*, RegKey};
+
+/// Which environment block an entry lives in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum EnvScope {
+    /// `HKCU\Environment`, used by local installs.
+    User,
+    /// The system environment, used by global installs.
+    Machine,
+}
+
+impl EnvScope {
+    fn of(is_global: bool) -> Self {
+        if is_global {
+            EnvScope::Machine
+        } else {
+            EnvScope::User
+        }
+    }
+}
+
+/// One scope's environment: PATH split into entries, and the other variables.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvState {
+    pub path: Vec<String>,
+    pub vars: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EnvIssueKind {
+    /// A PATH entry into the apps directory of a removed app, or a directory
+    /// that no longer exists.
+    StalePath,
+    /// The same directory is on PATH more than once.
+    DuplicatePath,
+    /// A directory an installed manifest adds to PATH is not on it.
+    MissingPath,
+    /// A variable pointing into the apps directory of a removed app.
+    StaleVariable,
+    /// A variable an installed manifest sets is not set.
+    MissingVariable,
+    /// A variable an installed manifest sets holds a different value.
+    MismatchedVariable,
+}
+
+/// A change to one scope's environment.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum EnvFix {
+    /// Removes every occurrence of the entry.
+    #[serde(rename_all = "camelCase")]
+    RemovePathEntry { scope: EnvScope, entry: String },
+    /// Keeps only the first occurrence of the entry.
+    #[serde(rename_all = "camelCase")]
+    DedupePathEntry { scope: EnvScope, entry: String },
+    /// Puts the entry at the front of PATH, as Scoop does.
+    #[serde(rename_all = "camelCase")]
+    AddPathEntry { scope: EnvScope, entry: String },
+    #[serde(rename_all = "camelCase")]
+    SetVariable {
+        scope: EnvScope,
+        name: String,
+        value: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    RemoveVariable { scope: EnvScope, name: String },
+}
+
+impl EnvFix {
+    fn scope(&self) -> EnvScope {
+        match self {
+            EnvFix::RemovePathEntry { scope, .. }
+            | EnvFix::DedupePathEntry { scope, .. }
+            | EnvFix::AddPathEntry { scope, .. }
+            | EnvFix::SetVariable { scope, .. }
+            | EnvFix::RemoveVariable { scope, .. } => *scope,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvIssue {
+    pub scope: EnvScope,
+    pub kind: EnvIssueKind,
+    /// The PATH entry or variable name.
+    pub name: String,
+    /// The app the entry belongs or belonged to, if known.
+    pub package: Option<String>,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    pub fix: EnvFix,
+}
+
+/// What installed manifests put into one scope's environment.
+#[derive(Debug, Clone, Default)]
+struct ExpectedEnv {
+    /// PATH entries with the app adding them; `None` for Scoop's shims.
+    path: Vec<(PathBuf, Option<String>)>,
+    /// Variables by lowercase name: the declared name, value and app.
+    vars: BTreeMap<String, (String, String, String)>,
+}
+
+/// Expands `%NAME%` references from the scope's own variables, then the
+/// process environment. Unknown references are left as they are.
+fn expand(value: &str, vars: &BTreeMap<String, String>) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        let resolved = vars
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.clone())
+            .or_else(|| std::env::var(name).ok());
+        out.push_str(&rest[..start]);
+        match resolved {
+            Some(value) if !name.is_empty() => out.push_str(&value),
+            _ => out.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Fills in the variables Scoop expands in `env_set` values.
+fn expand_env_set(value: &str, app: &InstalledApp, scoop_path: &Path) -> String {
+    let app_dir = app.current_dir.parent().unwrap_or(&app.current_dir);
+    let persist_root = if app.is_global {
+        scoop_path.join("global").join("persist")
+    } else {
+        scoop_path.join("persist")
+    };
+    [
+        ("$persist_dir", persist_root.join(&app.name)),
+        ("$original_dir", app_dir.join(&app.version)),
+        ("$dir", app.current_dir.clone()),
+    ]
+    .iter()
+    .fold(value.to_string(), |value, (var, path)| {
+        value.replace(var, &path.to_string_lossy())
+    })
+    .replace("$version", &app.version)
+    .replace("$app", &app.name)
+}
+
+fn expected_env(scoop_path: &Path, apps: &[InstalledApp], scope: EnvScope) -> ExpectedEnv {
+    let mut expected = ExpectedEnv::default();
+    let scoped: Vec<&InstalledApp> = apps
+        .iter()
+        .filter(|app| EnvScope::of(app.is_global) == scope)
+        .collect();
+    // Scoop only touches the machine environment for global installs.
+    if scope == EnvScope::User || !scoped.is_empty() {
+        let shims = match scope {
+            EnvScope::User => scoop_path.join("shims"),
+            EnvScope::Machine => scoop_path.join("global").join("shims"),
+        };
+        expected.path.push((shims, None));
+    }
+    for app in scoped {
+        for dir in owner::env_paths(app) {
+            expected.path.push((dir, Some(app.name.clone())));
+        }
+        for (name, value) in app.manifest.env_vars(&app.architecture) {
+            expected.vars.entry(name.to_lowercase()).or_insert_with(|| {
+                (
+                    name.to_string(),
+                    expand_env_set(value, app, scoop_path),
+                    app.name.clone(),
+                )
+            });
+        }
+    }
+    expected
+}
+
+/// The app whose directory under one of Scoop's `apps` folders `path` is in.
+fn app_of_path(scoop_path: &Path, path: &str) -> Option<String> {
+    let normalized = path.replace('/', "\\");
+    apps::apps_dirs(scoop_path).iter().find_map(|(dir, _)| {
+        let prefix = format!("{}\\", apps::path_key(&dir.to_string_lossy()));
+        let head = normalized.get(..prefix.len())?;
+        if head.to_lowercase() != prefix {
+            return None;
+        }
+        normalized[prefix.len()..]
+            .split('\\')
+            .next()
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+    })
+}
+
+fn is_installed(apps: &[InstalledApp], name: &str) -> bool {
+    apps::find_app(apps, name).is_some()
+}
+
+/// Compares one scope's actual environment with what installed manifests expect.
+pub fn audit_scope(
+    scoop_path: &Path,
+    apps: &[InstalledApp],
+    scope: EnvScope,
+    actual: &EnvState,
+) -> Vec<EnvIssue> {
+    let expected = expected_env(scoop_path, apps, scope);
+    let mut issues = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut reported: HashSet<String> = HashSet::new();
+
+    for entry in actual.path.iter().filter(|entry| !entry.trim().is_empty()) {
+        let expanded = expand(entry, &actual.vars);
+        let key = apps::path_key(&expanded);
+        if !seen.insert(key.clone()) {
+            if reported.insert(key) {
+                issues.push(EnvIssue {
+                    scope,
+                    kind: EnvIssueKind::DuplicatePath,
+                    name: entry.clone(),
+                    package: app_of_path(scoop_path, &expanded),
+                    expected: None,
+                    actual: Some(entry.clone()),
+                    fix: EnvFix::DedupePathEntry {
+                        scope,
+                        entry: entry.clone(),
+                    },
+                });
+            }
+            continue;
+        }
+        let Some(package) = app_of_path(scoop_path, &expanded) else {
+            continue;
+        };
+        let declared = expected
+            .path
+            .iter()
+            .any(|(dir, _)| apps::path_key(&dir.to_string_lossy()) == key);
+        if declared || (is_installed(apps, &package) && Path::new(&expanded).is_dir()) {
+            continue;
+        }
+        issues.push(EnvIssue {
+            scope,
+            kind: EnvIssueKind::StalePath,
+            name: entry.clone(),
+            package: Some(package),
+            expected: None,
+            actual: Some(entry.clone()),
+            fix: EnvFix::RemovePathEntry {
+                scope,
+                entry: entry.clone(),
+            },
+        });
+    }
+
+    let mut added: HashSet<String> = HashSet::new();
+    for (dir, package) in &expected.path {
+        let entry = dir.to_string_lossy().to_string();
+        let key = apps::path_key(&entry);
+        if seen.contains(&key) || !added.insert(key) {
+            continue;
+        }
+        issues.push(EnvIssue {
+            scope,
+            kind: EnvIssueKind::MissingPath,
+            name: entry.clone(),
+            package: package.clone(),
+            expected: Some(entry.clone()),
+            actual: None,
+            fix: EnvFix::AddPathEntry { scope, entry },
+        });
+    }
+
+    for (name, value) in &actual.vars {
+        if expected.vars.contains_key(&name.to_lowercase()) {
+            continue;
+        }
+        let expanded = expand(value, &actual.vars);
+        let Some(package) = app_of_path(scoop_path, &expanded) else {
+            continue;
+        };
+        if is_installed(apps, &package) {
+            continue;
+        }
+        issues.push(EnvIssue {
+            scope,
+            kind: EnvIssueKind::StaleVariable,
+            name: name.clone(),
+            package: Some(package),
+            expected: None,
+            actual: Some(value.clone()),
+            fix: EnvFix::RemoveVariable {
+                scope,
+                name: name.clone(),
+            },
+        });
+    }
+
+    for (name, value, package) in expected.vars.values() {
+        let current = actual
+            .vars
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value);
+        let kind = match current {
+            None => EnvIssueKind::MissingVariable,
+            Some(current) if expand(current, &actual.vars) != *value => {
+                EnvIssueKind::MismatchedVariable
+            }
+            Some(_) => continue,
+        };
+        issues.push(EnvIssue {
+            scope,
+            kind,
+            name: name.clone(),
+            package: Some(package.clone()),
+            expected: Some(value.clone()),
+            actual: current.cloned(),
+            fix: EnvFix::SetVariable {
+                scope,
+                name: name.clone(),
+                value: value.clone(),
+            },
+        });
+    }
+
+    issues
+}
+
+/// Applies fixes for one scope to a copy of its environment.
+pub fn apply_fixes(state: &EnvState, scope: EnvScope, fixes: &[EnvFix]) -> EnvState {
+    let mut state = state.clone();
+    for fix in fixes.iter().filter(|fix| fix.scope() == scope) {
+        match fix {
+            EnvFix::RemovePathEntry { entry, .. } => {
+                let key = apps::path_key(entry);
+                state.path.retain(|other| apps::path_key(other) != key);
+            }
+            EnvFix::DedupePathEntry { entry, .. } => {
+                let key = apps::path_key(&expand(entry, &state.vars));
+                let vars = state.vars.clone();
+                let mut kept = false;
+                state.path.retain(|other| {
+                    if apps::path_key(&expand(other, &vars)) != key {
+                        return true;
+                    }
+                    !std::mem::replace(&mut kept, true)
+                });
+            }
+            EnvFix::AddPathEntry { entry, .. } => {
+                let key = apps::path_key(entry);
+                if !state.path.iter().any(|other| apps::path_key(other) == key) {
+                    state.path.insert(0, entry.clone());
+                }
+            }
+            EnvFix::SetVariable { name, value, .. } => {
+                state.vars.retain(|key, _| !key.eq_ignore_ascii_case(name));
+                state.vars.insert(name.clone(), value.clone());
+            }
+            EnvFix::RemoveVariable { name, .. } => {
+                state.vars.retain(|key, _| !key.eq_ignore_ascii_case(name));
+            }
+        }
+    }
+    state
+}
+
+#[cfg(windows)]
+fn env_key(scope: EnvScope, flags: u32) -> std::io::Result<RegKey> {
+    match scope {
+        EnvScope::User => {
+            RegKey::predef(HKEY_CURRENT_USER).open_subkey_with_flags("Environment", flags)
+        }
+        EnvScope::Machine => RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey_with_flags(
+            r"SYSTEM\CurrentControlSet\Control\Session Manager\Environment",
+            flags,
+        ),
+    }
+}
+
+/// Reads one scope's environment from the registry, without expanding it.
+#[cfg(windows)]
+pub fn read_env(scope: EnvScope) -> Result<EnvState, String> {
+    let key = env_key(scope, KEY_READ).map_err(|e| format!("Failed to open environment: {}", e))?;
+    let mut state = EnvState::default();
+    for (name, _) in key.enum_values().flatten() {
+        let Ok(value) = key.get_value::<String, _>(&name) else {
+            continue;
+        };
+        if name.eq_ignore_ascii_case("Path") {
+            state.path = value.split(';').map(str::to_string).collect();
+        } else {
+            state.vars.insert(name, value);
+        }
+    }
+    Ok(state)
+}
+
+#[cfg(not(windows))]
+pub fn read_env(_scope: EnvScope) -> Result<EnvState, String> {
+    Err("Reading the environment is only supported on Windows".to_string())
+}
+
+/// Writes the differences between `before` and `after` to the registry and
+/// tells running programs the environment changed.
+#[cfg(windows)]
+pub fn write_env(scope: EnvScope, before: &EnvState, after: &EnvState) -> Result<(), String> {
+    use winreg::types::ToRegValue;
+
+    let key = env_key(scope, KEY_READ | KEY_WRITE)
+        .map_err(|e| format!("Failed to open environment: {}", e))?;
+    // PATH and Scoop's variables may reference others, so keep them expandable.
+    let set_expandable = |name: &str, value: &String| {
+        let mut raw = value.to_reg_value();
+        raw.vtype = REG_EXPAND_SZ;
+        key.set_raw_value(name, &raw)
+            .map_err(|e| format!("Failed to set {}: {}", name, e))
+    };
+
+    if before.path != after.path {
+        set_expandable("Path", &after.path.join(";"))?;
+    }
+    for (name, value) in &after.vars {
+        if before.vars.get(name) != Some(value) {
+            set_expandable(name, value)?;
+        }
+    }
+    for name in before
+        .vars
+        .keys()
+        .filter(|name| !after.vars.contains_key(*name))
+    {
+        key.delete_value(name)
+            .map_err(|e| format!("Failed to remove {}: {}", name, e))?;
+    }
+
+    broadcast_environment_change();
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn write_env(_scope: EnvScope, _before: &EnvState, _after: &EnvState) -> Result<(), String> {
+    Err("Writing the environment is only supported on Windows".to_string())
+}
+
+#[cfg(windows)]
+fn broadcast_environment_change() {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        SendMessageTimeoutW, HWND_BROADCAST, SMTO_ABORTIFHUNG, WM_SETTINGCHANGE,
+    };
+
+    let area: Vec<u16> = "Environment".encode_utf16().chain(Some(0)).collect();
+    let mut result = 0;
+    unsafe {
+        SendMessageTimeoutW(
+            HWND_BROADCAST,
+            WM_SETTINGCHANGE,
+            0,
+            area.as_ptr() as isize,
+            SMTO_ABORTIFHUNG,
+            5000,
+            &mut result,
+        );
+    }
+}
+
+fn audit(scoop_path: &Path) -> Result<Vec<EnvIssue>, String> {
+    let apps = apps::installed_apps(scoop_path);
+    let mut issues = Vec::new();
+    for scope in [EnvScope::User, EnvScope::Machine] {
+        match read_env(scope) {
+            Ok(actual) => issues.extend(audit_scope(scoop_path, &apps, scope, &actual)),
+            // Non-admin users may not be able to read everything; keep going.
+            Err(e) if scope == EnvScope::Machine => {
+                log::warn!("Skipping machine environment audit: {}", e)
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(issues)
+}
+
+/// Reports stale, duplicate and missing PATH entries and Scoop-managed
+/// variables in the user and machine environments.
+#[tauri::command]
+pub async fn audit_environment(state: State<'_, AppState>) -> Result<Vec<EnvIssue>, String> {
+    let scoop_path = state.scoop_path();
+    tokio::task::spawn_blocking(move || audit(&scoop_path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Applies the chosen fixes and returns the audit that follows.
+#[tauri::command]
+pub async fn fix_environment(
+    state: State<'_, AppState>,
+    fixes: Vec<EnvFix>,
+) -> Result<Vec<EnvIssue>, String> {
+    log::info!("Applying {} environment fixes", fixes.len());
+    let scoop_path = state.scoop_path();
+    tokio::task::spawn_blocking(move || {
+        for scope in [EnvScope::User, EnvScope::Machine] {
+            if !fixes.iter().any(|fix| fix.scope() == scope) {
+                continue;
+            }
+            let before = read_env(scope)?;
+            let after = apply_fixes(&before, scope, &fixes);
+            write_env(scope, &before, &after)?;
+        }
+        audit(&scoop_path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn scoop_tree() -> TempDir {
+        let temp = TempDir::new("env-audit");
+        temp.write(
+            "apps/jdk/current/manifest.json",
+            r#"{ "version": "21", "env_add_path": "bin",
+                 "env_set": { "JAVA_HOME": "$dir", "JDK_DATA": "$persist_dir\\data" } }"#,
+        );
+        temp.write("apps/jdk/current/bin/java.exe", "");
+        temp
+    }
+
+    fn entry(path: PathBuf) -> String {
+        path.to_string_lossy().to_string()
+    }
+
+    fn kinds(issues: &[EnvIssue]) -> Vec<(EnvIssueKind, String)> {
+        issues.iter().map(|i| (i.kind, i.name.clone())).collect()
+    }
+
+    #[test]
+    fn reports_stale_duplicate_and_missing_entries() {
+        let temp = scoop_tree();
+        let apps = apps::installed_apps(&temp.path);
+        let shims = entry(temp.path.join("shims"));
+        let removed = entry(temp.path.join("apps/node/current"));
+        let actual = EnvState {
+            path: vec![
+                shims.clone(),
+                "C:\\Windows".to_string(),
+                removed.clone(),
+                format!("{}/", shims.to_uppercase()),
+            ],
+            vars: BTreeMap::from([
+                ("NODE_HOME".to_string(), removed.clone()),
+                (
+                    "JAVA_HOME".to_string(),
+                    entry(temp.path.join("apps/jdk/20")),
+                ),
+                ("EDITOR".to_string(), "vim".to_string()),
+            ]),
+        };
+
+        let issues = audit_scope(&temp.path, &apps, EnvScope::User, &actual);
+        let jdk_bin = entry(temp.path.join("apps/jdk/current").join("bin"));
+        assert_eq!(
+            kinds(&issues),
+            vec![
+                (EnvIssueKind::StalePath, removed.clone()),
+                (
+                    EnvIssueKind::DuplicatePath,
+                    format!("{}/", shims.to_uppercase())
+                ),
+                (EnvIssueKind::MissingPath, jdk_bin),
+                (EnvIssueKind::StaleVariable, "NODE_HOME".to_string()),
+                (EnvIssueKind::MismatchedVariable, "JAVA_HOME".to_string()),
+                (EnvIssueKind::MissingVariable, "JDK_DATA".to_string()),
+            ]
+        );
+        assert_eq!(issues[0].package.as_deref(), Some("node"));
+        let data = issues.last().unwrap().expected.as_deref().unwrap();
+        assert!(data.starts_with(&entry(temp.path.join("persist").join("jdk"))));
+
+        // Nothing is expected in the machine scope without global installs.
+        assert!(audit_scope(&temp.path, &apps, EnvScope::Machine, &EnvState::default()).is_empty());
+    }
+
+    #[test]
+    fn applying_every_fix_clears_the_audit() {
+        let temp = scoop_tree();
+        let apps = apps::installed_apps(&temp.path);
+        let shims = entry(temp.path.join("shims"));
+        let actual = EnvState {
+            path: vec![
+                "C:\\Windows".to_string(),
+                shims.clone(),
+                entry(temp.path.join("apps/node/current")),
+                shims.clone(),
+            ],
+            vars: BTreeMap::from([("java_home".to_string(), "old".to_string())]),
+        };
+
+        let issues = audit_scope(&temp.path, &apps, EnvScope::User, &actual);
+        let fixes: Vec<EnvFix> = issues.iter().map(|i| i.fix.clone()).collect();
+        let fixed = apply_fixes(&actual, EnvScope::User, &fixes);
+
+        assert!(audit_scope(&temp.path, &apps, EnvScope::User, &fixed).is_empty());
+        assert_eq!(
+            fixed.path[0],
+            entry(temp.path.join("apps/jdk/current").join("bin"))
+        );
+        assert_eq!(&fixed.path[1..], &["C:\\Windows".to_string(), shims]);
+        assert!(!fixed.vars.contains_key("java_home"));
+        // Fixes for another scope are left alone.
+        assert_eq!(apply_fixes(&actual, EnvScope::Machine, &fixes), actual);
+    }
+
+    #[test]
+    fn expects_only_the_installed_architecture() {
+        let temp = TempDir::new("env-audit-arch");
+        temp.write(
+            "apps/python/current/manifest.json",
+            r#"{ "version": "3.12", "env_set": { "PY_ARCH": "generic" },
+                 "architecture": {
+                     "64bit": { "env_add_path": "x64", "env_set": { "PY_ARCH": "amd64" } },
+                     "32bit": { "env_add_path": "x86", "env_set": { "PY_ARCH": "x86" } }
+                 } }"#,
+        );
+        temp.write(
+            "apps/python/current/install.json",
+            r#"{ "bucket": "main", "architecture": "32bit" }"#,
+        );
+        let apps = apps::installed_apps(&temp.path);
+        let actual = EnvState {
+            path: vec![entry(temp.path.join("shims"))],
+            vars: BTreeMap::new(),
+        };
+
+        let issues = audit_scope(&temp.path, &apps, EnvScope::User, &actual);
+        assert_eq!(
+            kinds(&issues),
+            vec![
+                (
+                    EnvIssueKind::MissingPath,
+                    entry(temp.path.join("apps/python/current").join("x86"))
+                ),
+                (EnvIssueKind::MissingVariable, "PY_ARCH".to_string()),
+            ]
+        );
+        assert_eq!(issues[1].expected.as_deref(), Some("x86"));
+    }
+
+    #[test]
+    fn expands_references_before_comparing() {
+        let vars = BTreeMap::from([("SCOOP".to_string(), "C:\\scoop".to_string())]);
+        assert_eq!(expand("%scoop%\\shims", &vars), "C:\\scoop\\shims");
+        assert_eq!(expand("%NOPE_NOT_SET%\\x", &vars), "%NOPE_NOT_SET%\\x");
+        assert_eq!(expand("50%", &vars), "50%");
+    }
+}
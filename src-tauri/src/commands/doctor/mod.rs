@@ -1,5 +1,11 @@
+pub mod apps;
 pub mod cache;
 pub mod checkup;
 pub mod cleanup;
+pub mod conflicts;
+pub mod env_audit;
+pub mod owner;
 pub mod shim;
+pub mod shim_repair;
+pub mod shortcuts;
 pub mod windows_checks;
@@ -0,0 +1,464 @@
+//! Reverse lookup: which package owns a command, shim, file or PATH entry.
+use crate::commands::doctor::apps::{self, InstalledApp};
+use crate::commands::doctor::shim;
+use crate::commands::manifest_cache::CachedManifest;
+use crate::commands::search;
+use crate::state::AppState;
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// What the lookup query was understood as.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LookupKind {
+    /// A bare command name such as `rg` or `python.exe`.
+    Command,
+    /// A file in one of Scoop's shim directories.
+    Shim,
+    File,
+    Directory,
+}
+
+/// Why a package is considered the owner.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum OwnerEvidence {
+    /// The shim points into the package's directory.
+    Shim,
+    /// The package's manifest declares the command in `bin`.
+    Bin,
+    /// The package's manifest adds the directory to PATH.
+    EnvAddPath,
+    /// The path lies inside the package's install directory.
+    InstallDir,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageOwner {
+    pub name: String,
+    pub version: String,
+    pub bucket: Option<String>,
+    pub is_global: bool,
+    pub evidence: OwnerEvidence,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ShimStatus {
+    Ok,
+    /// The shim points into an app that is no longer installed.
+    PackageNotInstalled,
+    /// The app is installed but the target file is gone.
+    TargetMissing,
+    /// The target is outside Scoop's `apps` directories.
+    External,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShimResolution {
+    pub name: String,
+    pub shim_file: String,
+    pub target: String,
+    pub package: Option<String>,
+    pub is_global: bool,
+    pub status: ShimStatus,
+}
+
+/// A package in an added bucket that provides the command but is not installed.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailablePackage {
+    pub name: String,
+    pub version: String,
+    pub bucket: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnerReport {
+    pub query: String,
+    pub kind: LookupKind,
+    pub owners: Vec<PackageOwner>,
+    pub shim: Option<ShimResolution>,
+    pub available_from: Vec<AvailablePackage>,
+}
+
+/// Strips a directory and executable extension: `bin\rg.exe` -> `rg`.
+pub(crate) fn command_name(path: &str) -> String {
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or_default();
+    let lower = file_name.to_lowercase();
+    for ext in [
+        ".exe", ".cmd", ".bat", ".ps1", ".com", ".shim", ".jar", ".py",
+    ] {
+        if let Some(stem) = lower.strip_suffix(ext) {
+            return stem.to_string();
+        }
+    }
+    lower
+}
+
+fn owner(app: &InstalledApp, evidence: OwnerEvidence) -> PackageOwner {
+    PackageOwner {
+        name: app.name.clone(),
+        version: app.version.clone(),
+        bucket: app.bucket.clone(),
+        is_global: app.is_global,
+        evidence,
+    }
+}
+
+fn push_owner(owners: &mut Vec<PackageOwner>, app: &InstalledApp, evidence: OwnerEvidence) {
+    let known = owners
+        .iter()
+        .any(|o| o.name.eq_ignore_ascii_case(&app.name) && o.is_global == app.is_global);
+    if !known {
+        owners.push(owner(app, evidence));
+    }
+}
+
+/// Resolves the shim `name` in the first shim directory that has it.
+pub(crate) fn resolve_shim(
+    scoop_path: &Path,
+    apps: &[InstalledApp],
+    name: &str,
+) -> Option<ShimResolution> {
+    shim::shim_dirs(scoop_path)
+        .into_iter()
+        .find_map(|(dir, is_global)| {
+            let (shim_file, target) = shim::read_shim_target(&dir, name)?;
+            let package = shim::package_of_target(&target);
+            let status = match &package {
+                None => ShimStatus::External,
+                Some(package) if apps::find_app(apps, package).is_none() => {
+                    ShimStatus::PackageNotInstalled
+                }
+                Some(_) if !Path::new(&target).exists() => ShimStatus::TargetMissing,
+                Some(_) => ShimStatus::Ok,
+            };
+            Some(ShimResolution {
+                name: name.to_string(),
+                shim_file: shim_file.to_string_lossy().to_string(),
+                target,
+                package,
+                is_global,
+                status,
+            })
+        })
+}
+
+/// The app whose install directory contains `path`, if any.
+fn app_containing<'a>(
+    scoop_path: &Path,
+    apps: &'a [InstalledApp],
+    path: &str,
+) -> Option<&'a InstalledApp> {
+    let path = apps::path_key(path);
+    apps::apps_dirs(scoop_path)
+        .into_iter()
+        .find_map(|(dir, is_global)| {
+            let prefix = format!("{}\\", apps::path_key(&dir.to_string_lossy()));
+            let rest = path.strip_prefix(&prefix)?;
+            let name = rest.split('\\').next()?;
+            apps.iter()
+                .find(|app| app.is_global == is_global && app.name.eq_ignore_ascii_case(name))
+        })
+}
+
+/// Apps whose `env_add_path` entries resolve to `dir`.
+fn apps_adding_path<'a>(
+    apps: &'a [InstalledApp],
+    dir: &str,
+) -> impl Iterator<Item = &'a InstalledApp> + 'a {
+    let dir = apps::path_key(dir);
+    apps.iter().filter(move |app| {
+        env_paths(app)
+            .iter()
+            .any(|entry| apps::path_key(&entry.to_string_lossy()) == dir)
+    })
+}
+
+/// Absolute directories an app adds to PATH for its installed architecture.
+pub(crate) fn env_paths(app: &InstalledApp) -> Vec<PathBuf> {
+    app.manifest
+        .env_add_paths(&app.architecture)
+        .map(|entry| {
+            let relative: PathBuf = Path::new(entry)
+                .components()
+                .filter(|c| !matches!(c, Component::CurDir))
+                .collect();
+            if relative.is_absolute() {
+                relative
+            } else {
+                app.current_dir.join(relative)
+            }
+        })
+        .collect()
+}
+
+fn lookup_command(
+    scoop_path: &Path,
+    apps: &[InstalledApp],
+    manifests: &[CachedManifest],
+    report: &mut OwnerReport,
+) {
+    let name = command_name(&report.query);
+
+    report.shim = resolve_shim(scoop_path, apps, &name);
+    if let Some(app) = report
+        .shim
+        .as_ref()
+        .and_then(|shim| shim.package.as_deref())
+        .and_then(|package| apps::find_app(apps, package))
+    {
+        push_owner(&mut report.owners, app, OwnerEvidence::Shim);
+    }
+    for app in apps {
+        if app
+            .manifest
+            .bins()
+            .any(|bin| bin.shim_name().eq_ignore_ascii_case(&name))
+        {
+            push_owner(&mut report.owners, app, OwnerEvidence::Bin);
+        }
+    }
+
+    let mut available: Vec<AvailablePackage> = manifests
+        .iter()
+        .filter(|cached| apps::find_app(apps, &cached.package.name).is_none())
+        .filter(|cached| {
+            cached
+                .bin_strings
+                .iter()
+                .any(|bin| command_name(bin) == name)
+        })
+        .map(|cached| AvailablePackage {
+            name: cached.package.name.clone(),
+            version: cached.package.version.clone(),
+            bucket: cached.package.source.clone(),
+        })
+        .collect();
+    available.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.bucket.cmp(&b.bucket)));
+    available.dedup();
+    report.available_from = available;
+}
+
+fn lookup_path(scoop_path: &Path, apps: &[InstalledApp], report: &mut OwnerReport) {
+    let path = PathBuf::from(report.query.trim());
+    let normalized = apps::path_key(&report.query);
+
+    let shim_dir = shim::shim_dirs(scoop_path).into_iter().find(|(dir, _)| {
+        path.parent().is_some_and(|parent| {
+            apps::path_key(&parent.to_string_lossy()) == apps::path_key(&dir.to_string_lossy())
+        })
+    });
+    if shim_dir.is_some() {
+        report.kind = LookupKind::Shim;
+        // `python.exe.shimmed` is a hidden shim; the name is still `python`.
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let name = command_name(file_name.trim_end_matches(".shimmed"));
+        report.shim = resolve_shim(scoop_path, apps, &name);
+        if let Some(app) = report
+            .shim
+            .as_ref()
+            .and_then(|shim| shim.package.as_deref())
+            .and_then(|package| apps::find_app(apps, package))
+        {
+            push_owner(&mut report.owners, app, OwnerEvidence::Shim);
+        }
+        return;
+    }
+
+    report.kind = if path.is_dir() {
+        LookupKind::Directory
+    } else {
+        LookupKind::File
+    };
+    for app in apps_adding_path(apps, &normalized) {
+        push_owner(&mut report.owners, app, OwnerEvidence::EnvAddPath);
+    }
+    if let Some(app) = app_containing(scoop_path, apps, &normalized) {
+        push_owner(&mut report.owners, app, OwnerEvidence::InstallDir);
+    }
+}
+
+/// Finds the owner of `query`: a command name, or a path to a shim, file or
+/// directory (e.g. a PATH entry).
+pub fn lookup_owner(
+    scoop_path: &Path,
+    apps: &[InstalledApp],
+    manifests: &[CachedManifest],
+    query: &str,
+) -> OwnerReport {
+    let query = query.trim().trim_matches('"').to_string();
+    let mut report = OwnerReport {
+        query,
+        kind: LookupKind::Command,
+        owners: Vec::new(),
+        shim: None,
+        available_from: Vec::new(),
+    };
+    if report.query.contains(['/', '\\', ':']) {
+        lookup_path(scoop_path, apps, &mut report);
+    } else {
+        lookup_command(scoop_path, apps, manifests, &mut report);
+    }
+    report
+}
+
+/// Reports which package owns a command, shim, file or PATH directory, and
+/// flags shims whose package or target is gone.
+#[tauri::command]
+pub async fn find_package_owner<R: Runtime>(
+    app: AppHandle<R>,
+    query: String,
+) -> Result<OwnerReport, String> {
+    log::info!("Looking up the owner of '{}'", query);
+    let scoop_path = app.state::<AppState>().scoop_path();
+    let manifests = match search::cached_manifests(app.clone()).await {
+        Ok(manifests) => manifests,
+        Err(e) => {
+            log::warn!("Owner lookup without bucket manifests: {}", e);
+            Default::default()
+        }
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let apps = apps::installed_apps(&scoop_path);
+        lookup_owner(&scoop_path, &apps, &manifests, &query)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ScoopPackage;
+    use crate::test_support::TempDir;
+    use std::fs;
+
+    fn scoop_tree() -> TempDir {
+        let temp = TempDir::new("owner-lookup");
+        temp.write(
+            "apps/ripgrep/current/manifest.json",
+            r#"{ "version": "14.1.0", "bin": "rg.exe" }"#,
+        );
+        temp.write(
+            "apps/ripgrep/current/install.json",
+            r#"{ "bucket": "main" }"#,
+        );
+        temp.write("apps/ripgrep/current/rg.exe", "");
+        temp.write(
+            "apps/python/current/manifest.json",
+            r#"{ "version": "3.12.0", "bin": [["python.exe", "python3"]], "env_add_path": ["Scripts", "."] }"#,
+        );
+        temp.write("apps/python/current/Scripts/pip.exe", "");
+        let shim = |app: &str, exe: &str| {
+            format!(
+                "path = \"{}\"\n",
+                temp.path
+                    .join("apps")
+                    .join(app)
+                    .join("current")
+                    .join(exe)
+                    .display()
+            )
+        };
+        temp.write("shims/rg.shim", &shim("ripgrep", "rg.exe"));
+        temp.write("shims/python3.shim", &shim("python", "python.exe"));
+        temp.write("shims/old.shim", &shim("removed-app", "old.exe"));
+        temp
+    }
+
+    fn cached(name: &str, bucket: &str, bins: &[&str]) -> CachedManifest {
+        CachedManifest {
+            package: ScoopPackage {
+                name: name.to_string(),
+                version: "1.0".to_string(),
+                source: bucket.to_string(),
+                ..Default::default()
+            },
+            bin_strings: bins.iter().map(|b| b.to_string()).collect(),
+            description: String::new(),
+            homepage: String::new(),
+            license: String::new(),
+            shortcut_names: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn finds_command_owner_and_other_providers() {
+        let temp = scoop_tree();
+        let apps = apps::installed_apps(&temp.path);
+        let manifests = vec![
+            cached("ripgrep", "main", &["rg.exe"]),
+            cached("rg-nightly", "versions", &["bin\\rg.exe"]),
+            cached("fd", "main", &["fd.exe"]),
+        ];
+
+        let report = lookup_owner(&temp.path, &apps, &manifests, "rg.exe");
+        assert_eq!(report.kind, LookupKind::Command);
+        assert_eq!(report.owners.len(), 1);
+        assert_eq!(report.owners[0].name, "ripgrep");
+        assert_eq!(report.owners[0].bucket.as_deref(), Some("main"));
+        assert_eq!(report.owners[0].evidence, OwnerEvidence::Shim);
+        assert_eq!(report.shim.as_ref().unwrap().status, ShimStatus::Ok);
+        assert_eq!(
+            report.available_from,
+            vec![AvailablePackage {
+                name: "rg-nightly".to_string(),
+                version: "1.0".to_string(),
+                bucket: "versions".to_string(),
+            }]
+        );
+
+        // Aliased bins are found by their alias.
+        let python = lookup_owner(&temp.path, &apps, &[], "python3");
+        assert_eq!(python.owners[0].name, "python");
+    }
+
+    #[test]
+    fn flags_broken_shims() {
+        let temp = scoop_tree();
+        fs::remove_file(temp.path.join("apps/ripgrep/current/rg.exe")).unwrap();
+        let apps = apps::installed_apps(&temp.path);
+
+        let orphan = lookup_owner(&temp.path, &apps, &[], "old");
+        let shim = orphan.shim.unwrap();
+        assert_eq!(shim.package.as_deref(), Some("removed-app"));
+        assert_eq!(shim.status, ShimStatus::PackageNotInstalled);
+        assert!(orphan.owners.is_empty());
+
+        let shim_path = temp.path.join("shims").join("rg.exe");
+        let missing = lookup_owner(&temp.path, &apps, &[], &shim_path.to_string_lossy());
+        assert_eq!(missing.kind, LookupKind::Shim);
+        assert_eq!(missing.shim.unwrap().status, ShimStatus::TargetMissing);
+        assert_eq!(missing.owners[0].name, "ripgrep");
+    }
+
+    #[test]
+    fn finds_owner_of_files_and_path_entries() {
+        let temp = scoop_tree();
+        let apps = apps::installed_apps(&temp.path);
+
+        let scripts = temp.path.join("apps/python/current/Scripts");
+        let report = lookup_owner(&temp.path, &apps, &[], &scripts.to_string_lossy());
+        assert_eq!(report.kind, LookupKind::Directory);
+        assert_eq!(report.owners.len(), 1);
+        assert_eq!(report.owners[0].evidence, OwnerEvidence::EnvAddPath);
+
+        let file = temp.path.join("apps/python/current/Scripts/pip.exe");
+        let report = lookup_owner(&temp.path, &apps, &[], &file.to_string_lossy());
+        assert_eq!(report.kind, LookupKind::File);
+        assert_eq!(report.owners[0].evidence, OwnerEvidence::InstallDir);
+
+        let outside = lookup_owner(&temp.path, &apps, &[], "C:\\Windows\\System32");
+        assert!(outside.owners.is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 //! Commands for managing Scoop shims.
+use crate::commands::doctor::owner::command_name;
 use crate::state::AppState;
 use once_cell::sync::Lazy;
 use rayon::prelude::*;
@@ -13,13 +14,13 @@ use tauri::State;
 #[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct Shim {
-    name: String,
-    path: String,
-    source: String,
-    shim_type: String,
-    args: Option<String>,
-    is_global: bool,
-    is_hidden: bool,
+    pub name: String,
+    pub path: String,
+    pub source: String,
+    pub shim_type: String,
+    pub args: Option<String>,
+    pub is_global: bool,
+    pub is_hidden: bool,
 }
 
 /// Defines the arguments required for adding a new shim.
@@ -36,6 +37,9 @@ pub struct AddShimArgs {
 static PATH_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"path\s*=\s*['"](.*?)['"]"#).unwrap());
 static ARGS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"args\s*=\s*(.*)"#).unwrap());
 static SOURCE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[\\/]apps[\\/]([^\\/]+)[\\/]").unwrap());
+// First quoted path into an `apps` directory, as written in `.cmd` and `.ps1` shims.
+static SCRIPT_TARGET_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"["']([^"'\r\n]*[\\/]apps[\\/][^"'\r\n]+)["']"#).unwrap());
 
 /// Parses the content of a `.shim` file to extract the target path and arguments.
 fn parse_shim_file_content(content: &str) -> (Option<String>, Option<String>) {
@@ -50,6 +54,172 @@ fn parse_shim_file_content(content: &str) -> (Option<String>, Option<String>) {
     (path, args)
 }
 
+/// The local and global shim directories, local first.
+pub(crate) fn shim_dirs(scoop_path: &Path) -> [(PathBuf, bool); 2] {
+    [
+        (scoop_path.join("shims"), false),
+        (scoop_path.join("global").join("shims"), true),
+    ]
+}
+
+/// The package whose `apps` directory a shim target points into.
+pub(crate) fn package_of_target(target: &str) -> Option<String> {
+    SOURCE_RE
+        .captures(target)
+        .and_then(|c| c.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
+/// Reads where the shim `name` points: the `path` of its `.shim` file, or the
+/// target embedded in its `.cmd`/`.ps1` script. Returns the file read and the
+/// target path.
+pub(crate) fn read_shim_target(shims_dir: &Path, name: &str) -> Option<(PathBuf, String)> {
+    let shim_file = shims_dir.join(format!("{}.shim", name));
+    if let Ok(content) = fs::read_to_string(&shim_file) {
+        // Shims written by `add_shim` escape backslashes.
+        let target = parse_shim_file_content(&content).0?.replace("\\\\", "\\");
+        return Some((shim_file, target));
+    }
+
+    ["cmd", "ps1"].iter().find_map(|ext| {
+        let script = shims_dir.join(format!("{}.{}", name, ext));
+        let content = fs::read_to_string(&script).ok()?;
+        let target = SCRIPT_TARGET_RE.captures(&content)?.get(1)?.as_str();
+        // PowerShell shims point at `..\apps\...` relative to the shims directory.
+        let target = if Path::new(target).is_relative() {
+            shims_dir.join(target).to_string_lossy().to_string()
+        } else {
+            target.to_string()
+        };
+        Some((script, target))
+    })
+}
+
+/// Files Scoop may create for one shim, besides extensionless shell shims.
+const SHIM_FILE_EXTENSIONS: &[&str] = &["exe", "exe.shimmed", "shim", "cmd", "ps1"];
+
+/// Existing files that make up the shim `name`.
+pub(crate) fn shim_files(shims_dir: &Path, name: &str) -> Vec<PathBuf> {
+    SHIM_FILE_EXTENSIONS
+        .iter()
+        .map(|ext| shims_dir.join(format!("{}.{}", name, ext)))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Command names with a shim in `shims_dir`.
+pub(crate) fn shim_names(shims_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(shims_dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.ends_with(".shimmed"))
+        .map(|name| command_name(&name))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Scoop's `shim.exe`, copied next to each `.shim` file.
+pub(crate) fn shim_template(scoop_path: &Path) -> Option<PathBuf> {
+    let scoop_app = scoop_path.join("apps").join("scoop").join("current");
+    [
+        scoop_app
+            .join("supporting")
+            .join("shims")
+            .join("kiennq")
+            .join("shim.exe"),
+        scoop_app.join("shim.exe"),
+    ]
+    .into_iter()
+    .find(|path| path.is_file())
+}
+
+/// Writes the shim `name` for `target` the way Scoop does, replacing whatever
+/// shim had that name. Executables get a `shim.exe` plus `.shim` file, scripts
+/// a `.cmd` (and for PowerShell a `.ps1`) wrapper. A hidden shim stays hidden.
+/// Returns the files written.
+pub(crate) fn write_shim(
+    scoop_path: &Path,
+    shims_dir: &Path,
+    name: &str,
+    target: &Path,
+    args: Option<&str>,
+) -> Result<Vec<PathBuf>, String> {
+    let target_str = target.to_string_lossy();
+    let args = args.map(str::trim).filter(|a| !a.is_empty());
+    let extension = target
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let template = match extension.as_str() {
+        "exe" | "com" => Some(
+            shim_template(scoop_path)
+                .ok_or("Scoop's shim.exe template not found. Is Scoop installed correctly?")?,
+        ),
+        _ => None,
+    };
+
+    let was_hidden = shims_dir.join(format!("{}.exe.shimmed", name)).exists();
+    fs::create_dir_all(shims_dir)
+        .map_err(|e| format!("Failed to create shims directory: {}", e))?;
+    for path in shim_files(shims_dir, name) {
+        fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    }
+
+    let mut files: Vec<(PathBuf, Option<String>)> = Vec::new();
+    let args_suffix = args.map(|a| format!(" {}", a)).unwrap_or_default();
+    if let Some(template) = template {
+        let mut content = format!("path = \"{}\"\n", target_str);
+        if let Some(args) = args {
+            content.push_str(&format!("args = {}\n", args));
+        }
+        files.push((shims_dir.join(format!("{}.shim", name)), Some(content)));
+        let exe_name = if was_hidden { "exe.shimmed" } else { "exe" };
+        let exe = shims_dir.join(format!("{}.{}", name, exe_name));
+        fs::copy(&template, &exe).map_err(|e| format!("Failed to copy shim executable: {}", e))?;
+        files.push((exe, None));
+    } else if extension == "ps1" {
+        files.push((
+            shims_dir.join(format!("{}.cmd", name)),
+            Some(format!(
+                "@rem {target}\r\n@powershell -noprofile -ex unrestricted -file \"{target}\"{args} %*\r\n",
+                target = target_str,
+                args = args_suffix
+            )),
+        ));
+        files.push((
+            shims_dir.join(format!("{}.ps1", name)),
+            Some(format!(
+                "# {target}\n$path = \"{target}\"\nif ($MyInvocation.ExpectingInput) {{ $input | & $path{args} @args }} else {{ & $path{args} @args }}\nexit $LASTEXITCODE\n",
+                target = target_str,
+                args = args_suffix
+            )),
+        ));
+    } else {
+        files.push((
+            shims_dir.join(format!("{}.cmd", name)),
+            Some(format!(
+                "@rem {target}\r\n@\"{target}\"{args} %*\r\n",
+                target = target_str,
+                args = args_suffix
+            )),
+        ));
+    }
+
+    for (path, content) in &files {
+        if let Some(content) = content {
+            fs::write(path, content)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
 /// Parses a directory entry to create a `Shim` struct.
 fn parse_shim_from_entry(entry: &fs::DirEntry, is_global: bool) -> Option<Shim> {
     let path = entry.path();
@@ -120,8 +290,11 @@ fn process_shim_dir(dir: &Path, is_global: bool) -> Result<Vec<Shim>, String> {
 #[tauri::command]
 pub fn list_shims(state: State<'_, AppState>) -> Result<Vec<Shim>, String> {
     log::info!("Listing shims from filesystem");
-    let scoop_path = state.scoop_path();
+    state.root().shims()
+}
 
+/// Every shim in the local and global shim directories, sorted by name.
+pub fn all_shims(scoop_path: &Path) -> Result<Vec<Shim>, String> {
     let local_shims = process_shim_dir(&scoop_path.join("shims"), false)?;
     let global_shims = process_shim_dir(&scoop_path.join("global").join("shims"), true)?;
 
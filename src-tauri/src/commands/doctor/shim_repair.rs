@@ -0,0 +1,287 @@
+//! Repairs shims from the `bin` entries of installed manifests.
+//!
+//! Shims whose target no longer exists are removed, then every `bin` entry of
+//! every installed app that has no shim gets one, honoring `[path, alias, args]`
+//! tuples. Only the installed architecture's `bin` entries count. The same plan is reported on a dry run without touching the disk.
+use crate::commands::doctor::apps;
+use crate::commands::doctor::conflicts::declared_bins;
+use crate::commands::doctor::shim;
+use crate::state::AppState;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ShimRepairAction {
+    /// A declared `bin` had no shim; one is written.
+    Create,
+    /// The shim's target no longer exists; its files are deleted.
+    Remove,
+    /// A declared `bin` has no shim, but its target is missing too.
+    Skip,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShimRepair {
+    pub name: String,
+    pub is_global: bool,
+    pub action: ShimRepairAction,
+    /// Package the shim belongs to, if known.
+    pub package: Option<String>,
+    pub target: String,
+    pub args: Option<String>,
+    /// Files deleted or written, or that would be on a dry run.
+    pub files: Vec<String>,
+    /// Set when applying this change failed.
+    pub error: Option<String>,
+}
+
+fn shims_dir_for(scoop_path: &Path, is_global: bool) -> PathBuf {
+    shim::shim_dirs(scoop_path)
+        .into_iter()
+        .find(|(_, global)| *global == is_global)
+        .map(|(dir, _)| dir)
+        .unwrap_or_else(|| scoop_path.join("shims"))
+}
+
+/// Files `write_shim` creates for a target.
+fn planned_files(shims_dir: &Path, name: &str, target: &Path) -> Vec<String> {
+    let extension = target
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let extensions: &[&str] = match extension.as_str() {
+        "exe" | "com" => &["shim", "exe"],
+        "ps1" => &["cmd", "ps1"],
+        _ => &["cmd"],
+    };
+    extensions
+        .iter()
+        .map(|ext| {
+            shims_dir
+                .join(format!("{}.{}", name, ext))
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect()
+}
+
+fn to_strings(paths: &[PathBuf]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Works out which shims to remove and which to create.
+pub fn plan_repairs(scoop_path: &Path, installed: &[apps::InstalledApp]) -> Vec<ShimRepair> {
+    let mut plan = Vec::new();
+    let mut removed: HashSet<(bool, String)> = HashSet::new();
+
+    for (shims_dir, is_global) in shim::shim_dirs(scoop_path) {
+        for name in shim::shim_names(&shims_dir) {
+            let Some((_, target)) = shim::read_shim_target(&shims_dir, &name) else {
+                continue;
+            };
+            if Path::new(&target).exists() {
+                continue;
+            }
+            removed.insert((is_global, name.clone()));
+            plan.push(ShimRepair {
+                package: shim::package_of_target(&target),
+                files: to_strings(&shim::shim_files(&shims_dir, &name)),
+                name,
+                is_global,
+                action: ShimRepairAction::Remove,
+                target,
+                args: None,
+                error: None,
+            });
+        }
+    }
+
+    for ((is_global, name), candidates) in declared_bins(installed) {
+        let shims_dir = shims_dir_for(scoop_path, is_global);
+        let has_shim = !shim::shim_files(&shims_dir, &name).is_empty()
+            && !removed.contains(&(is_global, name.clone()));
+        if has_shim {
+            continue;
+        }
+        // When packages conflict, the first installed one (by name) gets the shim.
+        let Some(candidate) = candidates.into_iter().next() else {
+            continue;
+        };
+        let target = PathBuf::from(&candidate.target);
+        let (action, files) = if target.exists() {
+            (
+                ShimRepairAction::Create,
+                planned_files(&shims_dir, &name, &target),
+            )
+        } else {
+            (ShimRepairAction::Skip, Vec::new())
+        };
+        plan.push(ShimRepair {
+            name,
+            is_global,
+            action,
+            package: Some(candidate.package),
+            target: candidate.target,
+            args: candidate.args,
+            files,
+            error: None,
+        });
+    }
+
+    plan
+}
+
+/// Applies a plan from [`plan_repairs`], recording failures on each change.
+pub fn apply_repairs(scoop_path: &Path, plan: &mut [ShimRepair]) {
+    for change in plan.iter_mut() {
+        let shims_dir = shims_dir_for(scoop_path, change.is_global);
+        let result = match change.action {
+            ShimRepairAction::Remove => change.files.iter().try_for_each(|file| {
+                fs::remove_file(file).map_err(|e| format!("Failed to remove {}: {}", file, e))
+            }),
+            ShimRepairAction::Create => shim::write_shim(
+                scoop_path,
+                &shims_dir,
+                &change.name,
+                Path::new(&change.target),
+                change.args.as_deref(),
+            )
+            .map(|written| change.files = to_strings(&written)),
+            ShimRepairAction::Skip => Ok(()),
+        };
+        if let Err(e) = result {
+            log::warn!("Shim repair for '{}' failed: {}", change.name, e);
+            change.error = Some(e);
+        }
+    }
+}
+
+/// Removes shims with missing targets and regenerates missing shims from the
+/// installed manifests. With `dry_run`, only reports what would change.
+#[tauri::command]
+pub async fn repair_shims(
+    state: State<'_, AppState>,
+    dry_run: bool,
+) -> Result<Vec<ShimRepair>, String> {
+    log::info!("Repairing shims (dry run: {})", dry_run);
+    let scoop_path = state.scoop_path();
+    tokio::task::spawn_blocking(move || {
+        let installed = apps::installed_apps(&scoop_path);
+        let mut plan = plan_repairs(&scoop_path, &installed);
+        if !dry_run {
+            apply_repairs(&scoop_path, &mut plan);
+        }
+        plan
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn scoop_tree() -> TempDir {
+        let temp = TempDir::new("shim-repair");
+        temp.write("apps/scoop/current/shim.exe", "template");
+        temp.write(
+            "apps/tools/current/manifest.json",
+            r#"{ "version": "1.0", "bin": ["tool.exe", ["run.ps1", "runner", "--fast"], "gone.exe"] }"#,
+        );
+        temp.write("apps/tools/current/tool.exe", "");
+        temp.write("apps/tools/current/run.ps1", "");
+        // A leftover shim from an uninstalled app.
+        let stale = temp.path.join("apps/old/current/old.exe");
+        temp.write(
+            "shims/old.shim",
+            &format!("path = \"{}\"\n", stale.display()),
+        );
+        temp.write("shims/old.exe", "template");
+        temp
+    }
+
+    fn summary(plan: &[ShimRepair]) -> Vec<(String, ShimRepairAction)> {
+        let mut summary: Vec<_> = plan.iter().map(|c| (c.name.clone(), c.action)).collect();
+        summary.sort_by(|a, b| a.0.cmp(&b.0));
+        summary
+    }
+
+    #[test]
+    fn plans_removals_and_regenerations() {
+        let temp = scoop_tree();
+        let installed = apps::installed_apps(&temp.path);
+        let plan = plan_repairs(&temp.path, &installed);
+
+        assert_eq!(
+            summary(&plan),
+            vec![
+                ("gone".to_string(), ShimRepairAction::Skip),
+                ("old".to_string(), ShimRepairAction::Remove),
+                ("runner".to_string(), ShimRepairAction::Create),
+                ("tool".to_string(), ShimRepairAction::Create),
+            ]
+        );
+        let runner = plan.iter().find(|c| c.name == "runner").unwrap();
+        assert_eq!(runner.args.as_deref(), Some("--fast"));
+        assert_eq!(runner.files.len(), 2);
+        // A dry run leaves the disk alone.
+        assert!(temp.path.join("shims/old.shim").exists());
+        assert!(!temp.path.join("shims/tool.shim").exists());
+    }
+
+    #[test]
+    fn applies_plan_and_converges() {
+        let temp = scoop_tree();
+        let installed = apps::installed_apps(&temp.path);
+        let mut plan = plan_repairs(&temp.path, &installed);
+        apply_repairs(&temp.path, &mut plan);
+        assert!(plan.iter().all(|c| c.error.is_none()));
+
+        assert!(!temp.path.join("shims/old.shim").exists());
+        assert!(!temp.path.join("shims/old.exe").exists());
+        assert!(temp.path.join("shims/tool.exe").exists());
+        let cmd = fs::read_to_string(temp.path.join("shims/runner.cmd")).unwrap();
+        assert!(cmd.contains("run.ps1\" --fast %*"));
+
+        // Only the unfixable entry is left.
+        let again = plan_repairs(&temp.path, &installed);
+        assert_eq!(
+            summary(&again),
+            vec![("gone".to_string(), ShimRepairAction::Skip)]
+        );
+    }
+
+    #[test]
+    fn uses_the_installed_architectures_bins() {
+        let temp = TempDir::new("shim-repair-arch");
+        temp.write("apps/scoop/current/shim.exe", "template");
+        temp.write(
+            "apps/tool/current/manifest.json",
+            r#"{ "version": "1.0", "architecture": {
+                   "64bit": { "bin": [["tool64.exe", "tool"], "helper64.exe"] },
+                   "32bit": { "bin": [["tool32.exe", "tool"]] } } }"#,
+        );
+        temp.write(
+            "apps/tool/current/install.json",
+            r#"{ "bucket": "main", "architecture": "32bit" }"#,
+        );
+        temp.write("apps/tool/current/tool32.exe", "");
+
+        let installed = apps::installed_apps(&temp.path);
+        let plan = plan_repairs(&temp.path, &installed);
+        assert_eq!(
+            summary(&plan),
+            vec![("tool".to_string(), ShimRepairAction::Create)]
+        );
+        assert!(plan[0].target.ends_with("tool32.exe"));
+    }
+}
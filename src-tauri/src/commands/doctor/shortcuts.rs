@@ -0,0 +1,711 @@
+//! Start Menu shortcuts declared by the `shortcuts` field of installed manifests.
+//!
+//! Scoop writes one `.lnk` per manifest entry into a `Scoop Apps` folder of the
+//! Start Menu, pointing at the app's `current` directory. Links go missing when
+//! the folder is cleaned by hand, go stale when they were written against a
+//! version directory, and linger after an app is removed outside of Scoop.
+//!
+//! Planning works on plain paths with the link reader passed in, so only
+//! reading and writing the `.lnk` files themselves is Windows-specific.
+use crate::commands::doctor::apps::{self, InstalledApp};
+use crate::state::AppState;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutStatus {
+    /// The link exists under its manifest name and points at `current`.
+    Ok,
+    /// The link points at the right target but was renamed.
+    Renamed,
+    /// The manifest declares the shortcut but no link exists.
+    Missing,
+    /// The link points somewhere else, usually an old version directory.
+    Stale,
+    /// The link runs something outside `apps/`, or an app that is gone.
+    Orphan,
+    /// No declared shortcut claims the link, but it runs something inside an
+    /// app directory that still exists, e.g. an app whose manifest could not
+    /// be read. Left alone by repairs.
+    Unclaimed,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutEntry {
+    /// Name relative to `Scoop Apps`, without `.lnk`.
+    pub name: String,
+    pub package: Option<String>,
+    pub is_global: bool,
+    pub status: ShortcutStatus,
+    /// The `.lnk` file on disk, or where it should be.
+    pub link: String,
+    /// What the manifest says the link should run.
+    pub expected_target: Option<String>,
+    /// What the link on disk runs, if it could be read.
+    pub actual_target: Option<String>,
+    pub args: Option<String>,
+    pub icon: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutRepairAction {
+    /// A declared shortcut had no link; one is written.
+    Create,
+    /// A stale link is overwritten with the declared target.
+    Recreate,
+    /// An orphaned link is deleted.
+    Remove,
+    /// A declared shortcut is missing or stale, but its target is missing too.
+    Skip,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutRepair {
+    pub name: String,
+    pub package: Option<String>,
+    pub is_global: bool,
+    pub action: ShortcutRepairAction,
+    pub link: String,
+    pub target: Option<String>,
+    pub args: Option<String>,
+    pub icon: Option<String>,
+    /// Set when applying this change failed.
+    pub error: Option<String>,
+}
+
+/// What a link should contain, as written by [`write_link`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkSpec {
+    pub target: PathBuf,
+    pub args: Option<String>,
+    pub icon: Option<PathBuf>,
+}
+
+/// The `Scoop Apps` Start Menu folders, per-user first then all-users.
+pub fn start_menu_dirs() -> Vec<(PathBuf, bool)> {
+    [("APPDATA", false), ("ProgramData", true)]
+        .into_iter()
+        .filter_map(|(var, is_global)| {
+            let base = std::env::var_os(var)?;
+            let dir = PathBuf::from(base)
+                .join("Microsoft")
+                .join("Windows")
+                .join("Start Menu")
+                .join("Programs")
+                .join("Scoop Apps");
+            Some((dir, is_global))
+        })
+        .collect()
+}
+
+fn name_key(name: &str) -> String {
+    apps::path_key(name.trim_matches(|c| c == '/' || c == '\\'))
+}
+
+/// Every `.lnk` below `dir`, named relative to it without the extension.
+fn link_files(dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut links = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&current) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let is_link = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"));
+            if !is_link {
+                continue;
+            }
+            let Ok(relative) = path
+                .with_extension("")
+                .strip_prefix(dir)
+                .map(Path::to_path_buf)
+            else {
+                continue;
+            };
+            let name = relative.to_string_lossy().replace('/', "\\");
+            links.push((name, path));
+        }
+    }
+    links.sort();
+    links
+}
+
+/// Whether `name` stays inside the `Scoop Apps` folder: not rooted, and no
+/// `..` or drive-prefixed component.
+fn is_safe_name(name: &str) -> bool {
+    !name.trim().is_empty()
+        && !name.starts_with(['/', '\\'])
+        && name
+            .split(['/', '\\'])
+            .all(|part| part != ".." && !part.contains(':'))
+}
+
+/// The `.lnk` for `name` below `dir`, or `None` if the name would leave it.
+fn link_path(dir: &Path, name: &str) -> Option<PathBuf> {
+    if !is_safe_name(name) {
+        return None;
+    }
+    let mut path = dir.to_path_buf();
+    for part in name.split(['/', '\\']).filter(|part| !part.is_empty()) {
+        path.push(part);
+    }
+    path.set_extension("lnk");
+    Some(path)
+}
+
+/// Whether an unclaimed link can go: it runs something outside `apps/`, or
+/// an app directory that no longer exists. Links whose target could not be
+/// read are kept.
+fn is_orphaned(scoop_path: &Path, target: Option<&str>) -> bool {
+    let Some(target) = target else {
+        return false;
+    };
+    let target = apps::path_key(target);
+    for (dir, _) in apps::apps_dirs(scoop_path) {
+        let prefix = format!("{}\\", apps::path_key(&dir.to_string_lossy()));
+        let Some(rest) = target.strip_prefix(&prefix) else {
+            continue;
+        };
+        let app = rest.split('\\').next().unwrap_or_default();
+        let exists = fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .any(|entry| {
+                entry.path().is_dir() && apps::path_key(&entry.file_name().to_string_lossy()) == app
+            });
+        return !exists;
+    }
+    true
+}
+
+fn dir_for(dirs: &[(PathBuf, bool)], is_global: bool) -> Option<&Path> {
+    dirs.iter()
+        .find(|(_, global)| *global == is_global)
+        .map(|(dir, _)| dir.as_path())
+}
+
+/// The shortcuts installed apps declare for their installed architecture,
+/// with their `.lnk` paths and targets.
+fn declared_shortcuts<'a>(
+    apps: &'a [InstalledApp],
+    dirs: &[(PathBuf, bool)],
+) -> Vec<(&'a InstalledApp, String, PathBuf, LinkSpec)> {
+    let mut seen = HashSet::new();
+    let mut declared = Vec::new();
+    for app in apps {
+        let Some(dir) = dir_for(dirs, app.is_global) else {
+            continue;
+        };
+        for shortcut in app.manifest.shortcuts_for(&app.architecture) {
+            if shortcut.target().is_empty() || shortcut.name().is_empty() {
+                continue;
+            }
+            // The first app to claim a name keeps it.
+            if !seen.insert((app.is_global, name_key(shortcut.name()))) {
+                continue;
+            }
+            let spec = LinkSpec {
+                target: app.current_dir.join(shortcut.target()),
+                args: shortcut.args().map(str::to_string),
+                icon: shortcut.icon().map(|icon| app.current_dir.join(icon)),
+            };
+            let Some(link) = link_path(dir, shortcut.name()) else {
+                log::warn!(
+                    "Ignoring shortcut '{}' of '{}': it would leave the Scoop Apps folder",
+                    shortcut.name(),
+                    app.name
+                );
+                continue;
+            };
+            declared.push((app, shortcut.name().to_string(), link, spec));
+        }
+    }
+    declared
+}
+
+/// Checks every declared shortcut against the links on disk and flags links no
+/// installed app declares. `read_target` returns the target a `.lnk` runs.
+pub fn check_shortcuts(
+    scoop_path: &Path,
+    apps: &[InstalledApp],
+    dirs: &[(PathBuf, bool)],
+    read_target: impl Fn(&Path) -> Option<String>,
+) -> Vec<ShortcutEntry> {
+    // Links on disk, per folder, that no declared shortcut has claimed yet.
+    let mut unclaimed: Vec<(bool, String, PathBuf, Option<String>)> = dirs
+        .iter()
+        .flat_map(|(dir, is_global)| {
+            link_files(dir)
+                .into_iter()
+                .map(|(name, path)| (*is_global, name, path, None))
+        })
+        .collect();
+    for link in unclaimed.iter_mut() {
+        link.3 = read_target(&link.2);
+    }
+
+    let mut entries = Vec::new();
+    for (app, name, link, spec) in declared_shortcuts(apps, dirs) {
+        let expected = spec.target.to_string_lossy().to_string();
+        let by_name = unclaimed.iter().position(|(global, other, _, _)| {
+            *global == app.is_global && name_key(other) == name_key(&name)
+        });
+        let by_target = || {
+            unclaimed.iter().position(|(global, _, _, target)| {
+                *global == app.is_global
+                    && target.as_deref().map(apps::path_key) == Some(apps::path_key(&expected))
+            })
+        };
+
+        let (status, link, name, actual_target) = match by_name {
+            Some(index) => {
+                let (_, _, path, actual) = unclaimed.remove(index);
+                let matches =
+                    actual.as_deref().map(apps::path_key) == Some(apps::path_key(&expected));
+                let status = if matches && spec.target.exists() {
+                    ShortcutStatus::Ok
+                } else {
+                    ShortcutStatus::Stale
+                };
+                (status, path, name, actual)
+            }
+            None => match by_target() {
+                Some(index) => {
+                    let (_, other, path, actual) = unclaimed.remove(index);
+                    (ShortcutStatus::Renamed, path, other, actual)
+                }
+                None => (ShortcutStatus::Missing, link, name, None),
+            },
+        };
+
+        entries.push(ShortcutEntry {
+            name,
+            package: Some(app.name.clone()),
+            is_global: app.is_global,
+            status,
+            link: link.to_string_lossy().to_string(),
+            expected_target: Some(expected),
+            actual_target,
+            args: spec.args,
+            icon: spec.icon.map(|icon| icon.to_string_lossy().to_string()),
+        });
+    }
+
+    entries.extend(
+        unclaimed
+            .into_iter()
+            .map(|(is_global, name, path, actual)| ShortcutEntry {
+                name,
+                package: None,
+                is_global,
+                status: if is_orphaned(scoop_path, actual.as_deref()) {
+                    ShortcutStatus::Orphan
+                } else {
+                    ShortcutStatus::Unclaimed
+                },
+                link: path.to_string_lossy().to_string(),
+                expected_target: None,
+                actual_target: actual,
+                args: None,
+                icon: None,
+            }),
+    );
+    entries
+}
+
+/// Works out which links to write and which to delete from a check.
+pub fn plan_repairs(entries: &[ShortcutEntry]) -> Vec<ShortcutRepair> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let action = match entry.status {
+                ShortcutStatus::Ok | ShortcutStatus::Renamed | ShortcutStatus::Unclaimed => {
+                    return None
+                }
+                ShortcutStatus::Orphan => ShortcutRepairAction::Remove,
+                _ if !entry
+                    .expected_target
+                    .as_deref()
+                    .is_some_and(|target| Path::new(target).exists()) =>
+                {
+                    ShortcutRepairAction::Skip
+                }
+                ShortcutStatus::Missing => ShortcutRepairAction::Create,
+                ShortcutStatus::Stale => ShortcutRepairAction::Recreate,
+            };
+            Some(ShortcutRepair {
+                name: entry.name.clone(),
+                package: entry.package.clone(),
+                is_global: entry.is_global,
+                action,
+                link: entry.link.clone(),
+                target: entry.expected_target.clone(),
+                args: entry.args.clone(),
+                icon: entry.icon.clone(),
+                error: None,
+            })
+        })
+        .collect()
+}
+
+/// Applies a plan from [`plan_repairs`], recording failures on each change.
+/// `write` creates or overwrites one `.lnk`.
+pub fn apply_repairs(
+    plan: &mut [ShortcutRepair],
+    write: impl Fn(&Path, &LinkSpec) -> Result<(), String>,
+) {
+    for change in plan.iter_mut() {
+        let link = Path::new(&change.link);
+        let result = match change.action {
+            ShortcutRepairAction::Remove => fs::remove_file(link)
+                .map_err(|e| format!("Failed to remove {}: {}", change.link, e)),
+            ShortcutRepairAction::Create | ShortcutRepairAction::Recreate => {
+                let spec = LinkSpec {
+                    target: PathBuf::from(change.target.clone().unwrap_or_default()),
+                    args: change.args.clone(),
+                    icon: change.icon.as_ref().map(PathBuf::from),
+                };
+                link.parent()
+                    .map_or(Ok(()), fs::create_dir_all)
+                    .map_err(|e| format!("Failed to create {}: {}", change.link, e))
+                    .and_then(|_| write(link, &spec))
+            }
+            ShortcutRepairAction::Skip => Ok(()),
+        };
+        if let Err(e) = result {
+            log::warn!("Shortcut repair for '{}' failed: {}", change.name, e);
+            change.error = Some(e);
+        }
+    }
+}
+
+/// Renames a link inside its `Scoop Apps` folder and returns the new path.
+pub fn rename_link(
+    dirs: &[(PathBuf, bool)],
+    is_global: bool,
+    name: &str,
+    new_name: &str,
+) -> Result<PathBuf, String> {
+    let dir = dir_for(dirs, is_global).ok_or("Scoop Apps folder not found")?;
+    let invalid = |name: &str| format!("'{}' is not a valid shortcut name.", name);
+    let from = link_path(dir, name).ok_or_else(|| invalid(name))?;
+    let to = link_path(dir, new_name).ok_or_else(|| invalid(new_name))?;
+    if !from.is_file() {
+        return Err(format!("Shortcut '{}' not found.", name));
+    }
+    if to.exists() && name_key(name) != name_key(new_name) {
+        return Err(format!("Shortcut '{}' already exists.", new_name));
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::rename(&from, &to).map_err(|e| format!("Failed to rename '{}': {}", name, e))?;
+    Ok(to)
+}
+
+/// The target a `.lnk` runs, without resolving the `current` junction.
+#[cfg(windows)]
+pub fn read_link_target(path: &Path) -> Option<String> {
+    let link = lnk::ShellLink::open(path, lnk::encoding::WINDOWS_1252).ok()?;
+    if let Some(local) = link
+        .link_info()
+        .as_ref()
+        .and_then(|info| info.local_base_path().clone())
+    {
+        return Some(local.to_string());
+    }
+    let relative = link.string_data().relative_path().clone()?;
+    Some(
+        path.parent()?
+            .join(relative.to_string())
+            .to_string_lossy()
+            .to_string(),
+    )
+}
+
+#[cfg(not(windows))]
+pub fn read_link_target(_path: &Path) -> Option<String> {
+    None
+}
+
+#[cfg(windows)]
+fn ps_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Writes a `.lnk` through the `WScript.Shell` COM object, as Scoop does.
+#[cfg(windows)]
+pub fn write_link(link: &Path, spec: &LinkSpec) -> Result<(), String> {
+    let working_dir = spec.target.parent().unwrap_or(Path::new(""));
+    let mut script = format!(
+        "$s = (New-Object -ComObject WScript.Shell).CreateShortcut({}); $s.TargetPath = {}; $s.WorkingDirectory = {};",
+        ps_quote(&link.to_string_lossy()),
+        ps_quote(&spec.target.to_string_lossy()),
+        ps_quote(&working_dir.to_string_lossy()),
+    );
+    if let Some(args) = &spec.args {
+        script.push_str(&format!(" $s.Arguments = {};", ps_quote(args)));
+    }
+    if let Some(icon) = &spec.icon {
+        script.push_str(&format!(
+            " $s.IconLocation = {};",
+            ps_quote(&icon.to_string_lossy())
+        ));
+    }
+    script.push_str(" $s.Save()");
+
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+        .map_err(|e| format!("Failed to run PowerShell: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to write shortcut: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(not(windows))]
+pub fn write_link(_link: &Path, _spec: &LinkSpec) -> Result<(), String> {
+    Err("Writing shortcuts is only supported on Windows".to_string())
+}
+
+/// Lists the Start Menu shortcuts of installed apps, optionally for one
+/// package, along with orphaned links in the `Scoop Apps` folders.
+#[tauri::command]
+pub async fn list_shortcuts(
+    state: State<'_, AppState>,
+    package: Option<String>,
+) -> Result<Vec<ShortcutEntry>, String> {
+    let scoop_path = state.scoop_path();
+    tokio::task::spawn_blocking(move || {
+        let apps = apps::installed_apps(&scoop_path);
+        let entries = check_shortcuts(&scoop_path, &apps, &start_menu_dirs(), read_link_target);
+        match package {
+            Some(package) => entries
+                .into_iter()
+                .filter(|entry| {
+                    entry
+                        .package
+                        .as_deref()
+                        .is_some_and(|p| p.eq_ignore_ascii_case(&package))
+                })
+                .collect(),
+            None => entries,
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Recreates missing and stale shortcuts and removes orphaned ones. With
+/// `dry_run`, only reports what would change.
+#[tauri::command]
+pub async fn repair_shortcuts(
+    state: State<'_, AppState>,
+    dry_run: bool,
+) -> Result<Vec<ShortcutRepair>, String> {
+    log::info!("Repairing shortcuts (dry run: {})", dry_run);
+    let scoop_path = state.scoop_path();
+    tokio::task::spawn_blocking(move || {
+        let apps = apps::installed_apps(&scoop_path);
+        let entries = check_shortcuts(&scoop_path, &apps, &start_menu_dirs(), read_link_target);
+        let mut plan = plan_repairs(&entries);
+        if !dry_run {
+            apply_repairs(&mut plan, write_link);
+        }
+        plan
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Renames a shortcut within its `Scoop Apps` folder.
+#[tauri::command]
+pub async fn rename_shortcut(
+    is_global: bool,
+    name: String,
+    new_name: String,
+) -> Result<String, String> {
+    log::info!("Renaming shortcut '{}' to '{}'", name, new_name);
+    tokio::task::spawn_blocking(move || {
+        rename_link(&start_menu_dirs(), is_global, &name, &new_name)
+            .map(|path| path.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Test links are plain files holding their target.
+    fn read_fake(path: &Path) -> Option<String> {
+        fs::read_to_string(path).ok()
+    }
+
+    fn scoop_tree() -> (TempDir, Vec<(PathBuf, bool)>) {
+        let temp = TempDir::new("shortcuts");
+        temp.write(
+            "scoop/apps/editor/current/manifest.json",
+            r#"{ "version": "2.0", "shortcuts": [
+                ["editor.exe", "Editor"],
+                ["tools/cli.exe", "Editor Tools/Editor CLI", "--new", "cli.ico"],
+                ["gone.exe", "Gone"]
+            ] }"#,
+        );
+        temp.write("scoop/apps/editor/current/editor.exe", "");
+        temp.write("scoop/apps/editor/current/tools/cli.exe", "");
+        temp.write(
+            "scoop/apps/viewer/current/manifest.json",
+            r#"{ "version": "1.0", "shortcuts": [["viewer.exe", "Viewer"]] }"#,
+        );
+        temp.write("scoop/apps/viewer/current/viewer.exe", "");
+
+        let old = temp.path.join("scoop/apps/editor/1.0");
+        // Written against a version directory by an old install.
+        temp.write("menu/Editor.lnk", &old.join("editor.exe").to_string_lossy());
+        // The viewer link was renamed by the user.
+        let viewer = temp.path.join("scoop/apps/viewer/current/viewer.exe");
+        temp.write("menu/My Viewer.lnk", &viewer.to_string_lossy());
+        temp.write(
+            "menu/Removed App.lnk",
+            "C:\\scoop\\apps\\removed\\current\\x.exe",
+        );
+        // Installed, but its manifest cannot be read, so nothing declares it.
+        temp.write("scoop/apps/broken/current/manifest.json", "{ not json");
+        let broken = temp.path.join("scoop/apps/broken/current/broken.exe");
+        temp.write("menu/Broken.lnk", &broken.to_string_lossy());
+        let uninstalled = temp.path.join("scoop/apps/uninstalled/current/u.exe");
+        temp.write("menu/Uninstalled.lnk", &uninstalled.to_string_lossy());
+
+        let dirs = vec![(temp.path.join("menu"), false)];
+        (temp, dirs)
+    }
+
+    fn summary(entries: &[ShortcutEntry]) -> Vec<(String, ShortcutStatus)> {
+        entries.iter().map(|e| (e.name.clone(), e.status)).collect()
+    }
+
+    #[test]
+    fn classifies_declared_and_orphaned_links() {
+        let (temp, dirs) = scoop_tree();
+        let apps = apps::installed_apps(&temp.path.join("scoop"));
+        let entries = check_shortcuts(&temp.path.join("scoop"), &apps, &dirs, read_fake);
+
+        assert_eq!(
+            summary(&entries),
+            vec![
+                ("Editor".to_string(), ShortcutStatus::Stale),
+                (
+                    "Editor Tools/Editor CLI".to_string(),
+                    ShortcutStatus::Missing
+                ),
+                ("Gone".to_string(), ShortcutStatus::Missing),
+                ("My Viewer".to_string(), ShortcutStatus::Renamed),
+                ("Broken".to_string(), ShortcutStatus::Unclaimed),
+                ("Removed App".to_string(), ShortcutStatus::Orphan),
+                ("Uninstalled".to_string(), ShortcutStatus::Orphan),
+            ]
+        );
+        let cli = &entries[1];
+        assert!(cli.link.ends_with("Editor CLI.lnk"));
+        assert!(cli.link.contains("Editor Tools"));
+        assert_eq!(cli.args.as_deref(), Some("--new"));
+        assert!(cli.icon.as_deref().unwrap().ends_with("cli.ico"));
+    }
+
+    #[test]
+    fn repairs_links_and_converges() {
+        let (temp, dirs) = scoop_tree();
+        let apps = apps::installed_apps(&temp.path.join("scoop"));
+        let mut plan = plan_repairs(&check_shortcuts(
+            &temp.path.join("scoop"),
+            &apps,
+            &dirs,
+            read_fake,
+        ));
+        let actions: HashMap<&str, ShortcutRepairAction> =
+            plan.iter().map(|c| (c.name.as_str(), c.action)).collect();
+        assert_eq!(actions["Editor"], ShortcutRepairAction::Recreate);
+        assert_eq!(
+            actions["Editor Tools/Editor CLI"],
+            ShortcutRepairAction::Create
+        );
+        assert_eq!(actions["Gone"], ShortcutRepairAction::Skip);
+        assert_eq!(actions["Removed App"], ShortcutRepairAction::Remove);
+        assert_eq!(actions["Uninstalled"], ShortcutRepairAction::Remove);
+        assert!(!actions.contains_key("My Viewer"));
+        assert!(!actions.contains_key("Broken"));
+
+        let written = Mutex::new(Vec::new());
+        apply_repairs(&mut plan, |link, spec| {
+            written.lock().unwrap().push(link.to_path_buf());
+            fs::write(link, spec.target.to_string_lossy().as_bytes()).map_err(|e| e.to_string())
+        });
+        assert!(plan.iter().all(|c| c.error.is_none()));
+        assert_eq!(written.into_inner().unwrap().len(), 2);
+        assert!(!temp.path.join("menu/Removed App.lnk").exists());
+        assert!(temp.path.join("menu/Broken.lnk").exists());
+
+        // Only the shortcut with a missing target is left.
+        let entries = check_shortcuts(&temp.path.join("scoop"), &apps, &dirs, read_fake);
+        let remaining: Vec<_> = plan_repairs(&entries)
+            .into_iter()
+            .map(|c| (c.name, c.action))
+            .collect();
+        assert_eq!(
+            remaining,
+            vec![("Gone".to_string(), ShortcutRepairAction::Skip)]
+        );
+    }
+
+    #[test]
+    fn renames_links_within_the_folder() {
+        let (temp, dirs) = scoop_tree();
+        let to = rename_link(&dirs, false, "My Viewer", "Viewers/Viewer").unwrap();
+        assert!(to.ends_with("Viewers/Viewer.lnk"));
+        assert!(!temp.path.join("menu/My Viewer.lnk").exists());
+
+        let apps = apps::installed_apps(&temp.path.join("scoop"));
+        let entries = check_shortcuts(&temp.path.join("scoop"), &apps, &dirs, read_fake);
+        assert!(entries
+            .iter()
+            .any(|e| e.name == "Viewers\\Viewer" && e.status == ShortcutStatus::Renamed));
+
+        assert!(rename_link(&dirs, false, "Editor", "Removed App").is_err());
+        for bad in [
+            "../escape",
+            "Viewers\\..\\..\\escape",
+            "C:\\escape",
+            "\\escape",
+        ] {
+            assert!(rename_link(&dirs, false, "Editor", bad).is_err());
+            assert!(rename_link(&dirs, false, bad, "Other").is_err());
+        }
+        assert!(temp.path.join("menu/Editor.lnk").exists());
+        assert!(rename_link(&dirs, false, "Nope", "Other").is_err());
+    }
+}
@@ -50,7 +50,11 @@ fn is_package_held(scoop_dir: &std::path::Path, package_name: &str) -> Result<bo
 }
 
 /// Modifies the hold status of a package by updating its `install.json`.
-fn modify_hold_status(scoop_dir: &Path, package_name: &str, hold: bool) -> Result<(), String> {
+pub(crate) fn modify_hold_status(
+    scoop_dir: &Path,
+    package_name: &str,
+    hold: bool,
+) -> Result<(), String> {
     let install_json_path = get_current_install_json_path(scoop_dir, package_name)?;
     let content = fs::read_to_string(&install_json_path).map_err(|e| e.to_string())?;
 
@@ -73,51 +77,25 @@ fn modify_hold_status(scoop_dir: &Path, package_name: &str, hold: bool) -> Resul
     }
 }
 
-/// Lists all packages that are currently on hold.
-/// Uses a memoized approach by checking the installed packages cache first,
-/// then only scanning directories if needed.
-#[tauri::command]
-pub async fn list_held_packages<R: Runtime>(
-    _app: AppHandle<R>,
-    state: State<'_, AppState>,
-) -> Result<Vec<String>, String> {
-    log::info!("Listing held packages by checking install.json files");
-
-    let scoop_path = state.scoop_path();
+/// Names of the installed packages under `scoop_path` that are on hold.
+pub fn held_packages(scoop_path: &Path) -> Result<Vec<String>, String> {
     let apps_path = scoop_path.join("apps");
     if !apps_path.is_dir() {
         log::warn!("Scoop apps directory not found at {}", apps_path.display());
         return Ok(vec![]);
     }
 
-    // First, try to get app dirs from cache if available
-    // If cache exists, we can extract held packages from it directly by re-reading install.json
     let app_dirs = fs::read_dir(apps_path)
         .map_err(|e| format!("Failed to read apps directory: {}", e))?
         .filter_map(Result::ok)
         .filter(|entry| entry.path().is_dir())
         .collect::<Vec<_>>();
 
-    // Check cache to see if we have the same package set
-    let cached_packages_count = {
-        let cache_guard = state.installed_packages.lock().await;
-        cache_guard.as_ref().map(|c| c.packages.len())
-    };
-
-    if let Some(cached_count) = cached_packages_count {
-        if cached_count == app_dirs.len() {
-            log::debug!(
-                "Using memoized approach: installed cache matches app dir count ({})",
-                app_dirs.len()
-            );
-        }
-    }
-
     let held_packages = app_dirs
         .par_iter()
         .filter_map(|entry| {
             let package_name = entry.file_name().to_string_lossy().to_string();
-            match is_package_held(&scoop_path, &package_name) {
+            match is_package_held(scoop_path, &package_name) {
                 Ok(true) => Some(package_name),
                 _ => None,
             }
@@ -128,6 +106,17 @@ pub async fn list_held_packages<R: Runtime>(
     Ok(held_packages)
 }
 
+/// Lists all packages that are currently on hold by checking their
+/// `install.json` files.
+#[tauri::command]
+pub async fn list_held_packages<R: Runtime>(
+    _app: AppHandle<R>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    log::info!("Listing held packages by checking install.json files");
+    state.root().held_packages()
+}
+
 /// Places a hold on a package to prevent it from being updated.
 #[tauri::command]
 pub async fn hold_package<R: Runtime>(
@@ -1,4 +1,5 @@
 //! Command for fetching detailed information about a Scoop package.
+use crate::manifest::Manifest;
 use crate::state::AppState;
 use crate::utils;
 use serde::Serialize;
@@ -43,63 +44,31 @@ fn format_json_value(value: &Value) -> String {
 // -----------------------------------------------------------------------------
 // Custom formatting helpers
 // -----------------------------------------------------------------------------
-/// Extracts executable names and aliases from the `bin` field.
-fn format_bin_value(value: &Value) -> String {
-    if let Value::Array(arr) = value {
-        let names: Vec<String> = arr
-            .iter()
-            .filter_map(|item| match item {
-                Value::String(s) => Some(s.clone()),
-                Value::Array(sub) => {
-                    // First element is the executable path, second (optionally) alias.
-                    sub.get(1)
-                        .or_else(|| sub.get(0))
-                        .and_then(|v| v.as_str())
-                        .map(String::from)
-                }
-                Value::Object(obj) => obj.keys().next().map(|k| k.clone()),
-                _ => None,
-            })
-            .collect();
-        names.join(", ")
-    } else {
-        format_json_value(value)
-    }
+/// Lists the commands a manifest provides: each `bin` entry's alias, or its path.
+fn format_bins(manifest: &Manifest) -> String {
+    manifest
+        .bins()
+        .map(|bin| bin.alias().unwrap_or(bin.path()).to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
-/// Parses the JSON manifest content into a structured format for display.
-fn parse_manifest_details(json_value: &Value) -> (Vec<(String, String)>, Option<String>) {
+/// Parses the manifest into a structured format for display.
+fn parse_manifest_details(manifest: &Manifest) -> (Vec<(String, String)>, Option<String>) {
     let mut details = vec![];
-    let mut notes = None;
-
-    if let Some(obj) = json_value.as_object() {
-        for (key, value) in obj {
-            if key == "notes" {
-                notes = Some(match value {
-                    Value::Array(arr) => arr
-                        .iter()
-                        .map(|v| {
-                            if let Some(s) = v.as_str() {
-                                s.to_string()
-                            } else {
-                                v.to_string().trim_matches('"').to_string()
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                        .join("\n"),
-                    _ => format_json_value(value),
-                });
-            } else if key == "bin" {
-                let formatted_value = format_bin_value(value);
-                details.push(("Includes".to_string(), formatted_value));
-            } else {
-                let formatted_key = format_field_key(key);
-                let formatted_value = format_json_value(value);
-                details.push((formatted_key, formatted_value));
+
+    if let Value::Object(obj) = manifest.to_value() {
+        for (key, value) in &obj {
+            if key == "notes" || key == "bin" {
+                continue;
             }
+            details.push((format_field_key(key), format_json_value(value)));
         }
     }
-    (details, notes)
+    if manifest.bins().next().is_some() {
+        details.push(("Includes".to_string(), format_bins(manifest)));
+    }
+    (details, manifest.notes_text())
 }
 
 /// Fetches and formats information about a specific Scoop package.
@@ -117,10 +86,10 @@ pub fn get_package_info(
     let manifest_content = fs::read_to_string(&manifest_path)
         .map_err(|e| format!("Failed to read manifest for {}: {}", package_name, e))?;
 
-    let json_value: Value = serde_json::from_str(&manifest_content)
-        .map_err(|e| format!("Failed to parse JSON for {}: {}", package_name, e))?;
+    let manifest = Manifest::parse(&manifest_content)
+        .map_err(|e| format!("Failed to parse manifest for {}: {}", package_name, e))?;
 
-    let (mut details, notes) = parse_manifest_details(&json_value);
+    let (mut details, notes) = parse_manifest_details(&manifest);
 
     details.push(("Bucket".to_string(), bucket_name));
 
@@ -140,10 +109,8 @@ pub fn get_package_info(
                     installed_version.to_string(),
                 );
                 // Also add the latest version from the bucket manifest
-                if let Some(latest_version) = json_value.get("version").and_then(|v| v.as_str()) {
-                    if installed_version != latest_version {
-                        details.push(("Latest Version".to_string(), latest_version.to_string()));
-                    }
+                if installed_version != manifest.version {
+                    details.push(("Latest Version".to_string(), manifest.version.clone()));
                 }
             } else {
                 details.push((
@@ -175,12 +142,7 @@ fn get_installed_version(scoop_dir: &std::path::Path, package_name: &str) -> Opt
         .join("current")
         .join("manifest.json");
 
-    fs::read_to_string(installed_manifest_path)
+    Manifest::from_path(&installed_manifest_path)
         .ok()
-        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
-        .and_then(|json| {
-            json.get("version")
-                .and_then(|v| v.as_str())
-                .map(String::from)
-        })
+        .map(|manifest| manifest.version)
 }
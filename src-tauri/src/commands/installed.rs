@@ -1,5 +1,7 @@
 //! Command for fetching all installed Scoop packages from the filesystem.
-use crate::models::{InstallManifest, PackageManifest, ScoopPackage};
+use crate::manifest::Manifest;
+use crate::models::{InstallManifest, ScoopPackage};
+use crate::scoop_root::ScoopRoot;
 use crate::state::{AppState, InstalledPackagesCache};
 use crate::utils::{locate_package_manifest, validate_scoop_child_dir};
 use chrono::{DateTime, Utc};
@@ -8,6 +10,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, UNIX_EPOCH};
 use tauri::{AppHandle, Runtime, State};
+use tokio::sync::Mutex;
 
 /// Helper to get modification time of a path (file or directory) in milliseconds.
 fn get_path_modification_time(path: &Path) -> u128 {
@@ -119,7 +122,7 @@ fn load_package_details(package_path: &Path, scoop_path: &Path) -> Result<ScoopP
     let manifest_content = fs::read_to_string(&manifest_path)
         .map_err(|e| format!("Failed to read manifest.json for {}: {}", package_name, e))?;
 
-    let manifest: PackageManifest = serde_json::from_str(&manifest_content)
+    let manifest = Manifest::parse(&manifest_content)
         .map_err(|e| format!("Failed to parse manifest.json for {}: {}", package_name, e))?;
 
     // install.json might not exist for versioned installs
@@ -167,6 +170,50 @@ fn load_package_details(package_path: &Path, scoop_path: &Path) -> Result<ScoopP
     })
 }
 
+fn list_app_dirs(apps_path: &Path) -> Result<Vec<PathBuf>, String> {
+    Ok(fs::read_dir(apps_path)
+        .map_err(|e| format!("Failed to read apps directory: {}", e))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect())
+}
+
+fn load_packages(app_dirs: &[PathBuf], scoop_path: &Path, log_prefix: &str) -> Vec<ScoopPackage> {
+    app_dirs
+        .par_iter()
+        .filter_map(
+            |path| match load_package_details(path.as_path(), scoop_path) {
+                Ok(package) => Some(package),
+                Err(e) => {
+                    log::warn!(
+                        "{} Skipping package at '{}': {}",
+                        log_prefix,
+                        path.display(),
+                        e
+                    );
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// Scans the installed packages under `scoop_path` without going through the
+/// app state or its cache.
+pub fn scan_installed_packages(scoop_path: &Path) -> Result<Vec<ScoopPackage>, String> {
+    let apps_path = scoop_path.join("apps");
+    if !apps_path.is_dir() {
+        return Ok(vec![]);
+    }
+    let app_dirs = list_app_dirs(&apps_path)?;
+    Ok(load_packages(
+        &app_dirs,
+        scoop_path,
+        "=== INSTALLED SCAN ===",
+    ))
+}
+
 /// Fetches a list of all installed Scoop packages by scanning the filesystem.
 async fn refresh_scoop_path_if_needed<R: Runtime>(
     app: AppHandle<R>,
@@ -196,32 +243,25 @@ async fn refresh_scoop_path_if_needed<R: Runtime>(
     }
 }
 
-/// Internal method to perform the actual installed packages scan.
-/// Separated from the public command to support both warm-up and user-initiated refresh paths.
-async fn scan_installed_packages_internal<R: Runtime>(
-    app: AppHandle<R>,
-    state: &AppState,
-    is_warmup: bool,
+/// Scans the installed packages under `scoop_path`, reusing `cache` while the
+/// fingerprint of the apps directory is unchanged.
+pub(crate) async fn cached_installed_packages(
+    scoop_path: &Path,
+    cache: &Mutex<Option<InstalledPackagesCache>>,
 ) -> Result<Vec<ScoopPackage>, String> {
-    let log_prefix = if is_warmup {
-        "=== INSTALLED WARMUP ==="
-    } else {
-        "=== INSTALLED SCAN ==="
-    };
+    let log_prefix = "=== INSTALLED SCAN ===";
 
     log::info!("{} Starting installed packages scan", log_prefix);
 
-    // Ensure apps path exists
-    let apps_path = match ensure_apps_path(app.clone(), state, log_prefix).await {
-        Some(path) => path,
-        None => {
-            log::warn!(
-                "{} ✗ Failed to find or refresh Scoop apps directory",
-                log_prefix
-            );
-            return Ok(vec![]);
-        }
-    };
+    let apps_path = scoop_path.join("apps");
+    if !apps_path.is_dir() {
+        log::warn!(
+            "{} ✗ Scoop apps directory does not exist at: {}",
+            log_prefix,
+            apps_path.display()
+        );
+        return Ok(vec![]);
+    }
 
     log::info!(
         "{} ✓ Apps directory found: {}",
@@ -229,12 +269,7 @@ async fn scan_installed_packages_internal<R: Runtime>(
         apps_path.display()
     );
 
-    let app_dirs: Vec<PathBuf> = fs::read_dir(&apps_path)
-        .map_err(|e| format!("Failed to read apps directory: {}", e))?
-        .filter_map(Result::ok)
-        .map(|entry| entry.path())
-        .filter(|path| path.is_dir())
-        .collect();
+    let app_dirs = list_app_dirs(&apps_path)?;
 
     log::info!(
         "{} Found {} app directories in apps path",
@@ -246,7 +281,7 @@ async fn scan_installed_packages_internal<R: Runtime>(
     log::info!("{} Computed fingerprint: {}", log_prefix, fingerprint);
 
     // Check cache
-    if let Some(cached_packages) = check_cache(state, &fingerprint, log_prefix).await {
+    if let Some(cached_packages) = check_cache(cache, &fingerprint, log_prefix).await {
         return Ok(cached_packages);
     }
 
@@ -256,23 +291,7 @@ async fn scan_installed_packages_internal<R: Runtime>(
         app_dirs.len()
     );
 
-    let packages: Vec<ScoopPackage> = app_dirs
-        .par_iter()
-        .filter_map(
-            |path| match load_package_details(path.as_path(), &state.scoop_path()) {
-                Ok(package) => Some(package),
-                Err(e) => {
-                    log::warn!(
-                        "{} Skipping package at '{}': {}",
-                        log_prefix,
-                        path.display(),
-                        e
-                    );
-                    None
-                }
-            },
-        )
-        .collect();
+    let packages = load_packages(&app_dirs, scoop_path, log_prefix);
 
     log::info!(
         "{} ✓ Scanned {} packages, found {} valid packages",
@@ -282,7 +301,7 @@ async fn scan_installed_packages_internal<R: Runtime>(
     );
 
     // Update cache
-    update_cache(state, packages.clone(), fingerprint.clone(), log_prefix).await;
+    update_cache(cache, packages.clone(), fingerprint.clone(), log_prefix).await;
 
     log::info!(
         "{} ✓ Returning {} installed packages",
@@ -292,6 +311,18 @@ async fn scan_installed_packages_internal<R: Runtime>(
     Ok(packages)
 }
 
+/// Returns the app's Scoop root, re-resolving the path first when its apps
+/// directory has gone missing (e.g. Scoop was moved or reinstalled).
+pub(crate) async fn resolved_root<'a, R: Runtime>(
+    app: AppHandle<R>,
+    state: &'a AppState,
+) -> ScoopRoot<'a> {
+    if !state.scoop_path().join("apps").is_dir() {
+        refresh_scoop_path_if_needed(app, state, "apps path missing").await;
+    }
+    state.root()
+}
+
 #[tauri::command]
 pub async fn get_installed_packages_full<R: Runtime>(
     app: AppHandle<R>,
@@ -300,7 +331,7 @@ pub async fn get_installed_packages_full<R: Runtime>(
     log::info!("=== INSTALLED SCAN === get_installed_packages_full called");
 
     // Perform the scan (cache is checked inside)
-    scan_installed_packages_internal(app, &state, false).await
+    resolved_root(app, &state).await.installed_packages().await
 }
 
 /// Invalidates the cached list of installed packages in AppState.
@@ -336,7 +367,7 @@ pub async fn refresh_installed_packages<R: Runtime>(
         );
         // During a debounce window, DO NOT invalidate the cache again.
         // Just return whatever the standard cache-aware fetch would return.
-        return scan_installed_packages_internal(app, &state, false).await;
+        return resolved_root(app, &state).await.installed_packages().await;
     }
 
     // First invalidate the cache
@@ -345,7 +376,7 @@ pub async fn refresh_installed_packages<R: Runtime>(
 
     // Then fetch fresh data
     log::info!("=== INSTALLED REFRESH === Fetching fresh data");
-    scan_installed_packages_internal(app, &state, false).await
+    resolved_root(app, &state).await.installed_packages().await
 }
 
 /// Gets the installation path for a specific package.
@@ -361,43 +392,12 @@ pub async fn get_package_path<R: Runtime>(
     Ok(package_path.to_string_lossy().to_string())
 }
 
-async fn ensure_apps_path<R: Runtime>(
-    app: AppHandle<R>,
-    state: &AppState,
-    log_prefix: &str,
-) -> Option<PathBuf> {
-    let mut scoop_path = state.scoop_path();
-    let mut apps_path = scoop_path.join("apps");
-
-    if !apps_path.is_dir() {
-        log::warn!(
-            "{} ✗ Scoop apps directory does not exist at: {}",
-            log_prefix,
-            apps_path.display()
-        );
-
-        if let Some(updated_path) =
-            refresh_scoop_path_if_needed(app, state, "apps path missing").await
-        {
-            scoop_path = updated_path;
-            apps_path = scoop_path.join("apps");
-            log::info!("{} Path refreshed to: {}", log_prefix, apps_path.display());
-        }
-    }
-
-    if apps_path.is_dir() {
-        Some(apps_path)
-    } else {
-        None
-    }
-}
-
 async fn check_cache(
-    state: &AppState,
+    cache: &Mutex<Option<InstalledPackagesCache>>,
     fingerprint: &str,
     log_prefix: &str,
 ) -> Option<Vec<ScoopPackage>> {
-    let cache_guard = state.installed_packages.lock().await;
+    let cache_guard = cache.lock().await;
     if let Some(cache) = cache_guard.as_ref() {
         if cache.fingerprint == *fingerprint {
             log::info!(
@@ -421,12 +421,12 @@ async fn check_cache(
 }
 
 async fn update_cache(
-    state: &AppState,
+    cache: &Mutex<Option<InstalledPackagesCache>>,
     packages: Vec<ScoopPackage>,
     fingerprint: String,
     log_prefix: &str,
 ) {
-    let mut cache_guard = state.installed_packages.lock().await;
+    let mut cache_guard = cache.lock().await;
     *cache_guard = Some(InstalledPackagesCache {
         packages: packages.clone(),
         fingerprint,
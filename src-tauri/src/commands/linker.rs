@@ -2,10 +2,8 @@ use crate::state::AppState;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
-use tauri::State;
-
-#[cfg(windows)]
 use std::process::Command;
+use tauri::State;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PackageVersion {
@@ -0,0 +1,410 @@
+//! Incremental cache of parsed bucket manifests.
+//!
+//! Every manifest is stamped with its file's modification time and size, so a
+//! refresh re-parses only the files that changed since the last one. The cache
+//! is written to disk and restored on launch, which keeps the first search
+//! after a restart warm.
+use crate::commands::search_rank::SearchFields;
+use crate::manifest::Manifest;
+use crate::models::{MatchSource, ScoopPackage};
+use crate::utils;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+/// Bumped whenever `CachedManifest` changes shape; older indexes are discarded.
+const INDEX_VERSION: u32 = 2;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CachedManifest {
+    pub package: ScoopPackage,
+    pub bin_strings: Vec<String>,
+    pub description: String,
+    pub homepage: String,
+    pub license: String,
+    /// Display names of the manifest's start menu shortcuts.
+    pub shortcut_names: Vec<String>,
+}
+
+impl CachedManifest {
+    pub fn search_fields(&self) -> SearchFields<'_> {
+        SearchFields {
+            name: &self.package.name,
+            bins: &self.bin_strings,
+            shortcuts: &self.shortcut_names,
+            description: &self.description,
+            homepage: &self.homepage,
+            license: &self.license,
+        }
+    }
+}
+
+/// Parses a Scoop package manifest file to extract package information and binary search strings.
+pub fn parse_package_from_manifest(path: &Path, bucket: &str) -> Option<CachedManifest> {
+    let file_name = path.file_stem().and_then(|s| s.to_str())?.to_string();
+    let manifest = Manifest::from_path(path).ok()?;
+
+    let package = ScoopPackage {
+        name: file_name,
+        version: manifest.version.clone(),
+        source: bucket.to_string(),
+        match_source: MatchSource::Name,
+        ..Default::default()
+    };
+
+    Some(CachedManifest {
+        package,
+        bin_strings: manifest_bin_strings(&manifest),
+        description: manifest.description.clone().unwrap_or_default(),
+        homepage: manifest.homepage.clone().unwrap_or_default(),
+        license: manifest.license_identifier().to_string(),
+        shortcut_names: manifest
+            .shortcuts()
+            .map(|shortcut| shortcut.name().to_string())
+            .filter(|name| !name.is_empty())
+            .collect(),
+    })
+}
+
+/// Collects every string in a manifest's `bin` field that a search can match:
+/// each executable path and its alias.
+pub(crate) fn manifest_bin_strings(manifest: &Manifest) -> Vec<String> {
+    let mut bin_strings = Vec::new();
+    for bin in manifest.bins() {
+        bin_strings.push(bin.path().to_string());
+        bin_strings.extend(bin.alias().map(str::to_string));
+    }
+    bin_strings.dedup();
+    bin_strings
+}
+
+/// Identity of a manifest file's contents as seen by the filesystem.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStamp {
+    modified_secs: u64,
+    modified_nanos: u32,
+    size: u64,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+            size: metadata.len(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FileEntry {
+    stamp: FileStamp,
+    /// `None` for files that are not valid manifests; they stay stamped so
+    /// they are not re-read on every refresh.
+    manifest: Option<CachedManifest>,
+}
+
+/// Number of manifest files touched by a refresh.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RefreshStats {
+    pub parsed: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+impl RefreshStats {
+    pub fn changed(&self) -> bool {
+        self.parsed > 0 || self.removed > 0
+    }
+
+    fn add(&mut self, other: RefreshStats) {
+        self.parsed += other.parsed;
+        self.removed += other.removed;
+        self.unchanged += other.unchanged;
+    }
+}
+
+/// Parsed manifests of every bucket, keyed by bucket name and then by the
+/// manifest's path relative to the bucket.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ManifestCache {
+    version: u32,
+    buckets_dir: PathBuf,
+    buckets: BTreeMap<String, HashMap<String, FileEntry>>,
+    /// Flattened manifests handed out to searches, rebuilt after changes.
+    #[serde(skip)]
+    snapshot: Option<Arc<Vec<CachedManifest>>>,
+}
+
+impl ManifestCache {
+    pub fn new(buckets_dir: &Path) -> Self {
+        Self {
+            version: INDEX_VERSION,
+            buckets_dir: buckets_dir.to_path_buf(),
+            ..Default::default()
+        }
+    }
+
+    pub fn buckets_dir(&self) -> &Path {
+        &self.buckets_dir
+    }
+
+    /// Restores a cache saved by [`ManifestCache::save`]. Indexes from another
+    /// format version or Scoop root are ignored.
+    pub fn load(index_path: &Path, buckets_dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(index_path).ok()?;
+        let cache: Self = match serde_json::from_str(&content) {
+            Ok(cache) => cache,
+            Err(e) => {
+                log::warn!("Ignoring unreadable manifest index: {}", e);
+                return None;
+            }
+        };
+        (cache.version == INDEX_VERSION && cache.buckets_dir == buckets_dir).then_some(cache)
+    }
+
+    pub fn save(&self, index_path: &Path) -> Result<(), String> {
+        if let Some(parent) = index_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+        }
+        let content = serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize manifest index: {}", e))?;
+        // Write then rename so a crash never leaves a truncated index behind.
+        let tmp_path = index_path.with_extension("json.tmp");
+        fs::write(&tmp_path, content)
+            .map_err(|e| format!("Failed to write manifest index: {}", e))?;
+        fs::rename(&tmp_path, index_path)
+            .map_err(|e| format!("Failed to write manifest index: {}", e))
+    }
+
+    /// Brings every bucket up to date with the files on disk.
+    pub fn refresh(&mut self) -> Result<RefreshStats, String> {
+        let mut names: BTreeSet<String> = self.buckets.keys().cloned().collect();
+        let entries = fs::read_dir(&self.buckets_dir)
+            .map_err(|_| "Scoop buckets directory not found".to_string())?;
+        names.extend(
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_string()),
+        );
+        Ok(self.refresh_buckets(names))
+    }
+
+    /// Brings the named buckets up to date, dropping any that no longer exist.
+    pub fn refresh_buckets<I>(&mut self, names: I) -> RefreshStats
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut stats = RefreshStats::default();
+        for name in names {
+            stats.add(self.refresh_bucket(&name));
+        }
+        if stats.changed() {
+            self.snapshot = None;
+        }
+        stats
+    }
+
+    fn refresh_bucket(&mut self, name: &str) -> RefreshStats {
+        let bucket_path = self.buckets_dir.join(name);
+        let previous = self.buckets.remove(name).unwrap_or_default();
+        if !bucket_path.is_dir() {
+            return RefreshStats {
+                removed: previous.len(),
+                ..Default::default()
+            };
+        }
+
+        let scanned: Vec<(String, FileEntry, bool)> = utils::bucket_manifest_paths(&bucket_path)
+            .par_iter()
+            .filter_map(|path| {
+                let relative = path
+                    .strip_prefix(&bucket_path)
+                    .ok()?
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                let stamp = FileStamp::of(path)?;
+                if let Some(entry) = previous.get(&relative) {
+                    if entry.stamp == stamp {
+                        return Some((relative, entry.clone(), false));
+                    }
+                }
+                let manifest = parse_package_from_manifest(path, name);
+                Some((relative, FileEntry { stamp, manifest }, true))
+            })
+            .collect();
+
+        let mut stats = RefreshStats::default();
+        let mut files = HashMap::with_capacity(scanned.len());
+        for (relative, entry, parsed) in scanned {
+            if parsed {
+                stats.parsed += 1;
+            } else {
+                stats.unchanged += 1;
+            }
+            files.insert(relative, entry);
+        }
+        stats.removed = previous
+            .keys()
+            .filter(|relative| !files.contains_key(*relative))
+            .count();
+        self.buckets.insert(name.to_string(), files);
+        stats
+    }
+
+    /// All cached manifests, ordered by bucket and path.
+    pub fn manifests(&mut self) -> Arc<Vec<CachedManifest>> {
+        self.snapshot
+            .get_or_insert_with(|| {
+                let manifests = self
+                    .buckets
+                    .values()
+                    .flat_map(|files| {
+                        let mut entries: Vec<_> = files.iter().collect();
+                        entries.sort_by(|a, b| a.0.cmp(b.0));
+                        entries
+                            .into_iter()
+                            .filter_map(|(_, entry)| entry.manifest.clone())
+                    })
+                    .collect();
+                Arc::new(manifests)
+            })
+            .clone()
+    }
+}
+
+/// The bucket affected by a filesystem change under `buckets_dir`, ignoring
+/// changes that cannot affect manifests such as git internals.
+pub fn bucket_of_change(buckets_dir: &Path, path: &Path) -> Option<String> {
+    let mut components = path.strip_prefix(buckets_dir).ok()?.components();
+    let bucket = match components.next()? {
+        Component::Normal(name) => name.to_string_lossy().to_string(),
+        _ => return None,
+    };
+    match components.next() {
+        // The bucket directory itself was added or removed.
+        None => Some(bucket),
+        Some(Component::Normal(name)) if name == ".git" => None,
+        Some(_) => {
+            let is_json = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+            // Extensionless paths may be directories that were just removed.
+            let is_dir = path.is_dir() || path.extension().is_none();
+            (is_json || is_dir).then_some(bucket)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::time::{Duration, SystemTime};
+
+    fn manifest(version: &str) -> String {
+        format!(r#"{{ "version": "{}", "bin": "app.exe" }}"#, version)
+    }
+
+    fn versions(cache: &mut ManifestCache) -> Vec<(String, String)> {
+        cache
+            .manifests()
+            .iter()
+            .map(|m| (m.package.name.clone(), m.package.version.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn refresh_reparses_only_changed_files() {
+        let temp = TempDir::new("manifest-cache-refresh");
+        temp.write("main/bucket/git.json", &manifest("2.40.0"));
+        temp.write("main/bucket/7zip.json", &manifest("23.01"));
+        temp.write("extras/bucket/vscode.json", &manifest("1.80.0"));
+
+        let mut cache = ManifestCache::new(&temp.path);
+        let first = cache.refresh().unwrap();
+        assert_eq!(first.parsed, 3);
+        assert_eq!(cache.manifests().len(), 3);
+
+        let second = cache.refresh().unwrap();
+        assert_eq!((second.parsed, second.unchanged), (0, 3));
+        assert!(!second.changed());
+
+        // A different size guarantees a new stamp even on coarse-mtime filesystems.
+        temp.write("main/bucket/git.json", &manifest("2.41.0-rc"));
+        fs::remove_file(temp.path.join("main/bucket/7zip.json")).unwrap();
+        let third = cache.refresh().unwrap();
+        assert_eq!((third.parsed, third.removed, third.unchanged), (1, 1, 1));
+        assert_eq!(
+            versions(&mut cache),
+            vec![
+                ("vscode".to_string(), "1.80.0".to_string()),
+                ("git".to_string(), "2.41.0-rc".to_string()),
+            ]
+        );
+
+        fs::remove_dir_all(temp.path.join("extras")).unwrap();
+        let removed = cache.refresh_buckets(["extras".to_string()]);
+        assert_eq!(removed.removed, 1);
+        assert_eq!(cache.manifests().len(), 1);
+    }
+
+    #[test]
+    fn invalid_manifests_are_stamped_but_not_listed() {
+        let temp = TempDir::new("manifest-cache-invalid");
+        temp.write("main/bucket/broken.json", "{ not json");
+        temp.write("main/bucket/ok.json", &manifest("1.0"));
+
+        let mut cache = ManifestCache::new(&temp.path);
+        assert_eq!(cache.refresh().unwrap().parsed, 2);
+        assert_eq!(cache.manifests().len(), 1);
+        assert_eq!(cache.refresh().unwrap().parsed, 0);
+    }
+
+    #[test]
+    fn saved_index_round_trips_for_the_same_root() {
+        let temp = TempDir::new("manifest-cache-save");
+        temp.write("buckets/main/bucket/git.json", &manifest("2.40.0"));
+        let buckets_dir = temp.path.join("buckets");
+        let index_path = temp.path.join("cache").join("manifest_index.json");
+
+        let mut cache = ManifestCache::new(&buckets_dir);
+        cache.refresh().unwrap();
+        cache.save(&index_path).unwrap();
+
+        let mut restored = ManifestCache::load(&index_path, &buckets_dir).unwrap();
+        assert_eq!(versions(&mut restored), versions(&mut cache));
+        assert!(!restored.refresh().unwrap().changed());
+        assert!(ManifestCache::load(&index_path, &temp.path.join("elsewhere")).is_none());
+
+        // Touching a file after the save is picked up on the next refresh.
+        let git = temp.path.join("buckets/main/bucket/git.json");
+        let file = fs::File::options().write(true).open(&git).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(restored.refresh().unwrap().parsed, 1);
+    }
+
+    #[test]
+    fn maps_filesystem_changes_to_buckets() {
+        let buckets_dir = Path::new("/scoop/buckets");
+        let bucket = |path: &str| bucket_of_change(buckets_dir, Path::new(path));
+
+        assert_eq!(
+            bucket("/scoop/buckets/main/bucket/git.json").as_deref(),
+            Some("main")
+        );
+        assert_eq!(bucket("/scoop/buckets/extras").as_deref(), Some("extras"));
+        assert_eq!(bucket("/scoop/buckets/main/.git/index"), None);
+        assert_eq!(bucket("/scoop/buckets/main/README.md"), None);
+        assert_eq!(bucket("/scoop/apps/git/current"), None);
+    }
+}
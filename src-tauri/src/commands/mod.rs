@@ -1,9 +1,16 @@
 pub mod app_info;
 pub mod auto_cleanup;
+pub mod automation;
 pub mod bucket;
+pub mod bucket_changes;
+pub mod bucket_fetch;
+pub mod bucket_health;
 pub mod bucket_install;
 pub mod bucket_parser;
+pub mod bucket_pin;
 pub mod bucket_search;
+pub mod bucket_sources;
+pub mod bucket_sync;
 pub mod debug;
 pub mod doctor;
 pub mod hold;
@@ -11,15 +18,20 @@ pub mod info;
 pub mod installed;
 pub mod linker;
 pub mod manifest;
+pub mod manifest_cache;
+pub mod notification_sinks;
 pub mod operations;
+pub mod package_index;
 pub mod path;
 pub mod profile;
 pub mod release_notes;
 pub mod scoop;
 pub mod scoop_interpreter;
 pub mod search;
+pub mod search_rank;
 pub mod settings;
 pub mod startup;
 pub mod status;
+pub mod update_groups;
 pub mod updates;
 pub mod virustotal;
@@ -0,0 +1,630 @@
+//! Notification sinks for finished operations.
+//!
+//! Besides the local toast, every finished operation (including scheduler
+//! runs, which finish as `auto-update` operations) can be reported to any
+//! number of configured sinks: a generic JSON webhook, Slack or Teams
+//! incoming webhooks, email over SMTP, or a JSON-lines file. Each sink can be
+//! limited to some operation kinds and to failures only. Delivery runs in the
+//! background and failures are only logged.
+use crate::commands::scoop_interpreter::PackageResult;
+use crate::commands::settings;
+use crate::commands::virustotal::ScanWarning;
+use crate::operations::{CompletedOperation, OperationKind, OperationWarning};
+use execra::Finding;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::io::AsyncWriteExt;
+
+/// Config key holding the list of [`NotificationSink`]s.
+pub const SINKS_KEY: &str = "notifications.sinks";
+pub const EVENT_NAME: &str = "operation-completed";
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(15);
+/// Output lines included with a report; enough to see why something failed.
+const OUTPUT_TAIL_LINES: usize = 20;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS (usually port 587).
+    #[default]
+    Starttls,
+    /// TLS from the start (usually port 465).
+    Tls,
+    /// No encryption. Only for relays on a trusted network.
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum SinkKind {
+    /// POSTs the [`OperationReport`] as JSON.
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// Slack incoming webhook (`{"text": ...}`).
+    Slack { url: String },
+    /// Microsoft Teams incoming webhook (MessageCard).
+    Teams { url: String },
+    Email {
+        host: String,
+        #[serde(default)]
+        port: Option<u16>,
+        #[serde(default)]
+        security: SmtpSecurity,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+        from: String,
+        to: Vec<String>,
+    },
+    /// Appends one JSON report per line.
+    File { path: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NotificationSink {
+    pub id: String,
+    #[serde(flatten)]
+    pub kind: SinkKind,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Operation kinds to report. Empty means all of them.
+    #[serde(default)]
+    pub kinds: Vec<OperationKind>,
+    /// Skip successful operations.
+    #[serde(default)]
+    pub failures_only: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl NotificationSink {
+    pub fn accepts(&self, report: &OperationReport) -> bool {
+        self.enabled
+            && (self.kinds.is_empty() || self.kinds.contains(&report.operation.kind))
+            && !(self.failures_only && report.operation.success)
+    }
+}
+
+/// A [`CompletedOperation`] without its full transcript.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationSummary {
+    pub id: String,
+    pub title: String,
+    pub kind: OperationKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_name: Option<String>,
+    pub success: bool,
+    pub status: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub operation_warnings: Vec<OperationWarning>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub findings: Vec<Finding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan_warning: Option<ScanWarning>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<PackageResult>,
+    pub output_tail: Vec<String>,
+}
+
+/// What sinks receive.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationReport {
+    pub event: String,
+    pub app_version: String,
+    pub host: String,
+    pub finished_at: String,
+    pub operation: OperationSummary,
+}
+
+impl OperationReport {
+    pub fn new(operation: &CompletedOperation) -> Self {
+        let skip = operation.output.len().saturating_sub(OUTPUT_TAIL_LINES);
+        Self {
+            event: EVENT_NAME.to_string(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            host: host_name(),
+            finished_at: chrono::Utc::now().to_rfc3339(),
+            operation: OperationSummary {
+                id: operation.id.clone(),
+                title: operation.title.clone(),
+                kind: operation.kind.clone(),
+                package_name: operation.package_name.clone(),
+                success: operation.success,
+                status: operation.status.clone(),
+                message: operation.message.clone(),
+                operation_warnings: operation.operation_warnings.clone(),
+                findings: operation.findings.clone(),
+                scan_warning: operation.scan_warning.clone(),
+                packages: operation.packages.clone(),
+                output_tail: operation
+                    .output
+                    .iter()
+                    .skip(skip)
+                    .map(|line| line.line.clone())
+                    .collect(),
+            },
+        }
+    }
+
+    fn headline(&self) -> String {
+        let marker = match self.operation.status.as_str() {
+            "warning" => "⚠",
+            _ if self.operation.success => "✓",
+            _ => "✗",
+        };
+        format!("{} {} on {}", marker, self.operation.title, self.host)
+    }
+
+    /// Message, warnings and findings as plain text lines.
+    fn details(&self) -> Vec<String> {
+        let mut lines = vec![self.operation.message.clone()];
+        // With one warning, the message is its text; don't repeat it.
+        let warnings = &self.operation.operation_warnings;
+        let repeated = warnings
+            .first()
+            .is_some_and(|warning| warning.message == self.operation.message);
+        lines.extend(
+            warnings
+                .iter()
+                .skip(usize::from(repeated))
+                .map(|warning| format!("- {}", warning.message)),
+        );
+        lines.extend(
+            self.operation
+                .findings
+                .iter()
+                .map(|finding| format!("- {}", finding.message)),
+        );
+        lines
+    }
+
+    fn plain_text(&self) -> String {
+        let mut text = self.details().join("\n");
+        if !self.operation.success && !self.operation.output_tail.is_empty() {
+            text.push_str("\n\nLast output:\n");
+            text.push_str(&self.operation.output_tail.join("\n"));
+        }
+        text
+    }
+}
+
+fn host_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "unknown host".to_string())
+}
+
+pub fn slack_payload(report: &OperationReport) -> Value {
+    json!({ "text": format!("*{}*\n{}", report.headline(), report.details().join("\n")) })
+}
+
+pub fn teams_payload(report: &OperationReport) -> Value {
+    let color = match report.operation.status.as_str() {
+        "warning" => "FFA500",
+        _ if report.operation.success => "2EB886",
+        _ => "D00000",
+    };
+    json!({
+        "@type": "MessageCard",
+        "@context": "https://schema.org/extensions",
+        "summary": report.headline(),
+        "themeColor": color,
+        "title": report.headline(),
+        "text": report.details().join("\n\n"),
+    })
+}
+
+/// Reads the configured sinks. An invalid setting disables all of them.
+pub fn parse_sinks(value: Option<Value>) -> Vec<NotificationSink> {
+    let Some(value) = value else {
+        return Vec::new();
+    };
+    match serde_json::from_value(value) {
+        Ok(sinks) => sinks,
+        Err(e) => {
+            log::warn!("Invalid notification sinks, none will be used: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn configured_sinks(app: &AppHandle) -> Vec<NotificationSink> {
+    parse_sinks(
+        settings::get_config_value(app.clone(), SINKS_KEY.to_string())
+            .ok()
+            .flatten(),
+    )
+}
+
+async fn post_json(
+    url: &str,
+    headers: &BTreeMap<String, String>,
+    body: &Value,
+) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut request = client.post(url).json(body);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("{} answered {}", url, response.status()))
+    }
+}
+
+async fn append_line(path: &str, report: &OperationReport) -> Result<(), String> {
+    let path = Path::new(path);
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    let mut line = serde_json::to_string(report).map_err(|e| e.to_string())?;
+    line.push('\n');
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    file.write_all(line.as_bytes())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn send_email(
+    host: &str,
+    port: Option<u16>,
+    security: SmtpSecurity,
+    username: Option<&str>,
+    password: Option<&str>,
+    from: &str,
+    to: &[String],
+    report: &OperationReport,
+) -> Result<(), String> {
+    use lettre::message::header::ContentType;
+    use lettre::transport::smtp::authentication::Credentials;
+    use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+    if to.is_empty() {
+        return Err("Email sink has no recipients".to_string());
+    }
+    let mut message = Message::builder()
+        .from(from.parse().map_err(|e| format!("Invalid sender: {}", e))?)
+        .subject(format!("[rScoop] {}", report.headline()))
+        .header(ContentType::TEXT_PLAIN);
+    for recipient in to {
+        message = message.to(recipient
+            .parse()
+            .map_err(|e| format!("Invalid recipient {}: {}", recipient, e))?);
+    }
+    let message = message
+        .body(report.plain_text())
+        .map_err(|e| e.to_string())?;
+
+    let mut transport = match security {
+        SmtpSecurity::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+        SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+            host,
+        )),
+    }
+    .map_err(|e| e.to_string())?
+    .timeout(Some(DELIVERY_TIMEOUT));
+    if let Some(port) = port {
+        transport = transport.port(port);
+    }
+    if let Some(username) = username {
+        transport = transport.credentials(Credentials::new(
+            username.to_string(),
+            password.unwrap_or_default().to_string(),
+        ));
+    }
+    transport
+        .build()
+        .send(message)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Sends one report to one sink, ignoring its filters.
+pub async fn deliver(sink: &NotificationSink, report: &OperationReport) -> Result<(), String> {
+    match &sink.kind {
+        SinkKind::Webhook { url, headers } => {
+            let body = serde_json::to_value(report).map_err(|e| e.to_string())?;
+            post_json(url, headers, &body).await
+        }
+        SinkKind::Slack { url } => post_json(url, &BTreeMap::new(), &slack_payload(report)).await,
+        SinkKind::Teams { url } => post_json(url, &BTreeMap::new(), &teams_payload(report)).await,
+        SinkKind::Email {
+            host,
+            port,
+            security,
+            username,
+            password,
+            from,
+            to,
+        } => {
+            send_email(
+                host,
+                *port,
+                *security,
+                username.as_deref(),
+                password.as_deref(),
+                from,
+                to,
+                report,
+            )
+            .await
+        }
+        SinkKind::File { path } => append_line(path, report).await,
+    }
+}
+
+/// Reports a finished operation to every configured sink that wants it.
+pub fn dispatch(app: &AppHandle, operation: &CompletedOperation) {
+    let sinks = configured_sinks(app);
+    if sinks.is_empty() {
+        return;
+    }
+    let report = OperationReport::new(operation);
+    for sink in sinks.into_iter().filter(|sink| sink.accepts(&report)) {
+        let report = report.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = deliver(&sink, &report).await {
+                log::warn!("Notification sink '{}' failed: {}", sink.id, e);
+            }
+        });
+    }
+}
+
+#[tauri::command]
+pub fn get_notification_sinks(app: AppHandle) -> Vec<NotificationSink> {
+    configured_sinks(&app)
+}
+
+#[tauri::command]
+pub fn set_notification_sinks(app: AppHandle, sinks: Vec<NotificationSink>) -> Result<(), String> {
+    if sinks.iter().any(|sink| sink.id.trim().is_empty()) {
+        return Err("Every notification sink needs an id".to_string());
+    }
+    let value = serde_json::to_value(&sinks)
+        .map_err(|e| format!("Failed to serialize notification sinks: {}", e))?;
+    settings::set_config_value(app, SINKS_KEY.to_string(), value)
+}
+
+/// Sends a sample report so the user can check a sink before relying on it.
+#[tauri::command]
+pub async fn test_notification_sink(sink: NotificationSink) -> Result<(), String> {
+    let sample = CompletedOperation {
+        id: "op-test".to_string(),
+        title: "Test notification".to_string(),
+        kind: OperationKind::Install,
+        package_name: None,
+        success: true,
+        message: "rScoop can reach this notification sink".to_string(),
+        output: Vec::new(),
+        status: "success".to_string(),
+        scan_warning: None,
+        operation_warnings: Vec::new(),
+        findings: Vec::new(),
+        error_code: None,
+        action: None,
+        retry_options: Vec::new(),
+        packages: Vec::new(),
+    };
+    deliver(&sink, &OperationReport::new(&sample)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::OutputLine;
+    use crate::test_support::TempDir;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    fn completed(kind: OperationKind, success: bool) -> CompletedOperation {
+        CompletedOperation {
+            id: "op-3".to_string(),
+            title: "Updating git".to_string(),
+            kind,
+            package_name: Some("git".to_string()),
+            success,
+            message: if success {
+                "Updated git to 2.47.0".to_string()
+            } else {
+                "Hash check failed".to_string()
+            },
+            output: (0..30)
+                .map(|i| OutputLine {
+                    line: format!("line {}", i),
+                    source: "stdout".to_string(),
+                })
+                .collect(),
+            status: if success { "success" } else { "error" }.to_string(),
+            scan_warning: None,
+            operation_warnings: Vec::new(),
+            findings: vec![Finding::info("notes", "Restart your shell")],
+            error_code: None,
+            action: None,
+            retry_options: Vec::new(),
+            packages: Vec::new(),
+        }
+    }
+
+    fn sink(kind: SinkKind) -> NotificationSink {
+        NotificationSink {
+            id: "test".to_string(),
+            kind,
+            enabled: true,
+            kinds: Vec::new(),
+            failures_only: false,
+        }
+    }
+
+    /// Accepts one request, answers 200 and returns the request body.
+    async fn stand_in() -> (String, tokio::task::JoinHandle<Value>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 4096];
+            let body = loop {
+                let read = stream.read(&mut chunk).await.unwrap();
+                buf.extend_from_slice(&chunk[..read]);
+                let text = String::from_utf8_lossy(&buf).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break body.to_string();
+                    }
+                }
+            };
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            serde_json::from_str(&body).unwrap()
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn filters_by_kind_and_outcome() {
+        let update_failed = OperationReport::new(&completed(OperationKind::Update, false));
+        let auto_ok = OperationReport::new(&completed(OperationKind::AutoUpdate, true));
+
+        let mut scheduled_only = sink(SinkKind::File {
+            path: "x".to_string(),
+        });
+        scheduled_only.kinds = vec![OperationKind::AutoUpdate];
+        assert!(!scheduled_only.accepts(&update_failed));
+        assert!(scheduled_only.accepts(&auto_ok));
+
+        scheduled_only.failures_only = true;
+        assert!(!scheduled_only.accepts(&auto_ok));
+
+        let parsed = parse_sinks(Some(json!([
+            {"id": "hook", "kind": "webhook", "url": "https://ci/hook", "kinds": ["update-all"]},
+            {"id": "mail", "kind": "email", "host": "smtp", "from": "a@b.c", "to": ["d@e.f"], "enabled": false}
+        ])));
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].kinds, vec![OperationKind::UpdateAll]);
+        assert!(!parsed[1].enabled);
+        assert!(parse_sinks(Some(json!({"id": "nope"}))).is_empty());
+    }
+
+    #[test]
+    fn builds_chat_payloads_with_findings() {
+        let report = OperationReport::new(&completed(OperationKind::Update, false));
+        assert_eq!(report.operation.output_tail.len(), OUTPUT_TAIL_LINES);
+        assert_eq!(report.operation.output_tail[0], "line 10");
+
+        let slack = slack_payload(&report);
+        let text = slack["text"].as_str().unwrap();
+        assert!(text.starts_with("*✗ Updating git on "));
+        assert!(text.contains("Hash check failed\n- Restart your shell"));
+
+        let teams = teams_payload(&report);
+        assert_eq!(teams["@type"], "MessageCard");
+        assert_eq!(teams["themeColor"], "D00000");
+    }
+
+    #[test]
+    fn lists_warnings_the_message_does_not_repeat() {
+        let warning = |message: &str| OperationWarning {
+            code: "warn".to_string(),
+            message: message.to_string(),
+        };
+        let mut operation = completed(OperationKind::Update, true);
+        operation.message = "Shim conflict (+1 more)".to_string();
+        operation.operation_warnings = vec![warning("Shim conflict"), warning("Old manifest")];
+        let details = OperationReport::new(&operation).details();
+        assert_eq!(details[1..3], ["- Shim conflict", "- Old manifest"]);
+
+        operation.message = "Shim conflict".to_string();
+        operation.operation_warnings.truncate(1);
+        let details = OperationReport::new(&operation).details();
+        assert_eq!(details, ["Shim conflict", "- Restart your shell"]);
+    }
+
+    #[tokio::test]
+    async fn posts_reports_to_webhooks() {
+        let (url, server) = stand_in().await;
+        let report = OperationReport::new(&completed(OperationKind::Install, true));
+        let mut headers = BTreeMap::new();
+        headers.insert("X-Token".to_string(), "abc".to_string());
+        deliver(&sink(SinkKind::Webhook { url, headers }), &report)
+            .await
+            .unwrap();
+
+        let body = server.await.unwrap();
+        assert_eq!(body["event"], EVENT_NAME);
+        assert_eq!(body["operation"]["kind"], "install");
+        assert_eq!(body["operation"]["packageName"], "git");
+        assert_eq!(
+            body["operation"]["findings"][0]["message"],
+            "Restart your shell"
+        );
+
+        let (url, server) = stand_in().await;
+        deliver(&sink(SinkKind::Slack { url }), &report)
+            .await
+            .unwrap();
+        assert!(server.await.unwrap()["text"]
+            .as_str()
+            .unwrap()
+            .contains("Updated git to 2.47.0"));
+    }
+
+    #[tokio::test]
+    async fn appends_reports_to_a_file() {
+        let temp = TempDir::new("sinks");
+        let path = temp.path.join("nested").join("ops.jsonl");
+        let file_sink = sink(SinkKind::File {
+            path: path.to_string_lossy().to_string(),
+        });
+        for success in [true, false] {
+            let report = OperationReport::new(&completed(OperationKind::Update, success));
+            deliver(&file_sink, &report).await.unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["operation"]["success"], false);
+    }
+}
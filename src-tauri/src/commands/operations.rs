@@ -1,5 +1,6 @@
 //! Tauri command surface for the OperationManager.
-use crate::operations::{self, EnqueueAction, OperationStateSnapshot};
+use crate::notifications::{self, NotificationAction};
+use crate::operations::{self, EnqueueAction, OperationStateSnapshot, RetryOption};
 use tauri::AppHandle;
 
 #[tauri::command]
@@ -44,3 +45,36 @@ pub async fn confirm_install_anyway(app: AppHandle) -> Result<(), String> {
 pub async fn run_pending_chain(app: AppHandle) -> Result<(), String> {
     operations::run_pending_chain(&app)
 }
+
+/// Runs a finished operation again. `option` picks one of the op's
+/// `retryOptions` (clear cache first, force reinstall, unhold and update);
+/// without it the original action is simply re-enqueued.
+#[tauri::command]
+pub async fn retry_operation(
+    app: AppHandle,
+    id: String,
+    option: Option<RetryOption>,
+) -> Result<String, String> {
+    operations::retry(&app, &id, option.unwrap_or(RetryOption::Plain))
+}
+
+/// Runs an action button from an in-app notification (the `app-notification`
+/// event), the same way a native toast button would.
+#[tauri::command]
+pub async fn activate_notification_action(
+    app: AppHandle,
+    action: Option<String>,
+    retry: Option<EnqueueAction>,
+) -> Result<(), String> {
+    let action = match action.as_deref() {
+        Some(id) => Some(
+            NotificationAction::from_id(id)
+                .ok_or_else(|| format!("Unknown notification action: {}", id))?,
+        ),
+        None => None,
+    };
+    // Window work must happen on the main thread.
+    let app_for_main = app.clone();
+    app.run_on_main_thread(move || notifications::activate(&app_for_main, action, retry))
+        .map_err(|e| e.to_string())
+}
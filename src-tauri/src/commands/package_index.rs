@@ -0,0 +1,509 @@
+//! Package index across directory buckets that are not added locally.
+//!
+//! The index lists package names, descriptions and binaries so a search can
+//! say "`foo` is available in bucket X (not added)". It is either downloaded
+//! as a prebuilt JSON file (`search.remoteIndexUrl`) or built by
+//! shallow-fetching the most popular buckets from the bucket directory.
+use crate::commands::bucket_fetch;
+use crate::commands::bucket_parser::{self, BucketFilterOptions};
+use crate::commands::bucket_search::{self, SearchableBucket};
+use crate::commands::bucket_sync;
+use crate::commands::manifest_cache::manifest_bin_strings;
+use crate::commands::search_rank::{self, SearchFields, SearchQuery};
+use crate::manifest::Manifest;
+use crate::models::RemotePackageMatch;
+use crate::state::AppState;
+use crate::utils;
+use git2::{build::RepoBuilder, FetchOptions};
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+/// URL of a prebuilt index. When set, building downloads it instead of
+/// fetching buckets.
+pub const INDEX_URL_KEY: &str = "search.remoteIndexUrl";
+/// Number of directory buckets (by stars) fetched when building locally.
+pub const MAX_BUCKETS_KEY: &str = "search.remoteIndexMaxBuckets";
+const DEFAULT_MAX_BUCKETS: usize = 100;
+const INDEX_FILE: &str = "package_index.json";
+/// Remote matches returned per search; the index can hold many thousands.
+const MAX_REMOTE_MATCHES: usize = 50;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RemotePackage {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub bins: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IndexedBucket {
+    pub name: String,
+    /// `owner/repo` of the bucket.
+    pub full_name: String,
+    pub url: String,
+    pub packages: Vec<RemotePackage>,
+}
+
+/// On-disk index format, also accepted from `search.remoteIndexUrl`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RemotePackageIndex {
+    /// RFC 3339 time the index was built.
+    #[serde(default)]
+    pub built_at: Option<String>,
+    pub buckets: Vec<IndexedBucket>,
+    /// Buckets that could not be fetched during the build.
+    #[serde(default)]
+    pub failed_buckets: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct RemoteIndexStatus {
+    pub built_at: Option<String>,
+    pub bucket_count: usize,
+    pub package_count: usize,
+    pub failed_buckets: Vec<String>,
+}
+
+impl RemoteIndexStatus {
+    fn of(index: &RemotePackageIndex) -> Self {
+        Self {
+            built_at: index.built_at.clone(),
+            bucket_count: index.buckets.len(),
+            package_count: index.buckets.iter().map(|b| b.packages.len()).sum(),
+            failed_buckets: index.failed_buckets.clone(),
+        }
+    }
+}
+
+// Loaded index, shared by searches until the next build.
+static INDEX_CACHE: Lazy<Mutex<Option<Arc<RemotePackageIndex>>>> = Lazy::new(|| Mutex::new(None));
+
+// Held for the length of a build, so a second build cannot clobber the
+// first one's checkouts or store a partial index over it.
+static BUILD_LOCK: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+/// Extracts the searchable fields of one manifest.
+pub fn index_manifest(name: &str, manifest: &Manifest) -> RemotePackage {
+    RemotePackage {
+        name: name.to_string(),
+        version: manifest.version.clone(),
+        description: manifest.description.clone().unwrap_or_default(),
+        bins: manifest_bin_strings(manifest),
+    }
+}
+
+/// Indexes every manifest in a bucket checkout, sorted by name.
+pub fn index_bucket_dir(bucket_path: &Path) -> Vec<RemotePackage> {
+    let mut packages: Vec<RemotePackage> = utils::bucket_manifest_paths(bucket_path)
+        .iter()
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?;
+            let manifest = Manifest::from_path(path).ok()?;
+            Some(index_manifest(name, &manifest))
+        })
+        .collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    packages
+}
+
+fn normalize_url(url: &str) -> String {
+    url.trim()
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_lowercase()
+}
+
+/// Names and origin URLs of the locally added buckets.
+#[derive(Debug, Default)]
+pub struct AddedBuckets {
+    names: HashSet<String>,
+    urls: HashSet<String>,
+}
+
+impl AddedBuckets {
+    pub fn new<'a>(buckets: impl IntoIterator<Item = (&'a str, Option<&'a str>)>) -> Self {
+        let mut added = Self::default();
+        for (name, url) in buckets {
+            added.names.insert(name.to_lowercase());
+            if let Some(url) = url {
+                added.urls.insert(normalize_url(url));
+            }
+        }
+        added
+    }
+
+    pub fn scan(buckets_dir: &Path) -> Self {
+        let buckets: Vec<(String, Option<String>)> = fs::read_dir(buckets_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| {
+                (
+                    entry.file_name().to_string_lossy().to_string(),
+                    bucket_sync::origin_url(&entry.path()),
+                )
+            })
+            .collect();
+        Self::new(
+            buckets
+                .iter()
+                .map(|(name, url)| (name.as_str(), url.as_deref())),
+        )
+    }
+
+    pub fn contains_url(&self, url: &str) -> bool {
+        self.urls.contains(&normalize_url(url))
+    }
+
+    /// Local name for a directory bucket: its own name, or `owner-name` when
+    /// an unrelated bucket already uses that name.
+    fn local_name(&self, bucket: &IndexedBucket) -> String {
+        if !self.names.contains(&bucket.name.to_lowercase()) {
+            return bucket.name.clone();
+        }
+        let owner = bucket.full_name.split('/').next().unwrap_or_default();
+        format!("{}-{}", owner.to_lowercase(), bucket.name)
+    }
+}
+
+/// Finds packages matching `query` in buckets that are not added, best
+/// first. Equal scores keep the index order (stars).
+pub fn search_index(
+    index: &RemotePackageIndex,
+    query: &SearchQuery,
+    added: &AddedBuckets,
+    limit: usize,
+) -> Vec<RemotePackageMatch> {
+    let mut matches: Vec<RemotePackageMatch> = index
+        .buckets
+        .iter()
+        .filter(|bucket| !added.contains_url(&bucket.url))
+        .flat_map(|bucket| {
+            let local_name = added.local_name(bucket);
+            bucket.packages.iter().filter_map(move |package| {
+                let fields = SearchFields {
+                    name: &package.name,
+                    bins: &package.bins,
+                    description: &package.description,
+                    ..Default::default()
+                };
+                let ranked = search_rank::rank(query, &fields)?;
+                Some(RemotePackageMatch {
+                    name: package.name.clone(),
+                    version: package.version.clone(),
+                    description: package.description.clone(),
+                    bucket: local_name.clone(),
+                    bucket_full_name: bucket.full_name.clone(),
+                    bucket_url: bucket.url.clone(),
+                    match_source: ranked.source,
+                    match_score: ranked.score,
+                })
+            })
+        })
+        .collect();
+
+    matches.sort_by_key(|m| std::cmp::Reverse(m.match_score));
+    matches.truncate(limit);
+    matches
+}
+
+fn shallow_fetch(url: &str, target: &Path) -> Result<(), String> {
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.depth(1);
+    RepoBuilder::new()
+        .fetch_options(fetch_options)
+        .clone(url, target)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))
+}
+
+/// Shallow-fetches each bucket into `staging`, indexes it and deletes the
+/// checkout again. Buckets that fail to fetch are listed in `failed_buckets`.
+pub fn build_from_directory(
+    buckets: &[SearchableBucket],
+    staging: &Path,
+    workers: usize,
+) -> Result<RemotePackageIndex, String> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(workers)
+        .build()
+        .map_err(|e| format!("Failed to start index workers: {}", e))?;
+
+    let results: Vec<Result<IndexedBucket, String>> = pool.install(|| {
+        buckets
+            .par_iter()
+            .enumerate()
+            .map(|(i, bucket)| {
+                let target = staging.join(i.to_string());
+                let result = shallow_fetch(&bucket.url, &target).map(|_| IndexedBucket {
+                    name: bucket.name.clone(),
+                    full_name: bucket.full_name.clone(),
+                    url: bucket.url.clone(),
+                    packages: index_bucket_dir(&target),
+                });
+                let _ = fs::remove_dir_all(&target);
+                result.map_err(|e| {
+                    log::warn!("Skipping '{}' in package index: {}", bucket.full_name, e);
+                    bucket.full_name.clone()
+                })
+            })
+            .collect()
+    });
+
+    let mut index = RemotePackageIndex::default();
+    for result in results {
+        match result {
+            Ok(bucket) => index.buckets.push(bucket),
+            Err(name) => index.failed_buckets.push(name),
+        }
+    }
+    Ok(index)
+}
+
+fn index_file_path() -> Result<PathBuf, String> {
+    let cache_dir = dirs::data_dir()
+        .ok_or("Failed to get app data directory")?
+        .join("rscoop")
+        .join("cache");
+    fs::create_dir_all(&cache_dir)
+        .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    Ok(cache_dir.join(INDEX_FILE))
+}
+
+/// Returns the index from memory or disk, or `None` if it was never built.
+fn load_index() -> Option<Arc<RemotePackageIndex>> {
+    let mut cache = INDEX_CACHE.lock().unwrap();
+    if let Some(index) = cache.as_ref() {
+        return Some(index.clone());
+    }
+
+    let content = fs::read_to_string(index_file_path().ok()?).ok()?;
+    match serde_json::from_str::<RemotePackageIndex>(&content) {
+        Ok(index) => {
+            let index = Arc::new(index);
+            *cache = Some(index.clone());
+            Some(index)
+        }
+        Err(e) => {
+            log::warn!("Ignoring unreadable package index: {}", e);
+            None
+        }
+    }
+}
+
+fn store_index(index: RemotePackageIndex) -> Result<(), String> {
+    let content = serde_json::to_string(&index)
+        .map_err(|e| format!("Failed to serialize package index: {}", e))?;
+    fs::write(index_file_path()?, content)
+        .map_err(|e| format!("Failed to write package index: {}", e))?;
+    *INDEX_CACHE.lock().unwrap() = Some(Arc::new(index));
+    Ok(())
+}
+
+fn config_value(app: &AppHandle, key: &str) -> Option<Value> {
+    crate::commands::settings::get_config_value(app.clone(), key.to_string())
+        .ok()
+        .flatten()
+}
+
+async fn download_index(url: &str) -> Result<RemotePackageIndex, String> {
+    log::info!("Downloading prebuilt package index from: {}", url);
+    let content = reqwest::get(url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to download package index: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read package index: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid package index: {}", e))
+}
+
+/// Remote matches for a search query. Empty when no index has been built.
+pub(crate) fn remote_matches(scoop_path: &Path, query: &SearchQuery) -> Vec<RemotePackageMatch> {
+    let Some(index) = load_index() else {
+        return Vec::new();
+    };
+    let added = AddedBuckets::scan(&scoop_path.join("buckets"));
+    search_index(&index, query, &added, MAX_REMOTE_MATCHES)
+}
+
+/// Builds (or downloads) the remote package index and stores it on disk.
+#[tauri::command]
+pub async fn build_remote_package_index(app: AppHandle) -> Result<RemoteIndexStatus, String> {
+    let _building = BUILD_LOCK
+        .try_lock()
+        .map_err(|_| "The package index is already being built".to_string())?;
+    let prebuilt_url = config_value(&app, INDEX_URL_KEY)
+        .and_then(|value| value.as_str().map(str::to_string))
+        .filter(|url| !url.trim().is_empty());
+
+    let mut index = match prebuilt_url {
+        Some(url) => download_index(&url).await?,
+        None => {
+            let max_buckets = config_value(&app, MAX_BUCKETS_KEY)
+                .and_then(|value| value.as_u64())
+                .map(|max| max as usize)
+                .unwrap_or(DEFAULT_MAX_BUCKETS);
+            let sources = bucket_search::configured_sources(&app);
+            let directory =
+                bucket_parser::get_cached_buckets(&sources, Some(BucketFilterOptions::default()))
+                    .await?;
+
+            let buckets_dir = app.state::<AppState>().scoop_path().join("buckets");
+            let added = AddedBuckets::scan(&buckets_dir);
+            let mut candidates: Vec<SearchableBucket> = directory
+                .into_values()
+                .filter(|bucket| !added.contains_url(&bucket.url))
+                .collect();
+            candidates.sort_by(|a, b| {
+                b.stars
+                    .cmp(&a.stars)
+                    .then_with(|| a.full_name.cmp(&b.full_name))
+            });
+            candidates.truncate(max_buckets);
+            log::info!(
+                "Building package index from {} directory buckets",
+                candidates.len()
+            );
+
+            let staging = std::env::temp_dir().join(format!(
+                "rscoop-index-{}-{}",
+                std::process::id(),
+                chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+            ));
+            let workers = bucket_fetch::worker_count(None);
+            tokio::task::spawn_blocking(move || {
+                let result = build_from_directory(&candidates, &staging, workers);
+                let _ = fs::remove_dir_all(&staging);
+                result
+            })
+            .await
+            .map_err(|e| e.to_string())??
+        }
+    };
+
+    index.built_at = Some(chrono::Utc::now().to_rfc3339());
+    let status = RemoteIndexStatus::of(&index);
+    log::info!(
+        "Package index built: {} packages in {} buckets ({} failed)",
+        status.package_count,
+        status.bucket_count,
+        status.failed_buckets.len()
+    );
+    store_index(index)?;
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn get_remote_index_status() -> Result<RemoteIndexStatus, String> {
+    Ok(load_index()
+        .map(|index| RemoteIndexStatus::of(&index))
+        .unwrap_or_default())
+}
+
+/// Searches the remote package index only.
+#[tauri::command]
+pub async fn search_remote_packages(
+    app: AppHandle,
+    term: String,
+) -> Result<Vec<RemotePackageMatch>, String> {
+    if term.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let query = SearchQuery::parse(&term);
+    let scoop_path = app.state::<AppState>().scoop_path();
+    tokio::task::spawn_blocking(move || remote_matches(&scoop_path, &query))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MatchSource;
+
+    fn package(name: &str, bins: &[&str]) -> RemotePackage {
+        RemotePackage {
+            name: name.to_string(),
+            version: "1.0".to_string(),
+            description: String::new(),
+            bins: bins.iter().map(|b| b.to_string()).collect(),
+        }
+    }
+
+    fn index() -> RemotePackageIndex {
+        RemotePackageIndex {
+            built_at: None,
+            buckets: vec![
+                IndexedBucket {
+                    name: "tools".to_string(),
+                    full_name: "alice/scoop-tools".to_string(),
+                    url: "https://github.com/alice/scoop-tools".to_string(),
+                    packages: vec![package("foo-helper", &[]), package("bar", &["foo.exe"])],
+                },
+                IndexedBucket {
+                    name: "extras".to_string(),
+                    full_name: "bob/scoop-extras".to_string(),
+                    url: "https://github.com/bob/scoop-extras".to_string(),
+                    packages: vec![package("foo", &[])],
+                },
+                IndexedBucket {
+                    name: "mine".to_string(),
+                    full_name: "me/mine".to_string(),
+                    url: "https://github.com/me/mine".to_string(),
+                    packages: vec![package("foo", &[])],
+                },
+            ],
+            failed_buckets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn indexes_manifest_fields() {
+        let manifest = Manifest::parse(
+            r#"{ "version": "2.1", "description": "A tool", "bin": ["tool.exe", ["tool.exe", "t"]] }"#,
+        )
+        .unwrap();
+        let package = index_manifest("tool", &manifest);
+        assert_eq!(package.version, "2.1");
+        assert_eq!(package.description, "A tool");
+        assert_eq!(package.bins, vec!["tool.exe", "t"]);
+        assert!(Manifest::parse("{}").is_err());
+    }
+
+    #[test]
+    fn search_skips_added_buckets_and_renames_collisions() {
+        let added = AddedBuckets::new([
+            ("extras", Some("https://github.com/ScoopInstaller/Extras")),
+            ("mine", Some("https://github.com/Me/Mine.git")),
+        ]);
+        let query = SearchQuery::parse("foo");
+        let matches = search_index(&index(), &query, &added, 10);
+
+        let found: Vec<(&str, &str, &MatchSource)> = matches
+            .iter()
+            .map(|m| (m.name.as_str(), m.bucket.as_str(), &m.match_source))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("foo", "bob-extras", &MatchSource::Name),
+                ("foo-helper", "tools", &MatchSource::Name),
+                ("bar", "tools", &MatchSource::Binary),
+            ]
+        );
+
+        assert_eq!(search_index(&index(), &query, &added, 1).len(), 1);
+    }
+}
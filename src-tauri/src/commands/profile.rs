@@ -5,17 +5,17 @@
 //! rScoop's own preferences. Profiles are portable: save one on machine A,
 //! load it on machine B.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use tauri::{AppHandle, Runtime, State};
 use tauri_plugin_store::StoreExt;
 
-use crate::commands::bucket::get_buckets;
+use crate::commands::bucket::list_buckets;
 use crate::commands::bucket_install::{install_bucket, BucketInstallOptions};
-use crate::commands::hold::list_held_packages;
-use crate::commands::installed::get_installed_packages_full;
+use crate::commands::hold::held_packages;
+use crate::commands::installed::{get_installed_packages_full, scan_installed_packages};
 use crate::operations::{self, EnqueueAction};
 use crate::state::AppState;
 
@@ -79,7 +79,7 @@ pub struct ProfileBucket {
 ///
 /// Also returns a list of non-fatal warnings so the caller can surface them
 /// to the user without failing the operation.
-fn parse_profile_lenient(json: &str) -> Result<(Profile, Vec<String>), String> {
+pub(crate) fn parse_profile_lenient(json: &str) -> Result<(Profile, Vec<String>), String> {
     let mut warnings = Vec::new();
 
     let raw: Value = serde_json::from_str(json).map_err(|e| format!("Not valid JSON: {}", e))?;
@@ -182,10 +182,53 @@ pub async fn export_profile<R: Runtime>(
         include_secrets
     );
 
+    let want = |id: &str| groups.iter().any(|g| g == id);
+    let rscoop_settings = if want("rscoopSettings") {
+        let mut map = Map::new();
+        let store = app
+            .store(PathBuf::from(STORE_PATH))
+            .map_err(|e| e.to_string())?;
+        for key in RSCOOP_SETTING_KEYS {
+            if let Some(v) = store.get(*key) {
+                map.insert((*key).to_string(), v.clone());
+            }
+        }
+
+        if let Some(v) = store.get("scoop_path") {
+            map.insert("scoop_path".to_string(), v.clone());
+        }
+
+        if map.is_empty() {
+            None
+        } else {
+            Some(map)
+        }
+    } else {
+        None
+    };
+
+    let profile = build_profile(
+        &state.scoop_path(),
+        groups,
+        include_secrets,
+        rscoop_settings,
+    )
+    .await?;
+    serde_json::to_string_pretty(&profile).map_err(|e| e.to_string())
+}
+
+/// Collects the Scoop-managed groups of a profile from `scoop_path`.
+/// rScoop's own preferences live in the app store, so callers pass them in.
+pub async fn build_profile(
+    scoop_path: &Path,
+    groups: Vec<String>,
+    include_secrets: bool,
+    rscoop_settings: Option<Map<String, Value>>,
+) -> Result<Profile, String> {
     let want = |id: &str| groups.iter().any(|g| g == id);
 
     let apps = if want("apps") {
-        let pkgs = get_installed_packages_full(app.clone(), state.clone()).await?;
+        let pkgs = scan_installed_packages(scoop_path)?;
         let values = pkgs
             .into_iter()
             .map(|p| ProfileApp {
@@ -202,7 +245,7 @@ pub async fn export_profile<R: Runtime>(
     };
 
     let buckets = if want("buckets") {
-        let bs = get_buckets(app.clone(), state.clone()).await?;
+        let bs = list_buckets(scoop_path).await?;
         let values = bs
             .into_iter()
             .map(|b| ProfileBucket {
@@ -217,7 +260,7 @@ pub async fn export_profile<R: Runtime>(
     };
 
     let holds = if want("holds") {
-        Some(list_held_packages(app.clone(), state.clone()).await?)
+        Some(held_packages(scoop_path)?)
     } else {
         None
     };
@@ -232,31 +275,7 @@ pub async fn export_profile<R: Runtime>(
         None
     };
 
-    let rscoop_settings = if want("rscoopSettings") {
-        let mut map = Map::new();
-        let store = app
-            .store(PathBuf::from(STORE_PATH))
-            .map_err(|e| e.to_string())?;
-        for key in RSCOOP_SETTING_KEYS {
-            if let Some(v) = store.get(*key) {
-                map.insert((*key).to_string(), v.clone());
-            }
-        }
-
-        if let Some(v) = store.get("scoop_path") {
-            map.insert("scoop_path".to_string(), v.clone());
-        }
-
-        if map.is_empty() {
-            None
-        } else {
-            Some(map)
-        }
-    } else {
-        None
-    };
-
-    let profile = Profile {
+    Ok(Profile {
         schema: SCHEMA_VERSION.to_string(),
         exported_at: Some(chrono::Utc::now().to_rfc3339()),
         groups,
@@ -265,9 +284,7 @@ pub async fn export_profile<R: Runtime>(
         holds,
         scoop_config,
         rscoop_settings,
-    };
-
-    serde_json::to_string_pretty(&profile).map_err(|e| e.to_string())
+    })
 }
 
 /// Export the selected Scoop-managed state as a PowerShell setup script.
@@ -293,7 +310,7 @@ pub async fn export_profile_setup_script<R: Runtime>(
     Ok(render_profile_setup_script(&profile))
 }
 
-fn render_profile_setup_script(profile: &Profile) -> String {
+pub(crate) fn render_profile_setup_script(profile: &Profile) -> String {
     let mut script = String::new();
     let generated_at = profile.exported_at.as_deref().unwrap_or("unknown time");
     let groups = if profile.groups.is_empty() {
@@ -772,6 +789,7 @@ pub async fn import_profile(
                         name: b.name.clone(),
                         url: b.source.clone(),
                         force: false,
+                        depth: None,
                     },
                 )
                 .await
@@ -782,6 +800,9 @@ pub async fn import_profile(
                         bucket_name: b.name.clone(),
                         bucket_path: None,
                         manifest_count: None,
+                        changes: None,
+                        pinned_ref: None,
+                        network_error: false,
                     }
                 });
                 if res.success {
@@ -2,7 +2,9 @@ use execra::tauri::ExecraExt;
 use execra::Outcome;
 use tauri::AppHandle;
 
-use crate::commands::scoop_interpreter::{is_creep_phase, phase_range, scoop_interpreter};
+use crate::commands::scoop_interpreter::{
+    is_creep_phase, phase_range, scoop_interpreter, PackageResult,
+};
 use crate::commands::settings::is_pwsh_enabled;
 use crate::operations::{self, OperationWarning};
 
@@ -122,8 +124,8 @@ pub async fn run_operation(app: AppHandle, command: execra::Command) -> Result<O
                     },
                 );
             }
-            execra::Event::KnownErrorDetected { message, .. } => {
-                operations::set_known_error(app, message.clone());
+            execra::Event::KnownErrorDetected { code, message, .. } => {
+                operations::set_known_error(app, code.clone(), message.clone());
             }
             execra::Event::ProgressUpdated { progress, .. } => match progress {
                 execra::Progress::Indeterminate { hint: Some(hint) } => {
@@ -158,7 +160,10 @@ pub async fn run_operation(app: AppHandle, command: execra::Command) -> Result<O
                 operations::pop_phase(app);
             }
             execra::Event::FindingEmitted { finding, .. } => {
-                operations::push_finding(app, finding.clone());
+                match PackageResult::from_finding(finding) {
+                    Some(result) => operations::push_package_result(app, result),
+                    None => operations::push_finding(app, finding.clone()),
+                }
             }
             _ => {}
         })
@@ -232,6 +237,37 @@ pub async fn execute_scoop(
     }
 }
 
+/// Package arguments for `scoop update <a> <b> …`: plain names, optionally
+/// `bucket/name`, never anything Scoop could read as a flag.
+fn is_package_arg(package: &str) -> bool {
+    !package.is_empty()
+        && !package.starts_with(['-', '.', '/'])
+        && package
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | '@'))
+}
+
+/// `scoop update` for an explicit set of packages, in one run.
+pub async fn update_packages_outcome(
+    app: AppHandle,
+    packages: &[String],
+) -> Result<Outcome, String> {
+    if packages.is_empty() {
+        return Err("At least one package name is required to update.".into());
+    }
+    if let Some(invalid) = packages.iter().find(|p| !is_package_arg(p)) {
+        return Err(format!("Invalid package name: {}", invalid));
+    }
+    let label = match packages {
+        [package] => format!("Updating {}", package),
+        _ => format!("Updating {} packages", packages.len()),
+    };
+    let args = std::iter::once("update".to_string())
+        .chain(packages.iter().cloned())
+        .collect();
+    run_scoop_operation(app, args, label).await
+}
+
 pub async fn run_scoop_operation(
     app: AppHandle,
     args: Vec<String>,
@@ -3,7 +3,8 @@
 //! The mechanism — regex table, `$1` substitution, flat sequential phases,
 //! multi-line notes collection, on-exit error fallback — lives in
 //! [`execra::interpret`]. This module is now just the *data*: the Scoop rule
-//! table, the phase-weight model, and the creep predicate.
+//! table, the phase-weight model, and the creep predicate — plus a thin
+//! per-package tracker for multi-package updates (see [`PackageResult`]).
 //!
 //! ## What gets classified
 //!
@@ -29,6 +30,12 @@
 //! - **Summaries** — terminal success lines that replace the generic
 //!   "X completed successfully" status.
 //! - **Findings** — multi-line `Notes` block collected as `Finding::info`.
+//! - **Per-package results** — inside `scoop update *`, which package was
+//!   updated, skipped or failed. Emitted as findings with the
+//!   `scoop.update.package.*` codes and decoded by [`PackageResult`].
+//!
+//! Recorded transcripts for each of these live in `tests/transcripts/`;
+//! the golden tests below replay them and compare the event sequence.
 //!
 //! ## Invariants (enforced by [`execra::interpret::RuleInterpreter`])
 //!
@@ -42,7 +49,12 @@
 //!   fallback so the same error never fires twice.
 
 use execra::interpret::{FallbackPolicy, PhaseModel, Rule, RuleInterpreter};
-use execra::{rules, Interpreter};
+use execra::{
+    rules, Context, ExitCode, Finding, Interpreter, InterpreterEvent, Line, RelatedEntity,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
 
 /// Approximate fraction range each phase occupies in the overall job
 /// progress (0..=1). The bar fills monotonically as the pipeline moves
@@ -149,6 +161,10 @@ fn scoop_rules() -> Vec<Rule> {
             r"(?i)Folder in use";
         known, "scoop.access_denied", "Access denied: $1. You might need to restart.",
             r"^Access denied:\s+([^.]+)\.";
+        // 7-Zip / MSI / dark extraction. Scoop points at a log file on the
+        // next line; the raw transcript keeps it.
+        known, "scoop.extract_failed", "Failed to extract $1",
+            r"Failed to extract files from (.+?)\.?$";
         // Download pipeline.
         known, "scoop.download.failed", "Download failed (error $1): $2",
             r"^Download failed!\s*\(Error\s*([^)]+)\)\s*(.*)$";
@@ -190,6 +206,11 @@ fn scoop_rules() -> Vec<Rule> {
             r"^Warning: No hash in manifest\. SHA256 for '([^']+)' is";
         warning, "scoop.download.hash_skipped", "Hash verification skipped.",
             r"^Skipping hash verification";
+        // `uninstall --purge` leaves persisted data behind when a file in it
+        // is locked. The app itself is gone, so this is not a failure.
+        warning, "scoop.persist.remove_failed",
+            "Couldn't remove persisted data at $1; it may be in use.",
+            r"Couldn't remove '([^']*persist[^']*)'; it may be in use";
 
         // --- Phases (top-level pipeline; auto-exit each other) ---------
         // Top-level intent — populates the modal subtitle immediately,
@@ -208,7 +229,7 @@ fn scoop_rules() -> Vec<Rule> {
         // slice so hashing a multi-GB nupkg looks like progress, not a
         // stuck bar.
         enter_phase, "verify",   "Verifying download", r"^(?i)Checking hash";
-        enter_phase, "extract",  "Extracting $1", r"^(?i)Extracting (.+?)\.{2,}";
+        enter_phase, "extract",  "Extracting $1", r"^(?i)Extracting (.+?)\s*\.{2,}";
         enter_phase, "extract",  "Extracting",    r"^(?i)Extracting\b";
 
         // --- Byte progress (drives the real status-bar fill) -----------
@@ -234,18 +255,238 @@ fn scoop_rules() -> Vec<Rule> {
     ]
 }
 
+// --- Per-package results ----------------------------------------------------
+
+const PACKAGE_UPDATED: &str = "scoop.update.package.updated";
+const PACKAGE_SKIPPED: &str = "scoop.update.package.skipped";
+const PACKAGE_FAILED: &str = "scoop.update.package.failed";
+
+static UPDATING_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^Updating '([^']+)' \(([^)\s]+) -> ([^)\s]+)\)").unwrap());
+static INSTALLED_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"'([^']+)'\s+\(([^)]+)\)\s+was installed successfully").unwrap());
+static HELD_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"'([^']+)' is held to version\s+(\S+)").unwrap());
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageStatus {
+    Updated,
+    Skipped,
+    Failed,
+}
+
+/// What happened to one package inside a `scoop update` run. Travels from
+/// the interpreter to the operation as a finding: the code carries the
+/// status, `related` the package, and the message either `from -> to`
+/// (updated) or the reason (skipped / failed).
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct PackageResult {
+    pub package: String,
+    pub status: PackageStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl PackageResult {
+    fn updated(package: String, from: String, to: String) -> Self {
+        Self {
+            package,
+            status: PackageStatus::Updated,
+            from: Some(from),
+            to: Some(to),
+            reason: None,
+        }
+    }
+
+    fn not_updated(package: String, status: PackageStatus, reason: String) -> Self {
+        Self {
+            package,
+            status,
+            from: None,
+            to: None,
+            reason: Some(reason),
+        }
+    }
+
+    fn finding(&self) -> Finding {
+        let (code, message) = match self.status {
+            PackageStatus::Updated => (
+                PACKAGE_UPDATED,
+                format!(
+                    "{} -> {}",
+                    self.from.as_deref().unwrap_or_default(),
+                    self.to.as_deref().unwrap_or_default()
+                ),
+            ),
+            PackageStatus::Skipped => (PACKAGE_SKIPPED, self.reason.clone().unwrap_or_default()),
+            PackageStatus::Failed => (PACKAGE_FAILED, self.reason.clone().unwrap_or_default()),
+        };
+        Finding::info(code, message).with_related(RelatedEntity::Package(self.package.clone()))
+    }
+
+    /// Decodes a per-package finding; `None` for every other finding.
+    pub fn from_finding(finding: &Finding) -> Option<Self> {
+        let status = match finding.code.as_str() {
+            PACKAGE_UPDATED => PackageStatus::Updated,
+            PACKAGE_SKIPPED => PackageStatus::Skipped,
+            PACKAGE_FAILED => PackageStatus::Failed,
+            _ => return None,
+        };
+        let Some(RelatedEntity::Package(package)) = finding.related.clone() else {
+            return None;
+        };
+        Some(match status {
+            PackageStatus::Updated => {
+                let (from, to) = finding.message.split_once(" -> ")?;
+                Self::updated(package, from.to_string(), to.to_string())
+            }
+            _ => Self::not_updated(package, status, finding.message.clone()),
+        })
+    }
+}
+
+/// The package `scoop update` is working on, from its `Updating 'x' (a -> b)`
+/// line until it is installed, skipped or fails.
+struct InFlightUpdate {
+    package: String,
+    from: String,
+    to: String,
+}
+
+/// The rule interpreter plus per-package bookkeeping for updates. Scoop
+/// updates packages one after another and stops at the first hard error, so
+/// one in-flight package is all there is to track.
+pub struct ScoopInterpreter {
+    rules: RuleInterpreter<ScoopPhases>,
+    updating: Option<InFlightUpdate>,
+}
+
+impl ScoopInterpreter {
+    fn track(&mut self, text: &str, events: &[InterpreterEvent]) -> Vec<PackageResult> {
+        let mut results = Vec::new();
+
+        if let Some(caps) = UPDATING_RE.captures(text) {
+            // The previous package never reported back — Scoop moved on.
+            if let Some(prev) = self.updating.take() {
+                results.push(PackageResult::not_updated(
+                    prev.package,
+                    PackageStatus::Skipped,
+                    "Scoop moved on without updating it".to_string(),
+                ));
+            }
+            self.updating = Some(InFlightUpdate {
+                package: caps[1].to_string(),
+                from: caps[2].to_string(),
+                to: caps[3].to_string(),
+            });
+            return results;
+        }
+        if let Some(caps) = INSTALLED_RE.captures(text) {
+            if let Some(update) = self.updating.take_if(|u| u.package == caps[1]) {
+                results.push(PackageResult::updated(
+                    update.package,
+                    update.from,
+                    update.to,
+                ));
+            }
+            return results;
+        }
+        if let Some(caps) = HELD_RE.captures(text) {
+            self.updating.take_if(|u| u.package == caps[1]);
+            results.push(PackageResult::not_updated(
+                caps[1].to_string(),
+                PackageStatus::Skipped,
+                format!("held at {}", &caps[2]),
+            ));
+            return results;
+        }
+
+        for event in events {
+            let (status, message) = match event {
+                InterpreterEvent::KnownError { message, .. } => {
+                    (PackageStatus::Failed, message.as_str())
+                }
+                InterpreterEvent::Warning {
+                    code: Some(code), ..
+                } if code.ends_with(".running_process") => {
+                    (PackageStatus::Skipped, "a related process is still running")
+                }
+                _ => continue,
+            };
+            if let Some(update) = self.updating.take() {
+                results.push(PackageResult::not_updated(
+                    update.package,
+                    status,
+                    message.to_string(),
+                ));
+            }
+        }
+        results
+    }
+}
+
+fn package_findings(results: Vec<PackageResult>) -> impl Iterator<Item = InterpreterEvent> {
+    results.into_iter().map(|result| InterpreterEvent::Finding {
+        finding: result.finding(),
+    })
+}
+
+impl Interpreter for ScoopInterpreter {
+    fn on_line(&mut self, ctx: &Context, line: &Line) -> Vec<InterpreterEvent> {
+        let mut events = self.rules.on_line(ctx, line);
+        let results = self.track(&line.text, &events);
+        events.extend(package_findings(results));
+        events
+    }
+
+    fn on_exit(&mut self, ctx: &Context, exit: &ExitCode) -> Vec<InterpreterEvent> {
+        let mut events = self.rules.on_exit(ctx, exit);
+        if let Some(update) = self.updating.take() {
+            let error = events.iter().find_map(|e| match e {
+                InterpreterEvent::KnownError { message, .. } => Some(message.clone()),
+                _ => None,
+            });
+            let result = match error {
+                Some(message) => {
+                    PackageResult::not_updated(update.package, PackageStatus::Failed, message)
+                }
+                None if !exit.is_success() => PackageResult::not_updated(
+                    update.package,
+                    PackageStatus::Failed,
+                    "Scoop exited before the update finished".to_string(),
+                ),
+                None => PackageResult::not_updated(
+                    update.package,
+                    PackageStatus::Skipped,
+                    "Scoop finished without updating it".to_string(),
+                ),
+            };
+            events.extend(package_findings(vec![result]));
+        }
+        events
+    }
+}
+
 /// The Scoop interpreter: the rule table above, the [`ScoopPhases`] weight
-/// model, multi-line `Notes` collection, and an on-exit error fallback.
+/// model, multi-line `Notes` collection, an on-exit error fallback, and
+/// per-package update results.
 pub fn scoop_interpreter() -> impl Interpreter {
-    RuleInterpreter::new(scoop_rules(), ScoopPhases)
-        .notes("Notes", "scoop.notes")
-        .fallback(FallbackPolicy::default().code("scoop.command_error"))
+    ScoopInterpreter {
+        rules: RuleInterpreter::new(scoop_rules(), ScoopPhases)
+            .notes("Notes", "scoop.notes")
+            .fallback(FallbackPolicy::default().code("scoop.command_error")),
+        updating: None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use execra::{Context, ExitCode, InterpreterEvent, Line};
 
     /// Drive the real Scoop interpreter through a sequence of (stream, line)
     /// pairs plus an exit code, collecting every event it emits. This is an
@@ -503,4 +744,155 @@ mod tests {
             "expected stderr fallback, got {evs:?}"
         );
     }
+
+    // --- Golden transcripts ---------------------------------------------
+    //
+    // `tests/transcripts/<name>.log` is a recorded Scoop run: `>` marks a
+    // stdout line, `!` a stderr line, and the final `exit <code>` line the
+    // exit status. `<name>.events` next to it is the expected event
+    // sequence, one event per line. After an intended rule change, rerun
+    // with `UPDATE_GOLDEN=1` and review the diff.
+
+    fn transcripts_dir() -> std::path::PathBuf {
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/transcripts")
+    }
+
+    fn replay(transcript: &str) -> Vec<InterpreterEvent> {
+        let mut lines = Vec::new();
+        let mut exit = None;
+        for raw in transcript.lines() {
+            if let Some(code) = raw.strip_prefix("exit ") {
+                exit = Some(ExitCode::from_code(code.trim().parse().unwrap()));
+                continue;
+            }
+            let (stream, text) = if let Some(text) = raw.strip_prefix('>') {
+                (execra::Stream::Stdout, text)
+            } else if let Some(text) = raw.strip_prefix('!') {
+                (execra::Stream::Stderr, text)
+            } else {
+                panic!("transcript line without a stream marker: {raw:?}");
+            };
+            lines.push((stream, text.strip_prefix(' ').unwrap_or(text)));
+        }
+        drive(&lines, exit.expect("transcript has no `exit` line"))
+    }
+
+    fn render_event(event: &InterpreterEvent) -> String {
+        match event {
+            InterpreterEvent::EnterPhase { name, label } => {
+                format!(
+                    "enter_phase {name} {:?}",
+                    label.as_deref().unwrap_or_default()
+                )
+            }
+            InterpreterEvent::UpdatePhase { label } => format!("update_phase {label:?}"),
+            InterpreterEvent::ExitPhase => "exit_phase".to_string(),
+            InterpreterEvent::Progress { progress } => match progress {
+                execra::Progress::Indeterminate { hint } => {
+                    format!("progress {:?}", hint.as_deref().unwrap_or_default())
+                }
+                _ => format!("progress {:.3}", progress.as_fraction().unwrap_or_default()),
+            },
+            InterpreterEvent::Label { text } => format!("label {text:?}"),
+            InterpreterEvent::Warning { code, message } => {
+                format!("warning {} {message:?}", code.as_deref().unwrap_or("-"))
+            }
+            InterpreterEvent::KnownError { code, message } => {
+                format!("known_error {code} {message:?}")
+            }
+            InterpreterEvent::Finding { finding } => {
+                let related = match &finding.related {
+                    Some(RelatedEntity::Package(package)) => format!(" [{package}]"),
+                    _ => String::new(),
+                };
+                format!("finding {}{related} {:?}", finding.code, finding.message)
+            }
+            InterpreterEvent::Prompt { prompt } => format!("prompt {prompt:?}"),
+            InterpreterEvent::Summary { text } => format!("summary {text:?}"),
+        }
+    }
+
+    #[test]
+    fn golden_transcripts() {
+        let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+        let mut logs: Vec<_> = std::fs::read_dir(transcripts_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+            .collect();
+        logs.sort();
+        assert!(!logs.is_empty(), "no transcripts found");
+
+        let mut mismatched = Vec::new();
+        for log in &logs {
+            let transcript = std::fs::read_to_string(log).unwrap();
+            let mut actual: String = replay(&transcript)
+                .iter()
+                .map(|event| render_event(event) + "\n")
+                .collect();
+            if actual.is_empty() {
+                actual.push('\n');
+            }
+            let golden = log.with_extension("events");
+            if update {
+                std::fs::write(&golden, &actual).unwrap();
+                continue;
+            }
+            let expected = std::fs::read_to_string(&golden).unwrap_or_default();
+            if expected.replace("\r\n", "\n") != actual {
+                mismatched.push(format!(
+                    "{}:\n--- expected\n{expected}--- actual\n{actual}",
+                    log.display()
+                ));
+            }
+        }
+        assert!(
+            mismatched.is_empty(),
+            "golden mismatch (rerun with UPDATE_GOLDEN=1 to accept):\n{}",
+            mismatched.join("\n")
+        );
+    }
+
+    #[test]
+    fn update_all_reports_each_package() {
+        let transcript = std::fs::read_to_string(transcripts_dir().join("update_all.log")).unwrap();
+        let results: Vec<PackageResult> = replay(&transcript)
+            .iter()
+            .filter_map(|event| match event {
+                InterpreterEvent::Finding { finding } => PackageResult::from_finding(finding),
+                _ => None,
+            })
+            .collect();
+        let summary: Vec<(&str, PackageStatus)> = results
+            .iter()
+            .map(|r| (r.package.as_str(), r.status))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("nodejs", PackageStatus::Skipped),
+                ("git", PackageStatus::Updated),
+                ("firefox", PackageStatus::Skipped),
+                ("7zip", PackageStatus::Failed),
+            ]
+        );
+        assert_eq!(results[0].reason.as_deref(), Some("held at 20.11.0"));
+        assert_eq!(
+            (results[1].from.as_deref(), results[1].to.as_deref()),
+            (Some("2.47.0"), Some("2.47.1"))
+        );
+        assert_eq!(
+            results[3].reason.as_deref(),
+            Some("Downloaded file hash did not match manifest")
+        );
+    }
+
+    #[test]
+    fn single_installs_report_no_package_results() {
+        let transcript = std::fs::read_to_string(transcripts_dir().join("install.log")).unwrap();
+        assert!(!replay(&transcript).iter().any(|event| matches!(
+            event,
+            InterpreterEvent::Finding { finding } if PackageResult::from_finding(finding).is_some()
+        )));
+    }
 }
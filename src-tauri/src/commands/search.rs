@@ -1,162 +1,186 @@
 //! Commands for searching Scoop packages.
-use crate::commands::installed::get_installed_packages_full;
-use crate::models::{MatchSource, ScoopPackage, SearchResult};
+use crate::commands::installed::resolved_root;
+use crate::commands::manifest_cache::{self, CachedManifest, ManifestCache};
+use crate::commands::package_index;
+use crate::commands::search_rank::{self, SearchQuery};
+use crate::models::{ScoopPackage, SearchResult};
 use crate::state::AppState;
-use crate::utils;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use once_cell::sync::Lazy;
 use rayon::prelude::*;
-use regex::Regex;
-use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 use tauri::Manager;
 use tokio::sync::Mutex;
 
-#[derive(Clone, Debug)]
-pub struct CachedManifest {
-    pub package: ScoopPackage,
-    pub bin_strings: Vec<String>,
-}
-
 // Global cache for manifest content to avoid re-scanning the filesystem and re-parsing JSON on every search.
-static MANIFEST_CACHE: Lazy<Mutex<Option<Vec<CachedManifest>>>> = Lazy::new(|| Mutex::new(None));
-
-/// Finds all `.json` manifest files in a given bucket.
-fn find_manifests_in_bucket(bucket_path: &Path) -> Vec<PathBuf> {
-    utils::bucket_manifest_paths(bucket_path)
+static MANIFEST_CACHE: Lazy<Arc<Mutex<Option<ManifestCache>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(None)));
+
+// Watcher on the buckets directory, kept alive for the lifetime of the app.
+static BUCKETS_WATCHER: Lazy<std::sync::Mutex<Option<(PathBuf, RecommendedWatcher)>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
+
+/// How long to collect filesystem events before refreshing; a bucket update
+/// touches many files in quick succession.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+fn manifest_index_path() -> Result<PathBuf, String> {
+    let data_dir = dirs::data_dir().ok_or("Could not find data directory")?;
+    Ok(data_dir
+        .join("rscoop")
+        .join("cache")
+        .join("manifest_index.json"))
 }
 
-/// Parses a Scoop package manifest file to extract package information and binary search strings.
-fn parse_package_from_manifest(path: &Path, bucket: &str) -> Option<CachedManifest> {
-    let file_name = path.file_stem().and_then(|s| s.to_str())?.to_string();
-
-    let content = std::fs::read_to_string(path).ok()?;
-    let json: Value = serde_json::from_str(&content).ok()?;
-
-    let version = json.get("version").and_then(|v| v.as_str())?.to_string();
-
-    let package = ScoopPackage {
-        name: file_name,
-        version,
-        source: bucket.to_string(),
-        match_source: MatchSource::Name,
-        ..Default::default()
-    };
-
-    // Pre-extract all potential binary match strings
-    let mut bin_strings = Vec::new();
-    if let Some(bin_val) = json.get("bin") {
-        match bin_val {
-            Value::String(s) => bin_strings.push(s.to_string()),
-            Value::Array(arr) => {
-                for entry in arr {
-                    match entry {
-                        Value::String(s) => bin_strings.push(s.to_string()),
-                        Value::Object(obj) => {
-                            for k in obj.keys() {
-                                bin_strings.push(k.to_string());
-                            }
-                            for v in obj.values() {
-                                if let Some(s) = v.as_str() {
-                                    bin_strings.push(s.to_string());
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
-            Value::Object(obj) => {
-                for k in obj.keys() {
-                    bin_strings.push(k.to_string());
-                }
-                for v in obj.values() {
-                    if let Some(s) = v.as_str() {
-                        bin_strings.push(s.to_string());
-                    }
-                }
-            }
-            _ => {}
-        }
-    }
-
-    Some(CachedManifest {
-        package,
-        bin_strings,
-    })
+/// Runs `f` on the locked cache from a blocking thread, since refreshing stats
+/// and parses files.
+async fn with_cache<T, F>(f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut Option<ManifestCache>) -> T + Send + 'static,
+{
+    let mut guard = MANIFEST_CACHE.clone().lock_owned().await;
+    tokio::task::spawn_blocking(move || f(&mut guard))
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Scans all bucket directories to find package manifests and populates the cache.
-async fn populate_manifest_cache(scoop_path: &Path) -> Result<Vec<CachedManifest>, String> {
-    let buckets_path = scoop_path.join("buckets");
-    if !tokio::fs::try_exists(&buckets_path).await.unwrap_or(false) {
-        return Err("Scoop buckets directory not found".to_string());
+fn save_index(cache: &ManifestCache) {
+    if let Err(e) = manifest_index_path().and_then(|path| cache.save(&path)) {
+        log::warn!("Failed to save manifest index: {}", e);
     }
+}
 
-    let mut read_dir = tokio::fs::read_dir(&buckets_path)
-        .await
-        .map_err(|e| format!("Failed to read buckets directory: {}", e))?;
-    let mut manifest_paths = Vec::new();
-
-    while let Ok(Some(entry)) = read_dir.next_entry().await {
-        if entry.path().is_dir() {
-            let bucket_name = entry.file_name().to_string_lossy().to_string();
-            let bucket_manifests = find_manifests_in_bucket(&entry.path());
-            manifest_paths.extend(
-                bucket_manifests
-                    .into_iter()
-                    .map(|path| (path, bucket_name.clone())),
+/// Makes sure `slot` holds an up-to-date cache for `buckets_dir`, restoring the
+/// on-disk index if nothing is loaded yet. Returns `true` if every manifest had
+/// to be parsed from scratch.
+fn sync_cache(slot: &mut Option<ManifestCache>, buckets_dir: &Path) -> Result<bool, String> {
+    if let Some(cache) = slot.as_mut().filter(|c| c.buckets_dir() == buckets_dir) {
+        let stats = cache.refresh()?;
+        if stats.changed() {
+            log::info!(
+                "Manifest cache refreshed: {} parsed, {} removed, {} unchanged",
+                stats.parsed,
+                stats.removed,
+                stats.unchanged
             );
+            save_index(cache);
         }
+        return Ok(false);
     }
 
-    // Parse all manifests in parallel
-    let cached_manifests = tokio::task::spawn_blocking(move || {
-        manifest_paths
-            .par_iter()
-            .filter_map(|(path, bucket)| parse_package_from_manifest(path, bucket))
-            .collect::<Vec<_>>()
-    })
-    .await
-    .map_err(|e| e.to_string())?;
-
-    Ok(cached_manifests)
+    let restored = manifest_index_path()
+        .ok()
+        .and_then(|path| ManifestCache::load(&path, buckets_dir));
+    let is_cold = restored.is_none();
+    let mut cache = restored.unwrap_or_else(|| ManifestCache::new(buckets_dir));
+    let stats = cache.refresh()?;
+    log::info!(
+        "Manifest cache loaded ({}): {} parsed, {} removed, {} unchanged",
+        if is_cold { "cold" } else { "from index" },
+        stats.parsed,
+        stats.removed,
+        stats.unchanged
+    );
+    if is_cold || stats.changed() {
+        save_index(&cache);
+    }
+    *slot = Some(cache);
+    Ok(is_cold)
 }
 
 /// Acquires a lock on the manifest cache and populates it if it's empty.
-async fn get_manifests<R: tauri::Runtime>(
+async fn get_manifests(scoop_path: &Path) -> Result<(Arc<Vec<CachedManifest>>, bool), String> {
+    let buckets_dir = scoop_path.join("buckets");
+    with_cache(move |slot| {
+        let loaded = slot
+            .as_ref()
+            .is_some_and(|cache| cache.buckets_dir() == buckets_dir);
+        let is_cold = if loaded {
+            false
+        } else {
+            sync_cache(slot, &buckets_dir)?
+        };
+        let cache = slot.as_mut().ok_or("Manifest cache unavailable")?;
+        Ok((cache.manifests(), is_cold))
+    })
+    .await?
+}
+
+/// Parsed manifests of every bucket, loading the cache if needed.
+pub(crate) async fn cached_manifests<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
-) -> Result<(Vec<CachedManifest>, bool), String> {
-    let mut guard = MANIFEST_CACHE.lock().await;
-    let is_cold = guard.is_none();
+) -> Result<Arc<Vec<CachedManifest>>, String> {
+    let scoop_path = app.state::<AppState>().scoop_path();
+    get_manifests(&scoop_path)
+        .await
+        .map(|(manifests, _)| manifests)
+}
 
-    if is_cold {
-        log::info!("Cold search: Populating manifest cache.");
-        let state = app.state::<AppState>();
-        let scoop_path = state.scoop_path();
-        let cached = populate_manifest_cache(&scoop_path).await?;
-        *guard = Some(cached.clone());
-        Ok((cached, true))
-    } else {
-        Ok((guard.as_ref().unwrap().clone(), false))
+/// Starts watching `buckets/` so manifest edits, bucket updates and added or
+/// removed buckets are applied to the cache as they happen.
+fn watch_buckets(scoop_path: &Path) -> Result<(), String> {
+    let buckets_dir = scoop_path.join("buckets");
+    let mut watcher_slot = BUCKETS_WATCHER.lock().map_err(|e| e.to_string())?;
+    if matches!(watcher_slot.as_ref(), Some((dir, _)) if *dir == buckets_dir) {
+        return Ok(());
     }
-}
 
-/// Builds a regex pattern for searching, supporting exact and partial matches.
-fn build_search_regex(term: &str) -> Result<Regex, String> {
-    let trimmed = term.trim();
-    let pattern_str = if trimmed.starts_with('"') && trimmed.ends_with('"') && trimmed.len() > 1 {
-        // Exact match: "term"
-        let inner = &trimmed[1..trimmed.len() - 1];
-        let normalized = inner.trim().replace(' ', "-");
-        format!("(?i)^{}$", regex::escape(&normalized))
-    } else {
-        // Partial match: term
-        let normalized = trimmed.replace(' ', "-");
-        format!("(?i){}", regex::escape(&normalized))
-    };
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let event_dir = buckets_dir.clone();
+    let mut watcher =
+        notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+            Ok(event) => {
+                for path in &event.paths {
+                    if let Some(bucket) = manifest_cache::bucket_of_change(&event_dir, path) {
+                        let _ = tx.send(bucket);
+                    }
+                }
+            }
+            Err(e) => log::warn!("Buckets watcher error: {}", e),
+        })
+        .map_err(|e| format!("Failed to create buckets watcher: {}", e))?;
+    watcher
+        .watch(&buckets_dir, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch buckets directory: {}", e))?;
+
+    let refresh_dir = buckets_dir.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(first) = rx.recv().await {
+            tokio::time::sleep(WATCH_DEBOUNCE).await;
+            let mut changed = BTreeSet::from([first]);
+            while let Ok(bucket) = rx.try_recv() {
+                changed.insert(bucket);
+            }
+
+            let dir = refresh_dir.clone();
+            let result = with_cache(move |slot| {
+                let Some(cache) = slot.as_mut().filter(|c| c.buckets_dir() == dir) else {
+                    return;
+                };
+                let stats = cache.refresh_buckets(changed.iter().cloned());
+                if stats.changed() {
+                    log::info!(
+                        "Applied bucket changes to manifest cache ({:?}): {} parsed, {} removed",
+                        changed,
+                        stats.parsed,
+                        stats.removed
+                    );
+                    save_index(cache);
+                }
+            })
+            .await;
+            if let Err(e) = result {
+                log::warn!("Failed to apply bucket changes: {}", e);
+            }
+        }
+    });
 
-    Regex::new(&pattern_str).map_err(|e| e.to_string())
+    *watcher_slot = Some((buckets_dir, watcher));
+    Ok(())
 }
 
 /// Searches for Scoop packages based on a search term.
@@ -169,10 +193,25 @@ pub async fn search_scoop<R: tauri::Runtime>(
         return Ok(SearchResult::default());
     }
 
+    let state = app.state::<AppState>();
+    resolved_root(app.clone(), &state).await.search(&term).await
+}
+
+/// Searches the manifests of every bucket under `scoop_path`, marking the
+/// packages in `installed` as installed.
+pub async fn search_packages(
+    scoop_path: &Path,
+    term: &str,
+    installed: &[ScoopPackage],
+) -> Result<SearchResult, String> {
+    if term.is_empty() {
+        return Ok(SearchResult::default());
+    }
+
     log::info!("search_scoop: Starting search for term: '{}'", term);
     let search_start = std::time::Instant::now();
 
-    let (manifests, is_cold) = get_manifests(app.clone()).await?;
+    let (manifests, is_cold) = get_manifests(scoop_path).await?;
     let cache_time = search_start.elapsed();
 
     if is_cold {
@@ -188,32 +227,20 @@ pub async fn search_scoop<R: tauri::Runtime>(
         );
     }
 
-    let pattern = build_search_regex(&term)?;
+    let query = SearchQuery::parse(term);
+    let remote_query = query.clone();
 
     // Perform the search in memory
     let mut packages: Vec<ScoopPackage> = tokio::task::spawn_blocking(move || {
         manifests
             .par_iter()
             .filter_map(|cached| {
-                let name_matches = pattern.is_match(&cached.package.name);
-
-                let match_source = if name_matches {
-                    MatchSource::Name
-                } else {
-                    let does_bin_match = cached.bin_strings.iter().any(|s| pattern.is_match(s));
-                    if does_bin_match {
-                        MatchSource::Binary
-                    } else {
-                        MatchSource::None
-                    }
-                };
-
-                if match_source == MatchSource::None {
-                    return None;
-                }
-
+                let ranked = search_rank::rank(&query, &cached.search_fields())?;
                 let mut pkg = cached.package.clone();
-                pkg.match_source = match_source;
+                pkg.match_source = ranked.source;
+                pkg.match_score = ranked.score;
+                pkg.matched_text = Some(ranked.text);
+                pkg.highlights = ranked.highlights;
                 Some(pkg)
             })
             .collect()
@@ -221,21 +248,30 @@ pub async fn search_scoop<R: tauri::Runtime>(
     .await
     .map_err(|e| e.to_string())?;
 
-    // Determine which of the found packages are already installed.
-    let state = app.state::<AppState>();
-    if let Ok(installed_pkgs) = get_installed_packages_full(app.clone(), state).await {
-        let installed_set: HashSet<String> = installed_pkgs
-            .into_iter()
-            .map(|p| p.name.to_lowercase())
-            .collect();
-
-        for pkg in &mut packages {
-            if installed_set.contains(&pkg.name.to_lowercase()) {
-                pkg.is_installed = true;
-            }
+    let installed_set: HashSet<String> = installed.iter().map(|p| p.name.to_lowercase()).collect();
+    for pkg in &mut packages {
+        if installed_set.contains(&pkg.name.to_lowercase()) {
+            pkg.is_installed = true;
         }
     }
 
+    for pkg in &mut packages {
+        pkg.match_score += search_rank::boost(pkg.is_installed, &pkg.source);
+    }
+    packages.sort_by(|a, b| {
+        b.match_score
+            .cmp(&a.match_score)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    // Packages in directory buckets that are not added, if the index exists.
+    let scoop_path = scoop_path.to_path_buf();
+    let remote_matches = tokio::task::spawn_blocking(move || {
+        package_index::remote_matches(&scoop_path, &remote_query)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
     let total_time = search_start.elapsed();
     log::info!(
         "search_scoop: ✓ Found {} packages matching '{}' in {:.2}s",
@@ -244,11 +280,16 @@ pub async fn search_scoop<R: tauri::Runtime>(
         total_time.as_secs_f64()
     );
 
-    Ok(SearchResult { packages, is_cold })
+    Ok(SearchResult {
+        packages,
+        is_cold,
+        remote_matches,
+    })
 }
 
 /// Warms (populates) the global manifest cache if it is empty. Intended for use by the
-/// cold-start routine so that the first search from the UI is instant.
+/// cold-start routine so that the first search from the UI is instant. Also starts
+/// watching the buckets directory so later changes are applied incrementally.
 ///
 /// Returns Ok(()) on success or an error string if the cache population failed.
 pub async fn warm_manifest_cache<R: tauri::Runtime>(
@@ -256,9 +297,16 @@ pub async fn warm_manifest_cache<R: tauri::Runtime>(
 ) -> Result<(), String> {
     log::info!("warm_manifest_cache: Starting manifest cache warm-up");
     let start_time = std::time::Instant::now();
-    let result = get_manifests(app).await;
+    let scoop_path = app.state::<AppState>().scoop_path();
+    let result = get_manifests(&scoop_path).await;
     let elapsed = start_time.elapsed();
 
+    if result.is_ok() {
+        if let Err(e) = watch_buckets(&scoop_path) {
+            log::warn!("warm_manifest_cache: {}", e);
+        }
+    }
+
     match result {
         Ok((paths, was_cold)) => {
             log::info!(
@@ -280,27 +328,17 @@ pub async fn warm_manifest_cache<R: tauri::Runtime>(
     }
 }
 
-/// Invalidates and immediately re-warms the global manifest cache.
-/// This should be called after operations that change the available packages,
-/// such as installing or uninstalling a package or adding/removing buckets.
+/// Brings the global manifest cache up to date after operations that change the
+/// available packages, such as updating buckets or adding/removing them. Only
+/// manifests whose files changed are re-parsed.
 pub async fn invalidate_manifest_cache(scoop_path: &Path) {
-    let mut guard = MANIFEST_CACHE.lock().await;
-    *guard = None;
-    log::info!("Manifest cache invalidated, re-warming...");
-
+    let buckets_dir = scoop_path.join("buckets");
     let start = std::time::Instant::now();
-    match populate_manifest_cache(scoop_path).await {
-        Ok(manifests) => {
-            let count = manifests.len();
-            *guard = Some(manifests);
-            log::info!(
-                "Manifest cache re-warmed in {:.2}s ({} manifests)",
-                start.elapsed().as_secs_f64(),
-                count
-            );
-        }
-        Err(e) => {
-            log::warn!("Failed to re-warm manifest cache: {}", e);
-        }
+    match with_cache(move |slot| sync_cache(slot, &buckets_dir)).await {
+        Ok(Ok(_)) => log::info!(
+            "Manifest cache refreshed in {:.2}s",
+            start.elapsed().as_secs_f64()
+        ),
+        Ok(Err(e)) | Err(e) => log::warn!("Failed to refresh manifest cache: {}", e),
     }
 }
@@ -0,0 +1,351 @@
+//! Scoring for package search.
+//!
+//! Every searchable manifest field is matched against the query and scored
+//! by field weight and match quality: exact > prefix > substring > fuzzy.
+//! Fuzzy matches tolerate one typo (two for long words) per token.
+use crate::models::{MatchSource, MatchSpan};
+
+/// Bonus for packages that are already installed.
+pub const INSTALLED_BOOST: u32 = 300;
+/// Bonus for packages from the official `main` and `extras` buckets.
+pub const OFFICIAL_BUCKET_BOOST: u32 = 100;
+/// Prose fields (description, homepage, license) only match queries at
+/// least this long; shorter ones would match nearly every manifest.
+const MIN_PROSE_QUERY_LEN: usize = 3;
+/// Shortest token that may match with a typo.
+const MIN_FUZZY_LEN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    Fuzzy = 1,
+    Substring = 2,
+    Prefix = 3,
+    Exact = 4,
+}
+
+/// A parsed search term.
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    /// Lowercased term with spaces as dashes, for package and binary names.
+    name_term: String,
+    /// Lowercased term as typed, for prose fields.
+    text_term: String,
+    /// Quoted queries (`"term"`) only accept exact name or binary matches.
+    exact_only: bool,
+}
+
+impl SearchQuery {
+    pub fn parse(term: &str) -> Self {
+        let trimmed = term.trim();
+        let (inner, exact_only) =
+            if trimmed.starts_with('"') && trimmed.ends_with('"') && trimmed.len() > 1 {
+                (trimmed[1..trimmed.len() - 1].trim(), true)
+            } else {
+                (trimmed, false)
+            };
+        let text_term = lowercase_chars(inner);
+        Self {
+            name_term: text_term.replace(' ', "-"),
+            text_term,
+            exact_only,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text_term.is_empty()
+    }
+}
+
+/// Searchable fields of one manifest.
+#[derive(Debug, Clone, Default)]
+pub struct SearchFields<'a> {
+    pub name: &'a str,
+    pub bins: &'a [String],
+    pub shortcuts: &'a [String],
+    pub description: &'a str,
+    pub homepage: &'a str,
+    pub license: &'a str,
+}
+
+/// Best match of a query against one manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankedMatch {
+    pub source: MatchSource,
+    pub kind: MatchKind,
+    pub score: u32,
+    /// The field value that matched; `highlights` index into it.
+    pub text: String,
+    pub highlights: Vec<MatchSpan>,
+}
+
+fn field_weight(source: &MatchSource) -> u32 {
+    match source {
+        MatchSource::Name => 1000,
+        MatchSource::Binary => 600,
+        MatchSource::Shortcut => 500,
+        MatchSource::Description => 200,
+        MatchSource::Homepage => 150,
+        MatchSource::License => 100,
+        MatchSource::None => 0,
+    }
+}
+
+/// Lowercases char by char so char offsets stay aligned with the original.
+fn lowercase_chars(text: &str) -> String {
+    text.chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect()
+}
+
+/// Optimal string alignment distance, giving up once it exceeds `max`.
+fn edit_distance(a: &[char], b: &[char], max: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let mut prev2: Vec<usize> = Vec::new();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (prev[j] + 1).min(row[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(prev2[j - 2] + 1);
+            }
+        }
+        if row.iter().min().copied().unwrap_or(0) > max {
+            return None;
+        }
+        prev2 = std::mem::replace(&mut prev, row);
+    }
+    let distance = prev[b.len()];
+    (distance <= max).then_some(distance)
+}
+
+fn allowed_typos(len: usize) -> usize {
+    if len < MIN_FUZZY_LEN {
+        0
+    } else if len < 8 {
+        1
+    } else {
+        2
+    }
+}
+
+/// Alphanumeric runs of `text` as (char start, char end).
+fn tokens(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in chars.iter().enumerate() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, chars.len()));
+    }
+    spans
+}
+
+/// Matches `term` (already lowercased) against `text`.
+pub fn match_text(term: &str, text: &str, allow_fuzzy: bool) -> Option<(MatchKind, MatchSpan)> {
+    if term.is_empty() || text.is_empty() {
+        return None;
+    }
+    let lower = lowercase_chars(text);
+    let term_len = term.chars().count();
+
+    if lower == term {
+        return Some((MatchKind::Exact, MatchSpan::new(0, term_len)));
+    }
+    if lower.starts_with(term) {
+        return Some((MatchKind::Prefix, MatchSpan::new(0, term_len)));
+    }
+    if let Some(byte_idx) = lower.find(term) {
+        let start = lower[..byte_idx].chars().count();
+        return Some((
+            MatchKind::Substring,
+            MatchSpan::new(start, start + term_len),
+        ));
+    }
+
+    let max_typos = allowed_typos(term_len);
+    if !allow_fuzzy || max_typos == 0 {
+        return None;
+    }
+    let term_chars: Vec<char> = term.chars().collect();
+    let chars: Vec<char> = lower.chars().collect();
+    if edit_distance(&chars, &term_chars, max_typos).is_some() {
+        return Some((MatchKind::Fuzzy, MatchSpan::new(0, chars.len())));
+    }
+    tokens(&chars)
+        .into_iter()
+        .filter_map(|(start, end)| {
+            edit_distance(&chars[start..end], &term_chars, max_typos)
+                .map(|distance| (distance, start, end))
+        })
+        .min()
+        .map(|(_, start, end)| (MatchKind::Fuzzy, MatchSpan::new(start, end)))
+}
+
+/// Scores a manifest against the query, keeping the best-scoring field.
+pub fn rank(query: &SearchQuery, fields: &SearchFields) -> Option<RankedMatch> {
+    if query.is_empty() {
+        return None;
+    }
+
+    let mut candidates: Vec<(MatchSource, &str, &str)> =
+        vec![(MatchSource::Name, fields.name, &query.name_term)];
+    candidates.extend(
+        fields
+            .bins
+            .iter()
+            .map(|bin| (MatchSource::Binary, bin.as_str(), query.name_term.as_str())),
+    );
+    if !query.exact_only {
+        candidates.extend(fields.shortcuts.iter().map(|name| {
+            (
+                MatchSource::Shortcut,
+                name.as_str(),
+                query.text_term.as_str(),
+            )
+        }));
+        if query.text_term.chars().count() >= MIN_PROSE_QUERY_LEN {
+            candidates.push((
+                MatchSource::Description,
+                fields.description,
+                &query.text_term,
+            ));
+            candidates.push((MatchSource::Homepage, fields.homepage, &query.text_term));
+            candidates.push((MatchSource::License, fields.license, &query.text_term));
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(|(source, text, term)| {
+            // Typos are only forgiven in names; prose is too noisy.
+            let allow_fuzzy = !query.exact_only
+                && matches!(
+                    source,
+                    MatchSource::Name | MatchSource::Binary | MatchSource::Shortcut
+                );
+            let (kind, span) = match_text(term, text, allow_fuzzy)?;
+            if query.exact_only && kind != MatchKind::Exact {
+                return None;
+            }
+            Some(RankedMatch {
+                score: field_weight(&source) * kind as u32,
+                source,
+                kind,
+                text: text.to_string(),
+                highlights: vec![span],
+            })
+        })
+        // Ties keep the earlier (more important) field.
+        .reduce(|best, next| if next.score > best.score { next } else { best })
+}
+
+/// Ranking bonus from package context.
+pub fn boost(is_installed: bool, bucket: &str) -> u32 {
+    let mut boost = 0;
+    if is_installed {
+        boost += INSTALLED_BOOST;
+    }
+    if bucket.eq_ignore_ascii_case("main") || bucket.eq_ignore_ascii_case("extras") {
+        boost += OFFICIAL_BUCKET_BOOST;
+    }
+    boost
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields<'a>(name: &'a str, description: &'a str) -> SearchFields<'a> {
+        SearchFields {
+            name,
+            description,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ranks_exact_over_prefix_over_substring_over_fuzzy() {
+        let query = SearchQuery::parse("git");
+        let score = |name| rank(&query, &fields(name, "")).map(|m| m.score);
+        let exact = score("git").unwrap();
+        let prefix = score("github-cli").unwrap();
+        let substring = score("lazygit").unwrap();
+        assert!(exact > prefix && prefix > substring);
+
+        let typo = SearchQuery::parse("fierfox");
+        let fuzzy = rank(&typo, &fields("firefox", "")).unwrap();
+        assert_eq!(fuzzy.kind, MatchKind::Fuzzy);
+        assert!(fuzzy.score < substring);
+        // Too short to forgive a typo.
+        assert!(rank(&SearchQuery::parse("gti"), &fields("git", "")).is_none());
+    }
+
+    #[test]
+    fn reports_matched_field_and_highlights() {
+        let bins = vec!["bin\\rg.exe".to_string()];
+        let shortcuts = vec!["Visual Studio Code".to_string()];
+        let manifest = SearchFields {
+            name: "vscode",
+            bins: &bins,
+            shortcuts: &shortcuts,
+            description: "A lightweight source code editor",
+            homepage: "https://code.visualstudio.com",
+            license: "MIT",
+        };
+
+        let shortcut = rank(&SearchQuery::parse("studio"), &manifest).unwrap();
+        assert_eq!(shortcut.source, MatchSource::Shortcut);
+        assert_eq!(shortcut.highlights, vec![MatchSpan::new(7, 13)]);
+
+        let description = rank(&SearchQuery::parse("editor"), &manifest).unwrap();
+        assert_eq!(description.source, MatchSource::Description);
+        assert_eq!(description.text, "A lightweight source code editor");
+
+        let binary = rank(&SearchQuery::parse("rg.exe"), &manifest).unwrap();
+        assert_eq!(binary.source, MatchSource::Binary);
+        assert_eq!(binary.highlights, vec![MatchSpan::new(4, 10)]);
+
+        // Prose fields ignore very short queries and typos.
+        assert!(rank(&SearchQuery::parse("mi"), &manifest).is_none());
+        assert!(rank(&SearchQuery::parse("editro"), &manifest).is_none());
+    }
+
+    #[test]
+    fn quoted_queries_only_match_exact_names() {
+        let quoted = SearchQuery::parse("\"visual studio\"");
+        assert!(rank(&quoted, &fields("visual-studio", "")).is_some());
+        assert!(rank(&quoted, &fields("visual-studio-code", "")).is_none());
+        assert!(rank(&quoted, &fields("vs", "visual studio")).is_none());
+    }
+
+    #[test]
+    fn fuzzy_matches_individual_tokens() {
+        let (kind, span) = match_text("powershel", "microsoft-powershell", true).unwrap();
+        assert_eq!(kind, MatchKind::Substring);
+        assert_eq!(span, MatchSpan::new(10, 19));
+
+        let (kind, span) = match_text("powershlel", "microsoft-powershell", true).unwrap();
+        assert_eq!(kind, MatchKind::Fuzzy);
+        assert_eq!(span, MatchSpan::new(10, 20));
+        assert_eq!(edit_distance(&['a', 'b'], &['b', 'a'], 1), Some(1));
+    }
+
+    #[test]
+    fn boosts_installed_and_official_packages() {
+        assert_eq!(boost(false, "games"), 0);
+        assert_eq!(boost(true, "Main"), INSTALLED_BOOST + OFFICIAL_BUCKET_BOOST);
+        assert_eq!(boost(false, "extras"), OFFICIAL_BUCKET_BOOST);
+    }
+}
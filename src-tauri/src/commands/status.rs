@@ -1,10 +1,9 @@
 //! Command for checking the overall status of Scoop and installed packages.
 //! This implements the equivalent of `scoop status` command.
 
-use crate::commands::installed::get_installed_packages_full;
-use crate::models::{
-    AppStatusInfo, PackageManifest, ScoopPackage as InstalledPackage, ScoopStatus,
-};
+use crate::commands::installed::resolved_root;
+use crate::manifest::Manifest;
+use crate::models::{AppStatusInfo, ScoopPackage as InstalledPackage, ScoopStatus};
 use crate::state::AppState;
 use crate::utils::locate_package_manifest;
 use git2::Repository;
@@ -91,7 +90,7 @@ fn get_app_status(
         Ok((manifest_path, _)) => {
             match fs::read_to_string(manifest_path) {
                 Ok(content) => {
-                    match serde_json::from_str::<PackageManifest>(&content) {
+                    match Manifest::parse(&content) {
                         Ok(manifest) => {
                             latest_version = Some(manifest.version.clone());
                             // Check if package is outdated
@@ -181,15 +180,13 @@ fn get_local_buckets(scoop_path: &Path) -> Vec<PathBuf> {
     buckets
 }
 
-/// Main command to check scoop status
-#[tauri::command]
-pub async fn check_scoop_status<R: Runtime>(
-    app: AppHandle<R>,
-    state: State<'_, AppState>,
-) -> Result<ScoopStatus, String> {
-    log::info!("Checking scoop status");
-
-    let scoop_path = state.scoop_path();
+/// Computes the status of Scoop, its buckets and the given installed packages
+/// from local repositories and manifests only.
+pub async fn compute_scoop_status(
+    scoop_path: &Path,
+    installed_packages: &[InstalledPackage],
+    held_packages: &HashSet<String>,
+) -> ScoopStatus {
     let mut scoop_needs_update = false;
     let mut bucket_needs_update = false;
     let network_failure = false;
@@ -206,7 +203,7 @@ pub async fn check_scoop_status<R: Runtime>(
     }
 
     // Check if any buckets need updating
-    let buckets = get_local_buckets(&scoop_path);
+    let buckets = get_local_buckets(scoop_path);
     let mut tasks = Vec::new();
 
     for bucket_path in buckets {
@@ -223,25 +220,15 @@ pub async fn check_scoop_status<R: Runtime>(
         }
     }
 
-    // Get installed packages and check their status
-    let installed_packages = get_installed_packages_full(app.clone(), state.clone()).await?;
-
-    // Get held packages for efficient lookup
-    let held_packages: HashSet<String> =
-        crate::commands::hold::list_held_packages(app, state.clone())
-            .await?
-            .into_iter()
-            .collect();
-
     let mut apps_with_issues = Vec::new();
 
-    for package in &installed_packages {
+    for package in installed_packages {
         // Skip scoop itself
         if package.name == "scoop" {
             continue;
         }
 
-        if let Ok(Some(app_status)) = get_app_status(&scoop_path, package, &held_packages) {
+        if let Ok(Some(app_status)) = get_app_status(scoop_path, package, held_packages) {
             apps_with_issues.push(app_status);
         }
     }
@@ -251,11 +238,21 @@ pub async fn check_scoop_status<R: Runtime>(
     let is_everything_ok =
         !scoop_needs_update && !bucket_needs_update && !network_failure && !has_app_issues;
 
-    Ok(ScoopStatus {
+    ScoopStatus {
         scoop_needs_update,
         bucket_needs_update,
         network_failure,
         apps_with_issues,
         is_everything_ok,
-    })
+    }
+}
+
+/// Main command to check scoop status
+#[tauri::command]
+pub async fn check_scoop_status<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+) -> Result<ScoopStatus, String> {
+    log::info!("Checking scoop status");
+    resolved_root(app, &state).await.status().await
 }
@@ -0,0 +1,361 @@
+//! Named update groups and automatic-update policies.
+//!
+//! Groups such as "dev tools" or "never auto-update" live in settings under
+//! `updates.groups`. A group can be updated on demand, which queues one
+//! `UpdateSet` operation for its installed packages. Its policy also limits
+//! what the scheduler's automatic package update may do to those packages,
+//! without a Scoop `hold`, so manual updates keep working. Packages in no
+//! group follow `updates.defaultPolicy`.
+use crate::commands::auto_cleanup::{compare_versions, version_segments};
+use crate::commands::installed::resolved_root;
+use crate::commands::settings;
+use crate::commands::updates::UpdatablePackage;
+use crate::operations::{self, EnqueueAction};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use tauri::{AppHandle, Manager};
+
+pub const GROUPS_KEY: &str = "updates.groups";
+pub const DEFAULT_POLICY_KEY: &str = "updates.defaultPolicy";
+
+/// How far the scheduler may move a package on its own. Ordered strictest
+/// first, so the strictest of several groups wins.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpdatePolicy {
+    /// Never updated automatically.
+    Never,
+    /// Only bumps that keep major and minor, e.g. 1.4.2 → 1.4.3.
+    PatchOnly,
+    /// Only bumps that keep the major version, e.g. 1.4.2 → 1.5.0.
+    MinorOnly,
+    /// Any newer version.
+    #[default]
+    Any,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+fn is_numeric_version(version: &str) -> bool {
+    version
+        .split(['.', '-', '_'])
+        .all(|segment| !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// How far `to` moves from `from`, judged by the first numeric segment that
+/// changes. Anything that isn't a clear upgrade counts as major, so strict
+/// policies leave the package alone when unsure.
+fn version_bump(from: &str, to: &str) -> Bump {
+    if !is_numeric_version(from)
+        || !is_numeric_version(to)
+        || compare_versions(to, from) != Ordering::Greater
+    {
+        return Bump::Major;
+    }
+    let (from, to) = (version_segments(from), version_segments(to));
+    let segment = |v: &[u64], i: usize| v.get(i).copied().unwrap_or(0);
+    match (0..from.len().max(to.len())).find(|&i| segment(&from, i) != segment(&to, i)) {
+        Some(0) | None => Bump::Major,
+        Some(1) => Bump::Minor,
+        Some(_) => Bump::Patch,
+    }
+}
+
+impl UpdatePolicy {
+    pub fn allows(self, from: &str, to: &str) -> bool {
+        match self {
+            UpdatePolicy::Never => false,
+            UpdatePolicy::PatchOnly => version_bump(from, to) == Bump::Patch,
+            UpdatePolicy::MinorOnly => version_bump(from, to) != Bump::Major,
+            UpdatePolicy::Any => true,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            UpdatePolicy::Never => "never updated automatically",
+            UpdatePolicy::PatchOnly => "only patch updates are automatic",
+            UpdatePolicy::MinorOnly => "only minor and patch updates are automatic",
+            UpdatePolicy::Any => "any update is automatic",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UpdateGroup {
+    pub name: String,
+    #[serde(default)]
+    pub packages: Vec<String>,
+    #[serde(default)]
+    pub policy: UpdatePolicy,
+}
+
+impl UpdateGroup {
+    fn contains(&self, package: &str) -> bool {
+        self.packages
+            .iter()
+            .any(|p| p.eq_ignore_ascii_case(package))
+    }
+}
+
+pub fn parse_groups(value: Option<Value>) -> Vec<UpdateGroup> {
+    let Some(value) = value else {
+        return Vec::new();
+    };
+    match serde_json::from_value(value) {
+        Ok(groups) => groups,
+        Err(e) => {
+            log::warn!("Invalid update groups, none will be used: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn configured_groups(app: &AppHandle) -> Vec<UpdateGroup> {
+    parse_groups(
+        settings::get_config_value(app.clone(), GROUPS_KEY.to_string())
+            .ok()
+            .flatten(),
+    )
+}
+
+fn default_policy(app: &AppHandle) -> UpdatePolicy {
+    settings::get_config_value(app.clone(), DEFAULT_POLICY_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// The strictest policy among the groups holding `package`; packages in no
+/// group get `default`.
+fn policy_for(groups: &[UpdateGroup], package: &str, default: UpdatePolicy) -> UpdatePolicy {
+    groups
+        .iter()
+        .filter(|group| group.contains(package))
+        .map(|group| group.policy)
+        .min()
+        .unwrap_or(default)
+}
+
+fn unrestricted(groups: &[UpdateGroup], default: UpdatePolicy) -> bool {
+    default == UpdatePolicy::Any && groups.iter().all(|g| g.policy == UpdatePolicy::Any)
+}
+
+/// What the scheduler's automatic package update should do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutoUpdatePlan {
+    /// No policy restricts anything: `scoop update *`.
+    All,
+    /// Update exactly these packages. `held_back` explains each update the
+    /// policies left alone.
+    Only {
+        packages: Vec<String>,
+        held_back: Vec<String>,
+    },
+}
+
+fn plan(
+    groups: &[UpdateGroup],
+    default: UpdatePolicy,
+    updates: &[UpdatablePackage],
+    held: &HashSet<String>,
+) -> AutoUpdatePlan {
+    if unrestricted(groups, default) {
+        return AutoUpdatePlan::All;
+    }
+    let mut packages = Vec::new();
+    let mut held_back = Vec::new();
+    for update in updates.iter().filter(|u| !held.contains(&u.name)) {
+        let policy = policy_for(groups, &update.name, default);
+        if policy.allows(&update.current, &update.available) {
+            packages.push(update.name.clone());
+        } else {
+            held_back.push(format!(
+                "{} {} -> {}: {}",
+                update.name,
+                update.current,
+                update.available,
+                policy.describe()
+            ));
+        }
+    }
+    packages.sort();
+    held_back.sort();
+    AutoUpdatePlan::Only {
+        packages,
+        held_back,
+    }
+}
+
+/// Works out the automatic package update from the configured groups and
+/// the packages with updates available.
+pub async fn auto_update_plan(app: &AppHandle) -> Result<AutoUpdatePlan, String> {
+    let groups = configured_groups(app);
+    let default = default_policy(app);
+    if unrestricted(&groups, default) {
+        return Ok(AutoUpdatePlan::All);
+    }
+    let state = app.state::<AppState>();
+    let root = resolved_root(app.clone(), &state).await;
+    let held: HashSet<String> = root.held_packages()?.into_iter().collect();
+    let updates = root.updates().await?;
+    Ok(plan(&groups, default, &updates, &held))
+}
+
+#[tauri::command]
+pub fn get_update_groups(app: AppHandle) -> Vec<UpdateGroup> {
+    configured_groups(&app)
+}
+
+#[tauri::command]
+pub fn set_update_groups(app: AppHandle, groups: Vec<UpdateGroup>) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for group in &groups {
+        let name = group.name.trim().to_lowercase();
+        if name.is_empty() {
+            return Err("Every update group needs a name".to_string());
+        }
+        if !names.insert(name) {
+            return Err(format!(
+                "There is already an update group named {}",
+                group.name
+            ));
+        }
+    }
+    let value = serde_json::to_value(&groups)
+        .map_err(|e| format!("Failed to serialize update groups: {}", e))?;
+    settings::set_config_value(app, GROUPS_KEY.to_string(), value)
+}
+
+/// Queues an update of the group's installed packages as one operation.
+#[tauri::command]
+pub async fn update_group(app: AppHandle, name: String) -> Result<String, String> {
+    let group = configured_groups(&app)
+        .into_iter()
+        .find(|group| group.name.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| format!("No update group named {}", name))?;
+    let state = app.state::<AppState>();
+    let installed: HashSet<String> = resolved_root(app.clone(), &state)
+        .await
+        .installed_packages()
+        .await?
+        .into_iter()
+        .map(|package| package.name.to_lowercase())
+        .collect();
+    let packages: Vec<String> = group
+        .packages
+        .into_iter()
+        .filter(|package| installed.contains(&package.to_lowercase()))
+        .collect();
+    if packages.is_empty() {
+        return Err(format!("No package in {} is installed", group.name));
+    }
+    Ok(operations::enqueue(
+        &app,
+        EnqueueAction::UpdateSet { packages },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(name: &str, current: &str, available: &str) -> UpdatablePackage {
+        UpdatablePackage {
+            name: name.to_string(),
+            current: current.to_string(),
+            available: available.to_string(),
+        }
+    }
+
+    fn group(name: &str, packages: &[&str], policy: UpdatePolicy) -> UpdateGroup {
+        UpdateGroup {
+            name: name.to_string(),
+            packages: packages.iter().map(|p| p.to_string()).collect(),
+            policy,
+        }
+    }
+
+    #[test]
+    fn classifies_version_bumps() {
+        assert_eq!(version_bump("1.4.2", "1.4.3"), Bump::Patch);
+        assert_eq!(version_bump("1.4.2", "1.4.2.1"), Bump::Patch);
+        assert_eq!(version_bump("1.4.2", "1.5.0"), Bump::Minor);
+        assert_eq!(version_bump("1.9", "1.10"), Bump::Minor);
+        assert_eq!(version_bump("1.4.2", "2.0.0"), Bump::Major);
+        // Downgrades and unparseable versions are never "small".
+        assert_eq!(version_bump("1.4.3", "1.4.2"), Bump::Major);
+        assert_eq!(version_bump("nightly", "nightly-2"), Bump::Major);
+        assert_eq!(version_bump("1.0.0-beta", "1.0.1"), Bump::Major);
+    }
+
+    #[test]
+    fn policies_gate_bumps() {
+        assert!(UpdatePolicy::Any.allows("1.0", "2.0"));
+        assert!(UpdatePolicy::MinorOnly.allows("1.0.0", "1.1.0"));
+        assert!(!UpdatePolicy::MinorOnly.allows("1.0.0", "2.0.0"));
+        assert!(UpdatePolicy::PatchOnly.allows("2.47.0", "2.47.1"));
+        assert!(!UpdatePolicy::PatchOnly.allows("2.47.1", "2.48.0"));
+        assert!(!UpdatePolicy::Never.allows("1.0.0", "1.0.1"));
+    }
+
+    #[test]
+    fn unrestricted_settings_update_everything() {
+        let groups = [group("dev tools", &["git"], UpdatePolicy::Any)];
+        assert_eq!(
+            plan(&groups, UpdatePolicy::Any, &[], &HashSet::new()),
+            AutoUpdatePlan::All
+        );
+    }
+
+    #[test]
+    fn plans_updates_from_group_policies() {
+        let groups = [
+            group("never auto-update", &["nodejs"], UpdatePolicy::Never),
+            group("dev tools", &["git", "Python"], UpdatePolicy::Any),
+            group("careful", &["python"], UpdatePolicy::PatchOnly),
+        ];
+        let updates = [
+            update("git", "2.47.0", "3.0.0"),
+            update("nodejs", "20.11.0", "20.11.1"),
+            update("python", "3.12.7", "3.13.0"),
+            update("ripgrep", "14.1.0", "14.1.1"),
+            update("vscode", "1.95.3", "2.0.0"),
+            update("7zip", "24.08", "24.09"),
+        ];
+        let held = HashSet::from(["7zip".to_string()]);
+
+        assert_eq!(
+            plan(&groups, UpdatePolicy::MinorOnly, &updates, &held),
+            AutoUpdatePlan::Only {
+                packages: vec!["git".to_string(), "ripgrep".to_string()],
+                held_back: vec![
+                    "nodejs 20.11.0 -> 20.11.1: never updated automatically".to_string(),
+                    "python 3.12.7 -> 3.13.0: only patch updates are automatic".to_string(),
+                    "vscode 1.95.3 -> 2.0.0: only minor and patch updates are automatic"
+                        .to_string(),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn reads_groups_from_settings() {
+        let groups = parse_groups(Some(serde_json::json!([
+            { "name": "never auto-update", "packages": ["nodejs"], "policy": "never" },
+            { "name": "dev tools", "packages": ["git"] }
+        ])));
+        assert_eq!(groups[0].policy, UpdatePolicy::Never);
+        assert_eq!(groups[1].policy, UpdatePolicy::Any);
+        assert!(parse_groups(Some(serde_json::json!({ "bad": true }))).is_empty());
+    }
+}
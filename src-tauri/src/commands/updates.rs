@@ -1,12 +1,13 @@
 //! Command for checking for available updates for installed Scoop packages.
-use crate::commands::installed::get_installed_packages_full;
-use crate::models::{PackageManifest, ScoopPackage as InstalledPackage};
+use crate::commands::installed::resolved_root;
+use crate::manifest::Manifest;
+use crate::models::ScoopPackage as InstalledPackage;
 use crate::state::AppState;
 use crate::utils::locate_package_manifest;
 use rayon::prelude::*;
 use serde::Serialize;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Runtime, State};
 
 /// Represents a package that has a newer version available.
@@ -33,7 +34,7 @@ fn check_package_for_update(
     // Read and parse the manifest to get the latest version.
     let content = fs::read_to_string(manifest_path)
         .map_err(|e| format!("Could not read manifest for {}: {}", package.name, e))?;
-    let manifest: PackageManifest = serde_json::from_str(&content)
+    let manifest = Manifest::parse(&content)
         .map_err(|e| format!("Could not parse manifest for {}: {}", package.name, e))?;
 
     // Compare versions and return an UpdatablePackage if a new version is found.
@@ -48,29 +49,16 @@ fn check_package_for_update(
     }
 }
 
-/// Checks all installed packages for newer manifest versions.
-///
-/// Held packages are reported too so the UI can show a passive update indicator;
-/// callers decide whether the package can actually be updated.
-#[tauri::command]
-pub async fn check_for_updates<R: Runtime>(
-    app: AppHandle<R>,
-    state: State<'_, AppState>,
+/// Compares each installed package with its bucket manifest, in parallel.
+pub async fn find_updates(
+    scoop_path: PathBuf,
+    installed_packages: Vec<InstalledPackage>,
 ) -> Result<Vec<UpdatablePackage>, String> {
-    log::info!("Checking for updates using filesystem");
-
-    let installed_packages = get_installed_packages_full(app.clone(), state.clone()).await?;
-    let scoop_path = state.scoop_path();
-
-    // Check for updates in parallel.
-    let installed_packages_clone = installed_packages.clone();
-    let scoop_path_clone = scoop_path.clone();
-
     let updatable_packages = tokio::task::spawn_blocking(move || {
-        installed_packages_clone
+        installed_packages
             .par_iter()
             .filter_map(|package| {
-                match check_package_for_update(&scoop_path_clone, package) {
+                match check_package_for_update(&scoop_path, package) {
                     Ok(Some(updatable)) => Some(updatable),
                     Ok(None) => None, // Package is up-to-date
                     Err(e) => {
@@ -91,3 +79,16 @@ pub async fn check_for_updates<R: Runtime>(
     log::info!("Found {} updatable packages", updatable_packages.len());
     Ok(updatable_packages)
 }
+
+/// Checks all installed packages for newer manifest versions.
+///
+/// Held packages are reported too so the UI can show a passive update indicator;
+/// callers decide whether the package can actually be updated.
+#[tauri::command]
+pub async fn check_for_updates<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+) -> Result<Vec<UpdatablePackage>, String> {
+    log::info!("Checking for updates using filesystem");
+    resolved_root(app, &state).await.updates().await
+}
@@ -0,0 +1,316 @@
+//! `rscoop://` links that let web pages offer one-click installs.
+//!
+//! - `rscoop://install/<bucket>/<package>` (optionally `?version=<version>`)
+//!   queues a scan-then-install.
+//! - `rscoop://bucket/add?url=<repo>` (optionally `&name=<bucket>`) queues a
+//!   bucket add.
+//!
+//! Windows hands the link to the executable as an argument. A running instance
+//! receives it through the single-instance plugin, a fresh one reads it from
+//! its own arguments. Nothing is queued until the user confirms.
+use crate::operations::{self, EnqueueAction};
+use crate::utils;
+use tauri::AppHandle;
+use url::Url;
+
+pub const SCHEME: &str = "rscoop";
+
+#[cfg(target_os = "windows")]
+const REG_CLASS_PATH: &str = "Software\\Classes\\rscoop";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    Install {
+        bucket: String,
+        package: String,
+        version: Option<String>,
+    },
+    AddBucket {
+        name: String,
+        url: String,
+    },
+}
+
+impl DeepLink {
+    fn action(&self) -> EnqueueAction {
+        match self {
+            DeepLink::Install {
+                bucket,
+                package,
+                version,
+            } => EnqueueAction::ScanAndInstall {
+                package: package.clone(),
+                bucket: bucket.clone(),
+                version: version.clone(),
+            },
+            DeepLink::AddBucket { name, url } => EnqueueAction::AddBucket {
+                bucket: name.clone(),
+                url: url.clone(),
+            },
+        }
+    }
+
+    fn confirmation(&self) -> String {
+        match self {
+            DeepLink::Install {
+                bucket,
+                package,
+                version: Some(version),
+            } => format!(
+                "A link asked rScoop to install {}@{} from the \"{}\" bucket.\n\nInstall it?",
+                package, version, bucket
+            ),
+            DeepLink::Install {
+                bucket, package, ..
+            } => format!(
+                "A link asked rScoop to install {} from the \"{}\" bucket.\n\nInstall it?",
+                package, bucket
+            ),
+            DeepLink::AddBucket { name, url } => format!(
+                "A link asked rScoop to add the \"{}\" bucket from:\n{}\n\nOnly add buckets you trust. Add it?",
+                name, url
+            ),
+        }
+    }
+}
+
+/// Bucket, package and version segments end up as Scoop arguments, so only
+/// plain names are accepted.
+fn validate_name(value: &str, kind: &str) -> Result<String, String> {
+    let valid = !value.is_empty()
+        && !value.starts_with(['.', '-'])
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(value.to_string())
+    } else {
+        Err(format!("Invalid {} in link: {}", kind, value))
+    }
+}
+
+fn query_value(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Parses and validates an `rscoop://` link.
+pub fn parse(link: &str) -> Result<DeepLink, String> {
+    let url = Url::parse(link.trim()).map_err(|e| format!("Invalid link: {}", e))?;
+    if !url.scheme().eq_ignore_ascii_case(SCHEME) {
+        return Err(format!("Not an {}:// link", SCHEME));
+    }
+    let target = url.host_str().unwrap_or_default().to_ascii_lowercase();
+    // Browsers may append a trailing slash.
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+
+    match (target.as_str(), segments.as_slice()) {
+        ("install", [bucket, package]) => {
+            if !utils::is_valid_bucket(bucket) {
+                return Err(format!("Invalid bucket in link: {}", bucket));
+            }
+            let version = query_value(&url, "version")
+                .map(|version| validate_name(&version, "version"))
+                .transpose()?;
+            Ok(DeepLink::Install {
+                bucket: validate_name(bucket, "bucket")?.to_lowercase(),
+                package: validate_name(package, "package")?,
+                version,
+            })
+        }
+        ("install", _) => Err(format!(
+            "Install links look like {}://install/<bucket>/<package>",
+            SCHEME
+        )),
+        ("bucket", ["add"]) => {
+            let url_param = query_value(&url, "url").ok_or("Bucket link is missing a url")?;
+            let repo = utils::validate_and_normalize_url(&url_param)?;
+            let name =
+                utils::extract_bucket_name_from_url(&repo, query_value(&url, "name").as_deref())?;
+            if !utils::is_valid_bucket(&name) {
+                return Err(format!("Invalid bucket in link: {}", name));
+            }
+            Ok(DeepLink::AddBucket {
+                name: validate_name(&name, "bucket")?,
+                url: repo,
+            })
+        }
+        _ => Err(format!("Unsupported link: {}", link)),
+    }
+}
+
+/// The first `rscoop://` link among process arguments, if any.
+pub fn find_in_args<I, S>(args: I) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let prefix = format!("{}://", SCHEME);
+    args.into_iter()
+        .map(|arg| arg.as_ref().trim().to_string())
+        .find(|arg| {
+            arg.get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(&prefix))
+        })
+}
+
+/// Shows the main window, asks the user to confirm the link and queues it.
+/// Runs the dialog on its own thread so callers are never blocked.
+pub fn handle(app: AppHandle, link: String) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    log::info!("Received deep link: {}", link);
+    std::thread::spawn(move || {
+        crate::tray::show_or_create_main_window(&app);
+        let parsed = match parse(&link) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                log::warn!("Rejected deep link {}: {}", link, e);
+                app.dialog()
+                    .message(e)
+                    .title("Unsupported rScoop link")
+                    .kind(MessageDialogKind::Error)
+                    .blocking_show();
+                return;
+            }
+        };
+
+        let confirmed = app
+            .dialog()
+            .message(parsed.confirmation())
+            .title("Open rScoop link")
+            .kind(MessageDialogKind::Info)
+            .buttons(MessageDialogButtons::OkCancelCustom(
+                "Continue".to_string(),
+                "Cancel".to_string(),
+            ))
+            .blocking_show();
+        if confirmed {
+            let id = operations::enqueue(&app, parsed.action());
+            log::info!("Deep link queued as operation {}", id);
+        } else {
+            log::info!("Deep link declined by user");
+        }
+    });
+}
+
+/// Registers the `rscoop://` scheme for the current user, pointing it at this
+/// executable. Rewrites the entry only when it points somewhere else.
+#[cfg(target_os = "windows")]
+pub fn register_scheme() -> Result<(), String> {
+    use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let command = format!("\"{}\" \"%1\"", exe.to_string_lossy());
+
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let command_path = format!("{}\\shell\\open\\command", REG_CLASS_PATH);
+    if let Ok(existing) = hkcu
+        .open_subkey(&command_path)
+        .and_then(|key| key.get_value::<String, _>(""))
+    {
+        if existing.eq_ignore_ascii_case(&command) {
+            return Ok(());
+        }
+    }
+
+    let (class_key, _) = hkcu
+        .create_subkey(REG_CLASS_PATH)
+        .map_err(|e| e.to_string())?;
+    class_key
+        .set_value("", &"URL:rScoop")
+        .and_then(|_| class_key.set_value("URL Protocol", &""))
+        .map_err(|e| e.to_string())?;
+    let (icon_key, _) = class_key
+        .create_subkey("DefaultIcon")
+        .map_err(|e| e.to_string())?;
+    icon_key
+        .set_value("", &format!("\"{}\",0", exe.to_string_lossy()))
+        .map_err(|e| e.to_string())?;
+    let (command_key, _) = hkcu
+        .create_subkey(&command_path)
+        .map_err(|e| e.to_string())?;
+    command_key
+        .set_value("", &command)
+        .map_err(|e| e.to_string())?;
+
+    log::info!("Registered {}:// links to {}", SCHEME, exe.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_install_links() {
+        assert_eq!(
+            parse("rscoop://install/extras/vscode").unwrap(),
+            DeepLink::Install {
+                bucket: "extras".to_string(),
+                package: "vscode".to_string(),
+                version: None,
+            }
+        );
+        assert_eq!(
+            parse("RSCOOP://install/Main/git/?version=2.47.0").unwrap(),
+            DeepLink::Install {
+                bucket: "main".to_string(),
+                package: "git".to_string(),
+                version: Some("2.47.0".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_unsafe_install_links() {
+        for link in [
+            "rscoop://install/vscode",
+            "rscoop://install/none/vscode",
+            "rscoop://install/extras/..",
+            "rscoop://install/extras/--global",
+            "rscoop://install/extras/vs%20code",
+            "rscoop://install/extras/vscode?version=1;calc",
+            "rscoop://uninstall/extras/vscode",
+            "https://install/extras/vscode",
+        ] {
+            assert!(parse(link).is_err(), "{} should be rejected", link);
+        }
+    }
+
+    #[test]
+    fn parses_bucket_add_links() {
+        assert_eq!(
+            parse("rscoop://bucket/add?url=https%3A%2F%2Fgithub.com%2Facme%2Fscoop-tools").unwrap(),
+            DeepLink::AddBucket {
+                name: "tools".to_string(),
+                url: "https://github.com/acme/scoop-tools.git".to_string(),
+            }
+        );
+        assert_eq!(
+            parse("rscoop://bucket/add?name=Internal&url=acme/scoop-tools").unwrap(),
+            DeepLink::AddBucket {
+                name: "internal".to_string(),
+                url: "https://github.com/acme/scoop-tools.git".to_string(),
+            }
+        );
+        assert!(parse("rscoop://bucket/add?name=x").is_err());
+        assert!(parse("rscoop://bucket/add?name=../x&url=acme/tools").is_err());
+        assert!(parse("rscoop://bucket/add?url=ftp://example.com/x").is_err());
+    }
+
+    #[test]
+    fn finds_links_among_arguments() {
+        let args = ["rscoop.exe", "--flag", "RScoop://install/main/git"];
+        assert_eq!(
+            find_in_args(args).as_deref(),
+            Some("RScoop://install/main/git")
+        );
+        assert_eq!(find_in_args(["rscoop.exe"]), None);
+    }
+}
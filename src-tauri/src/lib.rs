@@ -1,11 +1,19 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+mod automation;
+pub mod cli;
 mod cold_start;
 mod commands;
+mod deep_link;
 mod icons;
+pub mod manifest;
 mod models;
+mod notifications;
 mod operations;
 mod scheduler;
+pub mod scoop_root;
 mod state;
+#[cfg(test)]
+mod test_support;
 mod tray;
 pub mod utils;
 
@@ -23,6 +31,11 @@ pub fn run() {
     #[cfg(windows)]
     {
         builder = builder.plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            // A second instance launched for an rscoop:// link hands it over.
+            if let Some(link) = deep_link::find_in_args(&_argv) {
+                deep_link::handle(app.clone(), link);
+                return;
+            }
             // When a second instance is attempted, show the main window unless
             // it is the Windows startup entry that should stay in the tray.
             if !_argv
@@ -89,6 +102,10 @@ pub fn run() {
                         .plugin(tauri_plugin_updater::Builder::new().build())
                         .expect("failed to add updater plugin");
                 }
+
+                if let Err(e) = deep_link::register_scheme() {
+                    log::warn!("Failed to register rscoop:// links: {}", e);
+                }
             }
 
             let app_handle = app.handle().clone();
@@ -114,6 +131,14 @@ pub fn run() {
     // Spawn background task for auto bucket updates
     scheduler::start_background_tasks(app.handle().clone());
 
+    // Start the local automation API if the user opted in
+    automation::start_if_enabled(app.handle().clone());
+
+    // Launched by an rscoop:// link while no instance was running
+    if let Some(link) = deep_link::find_in_args(std::env::args()) {
+        deep_link::handle(app.handle().clone(), link);
+    }
+
     let start_minimized = std::env::args()
         .any(|arg| arg == commands::startup::START_MINIMIZED_ARG);
     if !start_minimized {
@@ -215,6 +240,9 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .invoke_handler(tauri::generate_handler![
             commands::search::search_scoop,
+            commands::package_index::build_remote_package_index,
+            commands::package_index::get_remote_index_status,
+            commands::package_index::search_remote_packages,
             commands::installed::get_installed_packages_full,
             commands::installed::refresh_installed_packages,
             commands::installed::get_package_path,
@@ -230,6 +258,11 @@ pub fn run() {
             commands::operations::dismiss_current_operation,
             commands::operations::confirm_install_anyway,
             commands::operations::run_pending_chain,
+            commands::operations::retry_operation,
+            commands::operations::activate_notification_action,
+            commands::automation::get_automation_api_info,
+            commands::automation::set_automation_api_enabled,
+            commands::automation::rotate_automation_api_token,
             commands::status::check_scoop_status,
             commands::bucket_install::update_all_buckets,
             commands::settings::get_config_value,
@@ -238,6 +271,12 @@ pub fn run() {
             commands::settings::set_scoop_path,
             commands::settings::has_virustotal_api_key,
             commands::settings::set_virustotal_api_key,
+            commands::notification_sinks::get_notification_sinks,
+            commands::notification_sinks::set_notification_sinks,
+            commands::notification_sinks::test_notification_sink,
+            commands::update_groups::get_update_groups,
+            commands::update_groups::set_update_groups,
+            commands::update_groups::update_group,
             commands::doctor::checkup::run_scoop_checkup,
             commands::doctor::checkup::open_windows_settings_page,
             commands::doctor::cache::list_cache_contents,
@@ -246,21 +285,37 @@ pub fn run() {
             commands::doctor::shim::remove_shim,
             commands::doctor::shim::alter_shim,
             commands::doctor::shim::add_shim,
+            commands::doctor::owner::find_package_owner,
+            commands::doctor::conflicts::check_shim_conflicts,
+            commands::doctor::conflicts::repoint_shim,
+            commands::doctor::shim_repair::repair_shims,
+            commands::doctor::shortcuts::list_shortcuts,
+            commands::doctor::shortcuts::repair_shortcuts,
+            commands::doctor::shortcuts::rename_shortcut,
+            commands::doctor::env_audit::audit_environment,
+            commands::doctor::env_audit::fix_environment,
             commands::hold::list_held_packages,
             commands::hold::hold_package,
             commands::hold::unhold_package,
             commands::bucket::get_buckets,
             commands::bucket::get_bucket_info,
             commands::bucket::get_bucket_manifests,
+            commands::bucket::get_bucket_health,
             commands::bucket_install::install_bucket,
             commands::bucket_install::validate_bucket_install,
             commands::bucket_install::update_bucket,
             commands::bucket_install::remove_bucket,
+            commands::bucket_install::switch_bucket_branch,
+            commands::bucket_install::repair_bucket,
+            commands::bucket_install::pin_bucket,
+            commands::bucket_install::unpin_bucket,
             commands::bucket_search::search_buckets,
             commands::bucket_search::get_expanded_search_info,
             commands::bucket_search::get_default_buckets,
             commands::bucket_search::clear_bucket_cache,
             commands::bucket_search::check_bucket_cache_exists,
+            commands::bucket_search::get_bucket_directory_sources,
+            commands::bucket_search::set_bucket_directory_sources,
             commands::app_info::is_scoop_installation,
             commands::app_info::is_cwd_mismatch,
             commands::linker::get_package_versions,
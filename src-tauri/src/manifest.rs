@@ -0,0 +1,760 @@
+//! Typed model of a Scoop app manifest.
+//!
+//! Fields follow Scoop's manifest schema. Keys the model does not know about,
+//! including `##` comments, are kept in `extra` maps, so parsing and
+//! serializing a manifest gives back the same JSON value. Known keys whose
+//! value does not fit the schema are kept the same way instead of failing the
+//! whole manifest.
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A value Scoop accepts either on its own or as a list, e.g. `url` or `notes`.
+/// Remembers which form it was written in.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    // `Many` comes first so `["a", "b"]` never parses as a single tuple entry.
+    Many(Vec<T>),
+    One(T),
+}
+
+impl<T> OneOrMany<T> {
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        match self {
+            OneOrMany::Many(items) => items.iter(),
+            OneOrMany::One(item) => std::slice::from_ref(item).iter(),
+        }
+    }
+}
+
+impl OneOrMany<String> {
+    /// Joins the entries with newlines, as Scoop prints multi-line fields.
+    pub fn lines(&self) -> String {
+        self.iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// `license` is either an SPDX identifier or `{ "identifier": ..., "url": ... }`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum License {
+    Identifier(String),
+    Detailed {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        identifier: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+}
+
+impl License {
+    pub fn identifier(&self) -> &str {
+        match self {
+            License::Identifier(id) => id,
+            License::Detailed { identifier, .. } => identifier.as_deref().unwrap_or_default(),
+        }
+    }
+}
+
+/// One `bin` entry: a path, or `[path, alias, args...]`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum BinEntry {
+    Path(String),
+    Shim(Vec<String>),
+}
+
+impl BinEntry {
+    pub fn path(&self) -> &str {
+        match self {
+            BinEntry::Path(path) => path,
+            BinEntry::Shim(parts) => parts.first().map(String::as_str).unwrap_or_default(),
+        }
+    }
+
+    /// Explicit alias of a `[path, alias]` entry.
+    pub fn alias(&self) -> Option<&str> {
+        match self {
+            BinEntry::Path(_) => None,
+            BinEntry::Shim(parts) => parts.get(1).map(String::as_str).filter(|a| !a.is_empty()),
+        }
+    }
+
+    /// Arguments the shim passes before the user's.
+    pub fn args(&self) -> Option<&str> {
+        match self {
+            BinEntry::Path(_) => None,
+            BinEntry::Shim(parts) => parts.get(2).map(String::as_str),
+        }
+    }
+
+    /// Name of the shim Scoop creates: the alias, or the file stem of the path.
+    pub fn shim_name(&self) -> String {
+        self.alias().map(str::to_string).unwrap_or_else(|| {
+            let file_name = self.path().rsplit(['/', '\\']).next().unwrap_or_default();
+            Path::new(file_name)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// A start menu shortcut: `[target, name, args?, icon?]`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Shortcut(pub Vec<String>);
+
+impl Shortcut {
+    fn part(&self, index: usize) -> Option<&str> {
+        self.0
+            .get(index)
+            .map(String::as_str)
+            .filter(|s| !s.is_empty())
+    }
+
+    pub fn target(&self) -> &str {
+        self.part(0).unwrap_or_default()
+    }
+
+    pub fn name(&self) -> &str {
+        self.part(1).unwrap_or_default()
+    }
+
+    pub fn args(&self) -> Option<&str> {
+        self.part(2)
+    }
+
+    pub fn icon(&self) -> Option<&str> {
+        self.part(3)
+    }
+}
+
+/// One `persist` entry: a path, or `[source, target]` to rename it in `persist/`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PersistEntry {
+    Path(String),
+    Renamed(Vec<String>),
+}
+
+impl PersistEntry {
+    pub fn source(&self) -> &str {
+        match self {
+            PersistEntry::Path(path) => path,
+            PersistEntry::Renamed(parts) => parts.first().map(String::as_str).unwrap_or_default(),
+        }
+    }
+
+    pub fn target(&self) -> &str {
+        match self {
+            PersistEntry::Path(path) => path,
+            PersistEntry::Renamed(parts) => parts
+                .get(1)
+                .or_else(|| parts.first())
+                .map(String::as_str)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// `installer` and `uninstaller`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Installer {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<OneOrMany<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<OneOrMany<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// `checkver`: a regex run against the homepage, or a detailed object.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Checkver {
+    Regex(String),
+    Detailed(Box<CheckverSpec>),
+}
+
+/// Keys of a `checkver` object. `re` and `jp` are Scoop's short aliases and
+/// are kept apart so they serialize back under the same name.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct CheckverSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub re: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jsonpath: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xpath: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverse: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replace: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<OneOrMany<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Checkver {
+    /// Whether Scoop's checkver script can do anything with this.
+    pub fn is_usable(&self) -> bool {
+        match self {
+            Checkver::Regex(pattern) => !pattern.trim().is_empty(),
+            Checkver::Detailed(spec) => {
+                spec.github.is_some()
+                    || spec.url.is_some()
+                    || spec.regex.is_some()
+                    || spec.re.is_some()
+                    || spec.jsonpath.is_some()
+                    || spec.jp.is_some()
+                    || spec.xpath.is_some()
+                    || spec.script.is_some()
+            }
+        }
+    }
+}
+
+/// How `autoupdate` finds the hash of a new download.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct HashExtraction {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub find: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jsonpath: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xpath: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Download fields that `autoupdate` rewrites for a new version.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct AutoupdateArch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<OneOrMany<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<OneOrMany<HashExtraction>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extract_dir: Option<OneOrMany<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Autoupdate {
+    #[serde(flatten)]
+    pub download: AutoupdateArch,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub architecture: Option<BTreeMap<String, AutoupdateArch>>,
+}
+
+/// Fields an `architecture` block may override.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ArchSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<OneOrMany<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<OneOrMany<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extract_dir: Option<OneOrMany<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bin: Option<OneOrMany<BinEntry>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shortcuts: Option<Vec<Shortcut>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_add_path: Option<OneOrMany<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_set: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installer: Option<Installer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uninstaller: Option<Installer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_install: Option<OneOrMany<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_install: Option<OneOrMany<String>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// `architecture`, keyed by Scoop's architecture names.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Architectures {
+    #[serde(rename = "64bit", default, skip_serializing_if = "Option::is_none")]
+    pub x64: Option<ArchSpec>,
+    #[serde(rename = "32bit", default, skip_serializing_if = "Option::is_none")]
+    pub x86: Option<ArchSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arm64: Option<ArchSpec>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Architectures {
+    pub fn iter(&self) -> impl Iterator<Item = &ArchSpec> {
+        [&self.x64, &self.x86, &self.arm64]
+            .into_iter()
+            .filter_map(Option::as_ref)
+    }
+
+    /// The block for a Scoop architecture name such as `64bit`.
+    pub fn get(&self, architecture: &str) -> Option<&ArchSpec> {
+        match architecture {
+            "64bit" => self.x64.as_ref(),
+            "32bit" => self.x86.as_ref(),
+            "arm64" => self.arm64.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Parses each block on its own, keeping unusable fields and blocks in
+    /// `extra`.
+    fn lenient(value: Value) -> Option<Self> {
+        let Value::Object(blocks) = value else {
+            return None;
+        };
+        let mut architectures = Architectures::default();
+        for (name, block) in blocks {
+            let slot = match name.as_str() {
+                "64bit" => &mut architectures.x64,
+                "32bit" => &mut architectures.x86,
+                "arm64" => &mut architectures.arm64,
+                _ => {
+                    architectures.extra.insert(name, block);
+                    continue;
+                }
+            };
+            let Value::Object(fields) = block else {
+                architectures.extra.insert(name, block);
+                continue;
+            };
+            let (fitting, misfits) = split_fields::<ArchSpec>(fields, &Map::new());
+            match serde_json::from_value::<ArchSpec>(Value::Object(fitting.clone())) {
+                Ok(mut spec) => {
+                    spec.extra.extend(misfits);
+                    *slot = Some(spec);
+                }
+                Err(_) => {
+                    let mut fields = fitting;
+                    fields.extend(misfits);
+                    architectures.extra.insert(name, Value::Object(fields));
+                }
+            }
+        }
+        Some(architectures)
+    }
+}
+
+/// Splits `fields` into those that deserialize as `T` next to `required` and
+/// those that do not.
+fn split_fields<T: DeserializeOwned>(
+    fields: Map<String, Value>,
+    required: &Map<String, Value>,
+) -> (Map<String, Value>, Map<String, Value>) {
+    let mut fitting = required.clone();
+    let mut misfits = Map::new();
+    for (key, value) in fields {
+        let mut candidate = required.clone();
+        candidate.insert(key.clone(), value.clone());
+        if serde_json::from_value::<T>(Value::Object(candidate)).is_ok() {
+            fitting.insert(key, value);
+        } else {
+            misfits.insert(key, value);
+        }
+    }
+    (fitting, misfits)
+}
+
+/// A Scoop app manifest.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Manifest {
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<License>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<OneOrMany<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depends: Option<OneOrMany<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggest: Option<BTreeMap<String, OneOrMany<String>>>,
+    /// Notice on deprecated manifests, usually naming the replacement.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    #[serde(flatten)]
+    pub download: ArchSpec,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub architecture: Option<Architectures>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persist: Option<OneOrMany<PersistEntry>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_uninstall: Option<OneOrMany<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_uninstall: Option<OneOrMany<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkver: Option<Checkver>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autoupdate: Option<Autoupdate>,
+}
+
+impl Manifest {
+    /// Parses a manifest. Only invalid JSON or a missing or non-string
+    /// `version` is an error; other fields that do not fit the schema end up
+    /// in `extra`.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let value: Value =
+            serde_json::from_str(content).map_err(|e| format!("Invalid manifest: {}", e))?;
+        Self::from_value(value)
+    }
+
+    pub fn from_value(value: Value) -> Result<Self, String> {
+        serde_json::from_value(value.clone())
+            .or_else(|e| Self::lenient(value).ok_or_else(|| format!("Invalid manifest: {}", e)))
+    }
+
+    /// Keeps every field that parses on its own. Buckets in the wild carry
+    /// oddly typed values, and one of them should not hide the whole app.
+    fn lenient(value: Value) -> Option<Self> {
+        let Value::Object(mut fields) = value else {
+            return None;
+        };
+        let version = fields.remove("version")?;
+        version.as_str()?;
+        let architecture = fields.remove("architecture");
+
+        let required = Map::from_iter([("version".to_string(), version)]);
+        let (fitting, misfits) = split_fields::<Manifest>(fields, &required);
+        let mut manifest: Manifest = serde_json::from_value(Value::Object(fitting)).ok()?;
+        manifest.download.extra.extend(misfits);
+
+        if let Some(architecture) = architecture {
+            match Architectures::lenient(architecture.clone()) {
+                Some(parsed) => manifest.architecture = Some(parsed),
+                None => {
+                    manifest
+                        .download
+                        .extra
+                        .insert("architecture".to_string(), architecture);
+                }
+            }
+        }
+        Some(manifest)
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&content)
+    }
+
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+
+    /// The top-level fields, then each architecture's overrides.
+    fn specs(&self) -> impl Iterator<Item = &ArchSpec> {
+        std::iter::once(&self.download).chain(self.architecture.iter().flat_map(|a| a.iter()))
+    }
+
+    /// Every `bin` entry, across all architectures.
+    pub fn bins(&self) -> impl Iterator<Item = &BinEntry> {
+        self.specs()
+            .flat_map(|spec| spec.bin.iter().flat_map(|bin| bin.iter()))
+    }
+
+    /// Every shortcut, across all architectures.
+    pub fn shortcuts(&self) -> impl Iterator<Item = &Shortcut> {
+        self.specs()
+            .flat_map(|spec| spec.shortcuts.iter().flat_map(|list| list.iter()))
+    }
+
+    /// Every download URL, across all architectures.
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        self.specs()
+            .flat_map(|spec| spec.url.iter().flat_map(|url| url.iter()))
+            .map(String::as_str)
+    }
+
+    pub fn license_identifier(&self) -> &str {
+        self.license
+            .as_ref()
+            .map(License::identifier)
+            .unwrap_or_default()
+    }
+
+    pub fn notes_text(&self) -> Option<String> {
+        self.notes.as_ref().map(OneOrMany::lines)
+    }
+
+    pub fn dependencies(&self) -> impl Iterator<Item = &str> {
+        self.depends
+            .iter()
+            .flat_map(|deps| deps.iter())
+            .map(String::as_str)
+    }
+
+    /// A field as Scoop reads it for an install of `architecture`: the
+    /// architecture block's value if it sets one, otherwise the top-level one.
+    fn arch_specific<'a, T>(
+        &'a self,
+        architecture: &str,
+        field: impl Fn(&'a ArchSpec) -> Option<&'a T>,
+    ) -> Option<&'a T> {
+        self.architecture
+            .as_ref()
+            .and_then(|blocks| blocks.get(architecture))
+            .and_then(&field)
+            .or_else(|| field(&self.download))
+    }
+
+    /// `env_add_path` entries for an install of `architecture`.
+    pub fn env_add_paths(&self, architecture: &str) -> impl Iterator<Item = &str> {
+        self.arch_specific(architecture, |spec| spec.env_add_path.as_ref())
+            .into_iter()
+            .flat_map(|paths| paths.iter())
+            .map(String::as_str)
+    }
+
+    /// `bin` entries for an install of `architecture`.
+    pub fn bins_for(&self, architecture: &str) -> impl Iterator<Item = &BinEntry> {
+        self.arch_specific(architecture, |spec| spec.bin.as_ref())
+            .into_iter()
+            .flat_map(|bins| bins.iter())
+    }
+
+    /// Shortcuts for an install of `architecture`.
+    pub fn shortcuts_for(&self, architecture: &str) -> impl Iterator<Item = &Shortcut> {
+        self.arch_specific(architecture, |spec| spec.shortcuts.as_ref())
+            .into_iter()
+            .flat_map(|shortcuts| shortcuts.iter())
+    }
+
+    /// `env_set` variables for an install of `architecture`.
+    pub fn env_vars(&self, architecture: &str) -> BTreeMap<&str, &str> {
+        self.arch_specific(architecture, |spec| spec.env_set.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn full_manifest() -> Value {
+        json!({
+            "##": "Maintained by the extras bucket",
+            "version": "1.2.3",
+            "description": "An example app",
+            "homepage": "https://example.org",
+            "license": { "identifier": "MIT", "url": "https://example.org/LICENSE" },
+            "notes": ["Line one", "Line two"],
+            "depends": "7zip",
+            "suggest": { "vcredist": "extras/vcredist2022" },
+            "architecture": {
+                "64bit": {
+                    "url": "https://example.org/app-x64.zip#/dl.7z",
+                    "hash": "sha256:abc",
+                    "bin": [["app64.exe", "app"]]
+                },
+                "32bit": {
+                    "url": ["https://example.org/a.zip", "https://example.org/b.zip"],
+                    "hash": ["def", "0123"]
+                }
+            },
+            "extract_dir": "app-1.2.3",
+            "bin": ["tool.exe", ["bin\\app.exe", "app-cli", "--portable"]],
+            "shortcuts": [["app.exe", "Example App", "--start", "app.ico"]],
+            "persist": ["data", ["conf.ini", "config\\conf.ini"]],
+            "env_add_path": "bin",
+            "env_set": { "APP_HOME": "$dir" },
+            "installer": { "script": ["Write-Host hi", "Write-Host bye"], "keep": true },
+            "uninstaller": { "file": "uninstall.exe", "args": "/S" },
+            "post_install": "Write-Host done",
+            "psmodule": { "name": "Example" },
+            "checkver": { "github": "https://github.com/example/app", "re": "v([\\d.]+)" },
+            "autoupdate": {
+                "architecture": {
+                    "64bit": { "url": "https://example.org/app-$version-x64.zip" }
+                },
+                "hash": { "url": "$url.sha256", "mode": "extract" },
+                "extract_dir": "app-$version"
+            }
+        })
+    }
+
+    #[test]
+    fn round_trips_without_losing_fields() {
+        let value = full_manifest();
+        let manifest: Manifest = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(manifest.to_value(), value);
+
+        let minimal = json!({ "version": "1.0", "url": "https://example.org/a.zip" });
+        let manifest: Manifest = serde_json::from_value(minimal.clone()).unwrap();
+        assert_eq!(manifest.to_value(), minimal);
+    }
+
+    #[test]
+    fn exposes_typed_fields() {
+        let manifest: Manifest = serde_json::from_value(full_manifest()).unwrap();
+
+        let bins: Vec<_> = manifest.bins().collect();
+        assert_eq!(bins.len(), 3);
+        assert_eq!(bins[0].shim_name(), "tool");
+        assert_eq!(bins[1].path(), "bin\\app.exe");
+        assert_eq!(bins[1].shim_name(), "app-cli");
+        assert_eq!(bins[1].args(), Some("--portable"));
+        assert_eq!(bins[2].shim_name(), "app");
+
+        let shortcut = manifest.shortcuts().next().unwrap();
+        assert_eq!(shortcut.name(), "Example App");
+        assert_eq!(shortcut.icon(), Some("app.ico"));
+
+        let persist: Vec<_> = manifest.persist.iter().flat_map(|p| p.iter()).collect();
+        assert_eq!(persist[1].source(), "conf.ini");
+        assert_eq!(persist[1].target(), "config\\conf.ini");
+
+        assert_eq!(manifest.urls().count(), 3);
+        assert_eq!(manifest.license_identifier(), "MIT");
+        assert_eq!(manifest.notes_text().as_deref(), Some("Line one\nLine two"));
+        assert_eq!(manifest.dependencies().collect::<Vec<_>>(), vec!["7zip"]);
+        assert!(manifest.checkver.as_ref().unwrap().is_usable());
+        assert_eq!(
+            manifest.download.extra["psmodule"],
+            json!({ "name": "Example" })
+        );
+    }
+
+    #[test]
+    fn keeps_misfit_fields_instead_of_failing() {
+        // Shapes seen in third-party buckets: a numeric `env_set` value, a
+        // `bin` given as an object, an architecture Scoop does not know and a
+        // `checkver` that is just a number.
+        let value = json!({
+            "version": "0.9.1",
+            "description": "Odd but installable",
+            "bin": { "path": "odd.exe" },
+            "checkver": 1,
+            "architecture": {
+                "64bit": {
+                    "url": "https://example.org/odd-x64.zip",
+                    "bin": "odd64.exe",
+                    "env_set": { "ODD_HOME": "$dir", "ODD_THREADS": 4 }
+                },
+                "ia64": { "url": "https://example.org/odd-ia64.zip" }
+            }
+        });
+
+        let manifest = Manifest::parse(&value.to_string()).unwrap();
+        assert_eq!(manifest.version, "0.9.1");
+        assert_eq!(manifest.description.as_deref(), Some("Odd but installable"));
+        assert!(manifest.checkver.is_none());
+        assert_eq!(
+            manifest.bins().map(BinEntry::path).collect::<Vec<_>>(),
+            vec!["odd64.exe"]
+        );
+        assert_eq!(manifest.urls().count(), 1);
+        assert_eq!(manifest.to_value(), value);
+    }
+
+    #[test]
+    fn keeps_unknown_architectures() {
+        let value = json!({
+            "version": "1.0",
+            "architecture": {
+                "64bit": { "url": "https://example.org/a.zip" },
+                "riscv64": { "url": "https://example.org/r.zip" }
+            }
+        });
+        let manifest = Manifest::parse(&value.to_string()).unwrap();
+        assert_eq!(manifest.urls().count(), 1);
+        assert_eq!(manifest.to_value(), value);
+    }
+
+    #[test]
+    fn still_requires_a_version() {
+        assert!(Manifest::parse("{ not json").is_err());
+        assert!(Manifest::parse(r#"{ "description": "no version" }"#).is_err());
+        assert!(Manifest::parse(r#"{ "version": 3, "bin": "a.exe" }"#).is_err());
+    }
+
+    #[test]
+    fn architecture_values_replace_top_level_ones() {
+        let manifest = Manifest::parse(
+            r#"{ "version": "1.0", "env_add_path": "bin", "env_set": { "A": "top", "B": "top" },
+                 "architecture": {
+                     "64bit": { "env_add_path": ["bin64", "tools"], "env_set": { "A": "x64" } },
+                     "32bit": { "url": "https://example.org/x86.zip" }
+                 } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.env_add_paths("64bit").collect::<Vec<_>>(),
+            vec!["bin64", "tools"]
+        );
+        assert_eq!(manifest.env_vars("64bit"), BTreeMap::from([("A", "x64")]));
+        assert_eq!(
+            manifest.env_add_paths("32bit").collect::<Vec<_>>(),
+            vec!["bin"]
+        );
+        assert_eq!(
+            manifest.env_vars("arm64"),
+            BTreeMap::from([("A", "top"), ("B", "top")])
+        );
+
+        let manifest = Manifest::parse(
+            r#"{ "version": "1.0", "bin": "app.exe", "shortcuts": [["app.exe", "App"]],
+                 "architecture": { "32bit": {
+                     "bin": "x86\\app.exe", "shortcuts": [["x86\\app.exe", "App"]] } } }"#,
+        )
+        .unwrap();
+        let x86: Vec<_> = manifest.bins_for("32bit").map(BinEntry::path).collect();
+        assert_eq!(x86, vec!["x86\\app.exe"]);
+        let x64: Vec<_> = manifest.bins_for("64bit").map(BinEntry::path).collect();
+        assert_eq!(x64, vec!["app.exe"]);
+        let shortcut = manifest.shortcuts_for("32bit").next().unwrap();
+        assert_eq!(shortcut.target(), "x86\\app.exe");
+    }
+
+    #[test]
+    fn checkver_needs_a_version_source() {
+        let checkver = |value: Value| serde_json::from_value::<Checkver>(value).unwrap();
+        assert!(checkver(json!("v([\\d.]+)")).is_usable());
+        assert!(!checkver(json!("  ")).is_usable());
+        assert!(!checkver(json!({ "reverse": true })).is_usable());
+        assert!(checkver(json!({ "script": ["Get-Version"] })).is_usable());
+    }
+}
@@ -12,6 +12,10 @@ use serde::{Deserialize, Serialize};
 pub enum MatchSource {
     Name,
     Binary,
+    Description,
+    Homepage,
+    Shortcut,
+    License,
     None,
 }
 
@@ -36,6 +40,28 @@ pub struct ScoopPackage {
     pub match_source: MatchSource,
     #[serde(default)]
     pub is_versioned_install: bool,
+    /// Search ranking; higher is better. Zero outside of search results.
+    #[serde(default)]
+    pub match_score: u32,
+    /// The manifest value the search matched, e.g. a binary or description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_text: Option<String>,
+    /// Character ranges of `matched_text` to highlight.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<MatchSpan>,
+}
+
+/// Half-open character range `[start, end)` in a matched string.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MatchSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl MatchSpan {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -45,6 +71,30 @@ pub struct ScoopPackage {
 pub struct SearchResult {
     pub packages: Vec<ScoopPackage>,
     pub is_cold: bool,
+    /// Matches from the remote package index in buckets that are not added.
+    /// Empty unless the index has been built.
+    #[serde(default)]
+    pub remote_matches: Vec<RemotePackageMatch>,
+}
+
+// -----------------------------------------------------------------------------
+// RemotePackageMatch
+// -----------------------------------------------------------------------------
+/// A package available in a directory bucket that is not added locally.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RemotePackageMatch {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    /// Local name to add the bucket under. Differs from the directory name
+    /// when an unrelated bucket of that name is already added.
+    pub bucket: String,
+    /// `owner/repo` of the bucket.
+    pub bucket_full_name: String,
+    pub bucket_url: String,
+    pub match_source: MatchSource,
+    #[serde(default)]
+    pub match_score: u32,
 }
 
 // -----------------------------------------------------------------------------
@@ -59,6 +109,46 @@ pub struct BucketInfo {
     pub git_url: Option<String>,
     pub git_branch: Option<String>,
     pub last_updated: Option<String>,
+    /// Tag, commit or ref the bucket is pinned to, if any.
+    #[serde(default)]
+    pub pinned_ref: Option<String>,
+    /// Upstream commits missing from the pinned revision, from fetched refs.
+    #[serde(default)]
+    pub commits_behind: Option<usize>,
+    #[serde(default)]
+    pub health: Option<BucketHealth>,
+}
+
+// -----------------------------------------------------------------------------
+// BucketHealth
+// -----------------------------------------------------------------------------
+/// A package name that also exists in other installed buckets.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DuplicatePackage {
+    pub name: String,
+    pub other_buckets: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BucketHealth {
+    pub bucket_name: String,
+    /// 0 (unusable) to 100 (healthy).
+    pub score: u8,
+    pub days_since_last_commit: Option<i64>,
+    pub is_stale: bool,
+    pub manifest_count: usize,
+    /// Manifests with no `checkver`, or one Scoop cannot run. This is not a
+    /// count of failed checks: running checkver needs the network and
+    /// PowerShell, so only the manifests themselves are inspected.
+    pub unusable_checkvers: usize,
+    /// `unusable_checkvers` as a share of the bucket's manifests.
+    pub unusable_checkver_ratio: f32,
+    /// Manifests that are not valid JSON objects, relative to the bucket.
+    pub malformed_manifests: Vec<String>,
+    pub duplicate_packages: Vec<DuplicatePackage>,
+    /// Download hosts that failed to respond. `None` until probed.
+    #[serde(default)]
+    pub dead_hosts: Option<Vec<String>>,
 }
 
 // -----------------------------------------------------------------------------
@@ -89,15 +179,9 @@ pub struct ScoopStatus {
 // -----------------------------------------------------------------------------
 // Manifest Types (from installed.rs)
 // -----------------------------------------------------------------------------
-#[derive(Deserialize, Debug, Clone)]
-pub struct PackageManifest {
-    pub description: Option<String>,
-    pub version: String,
-    #[serde(default)]
-    pub deprecated: Option<String>,
-}
-
 #[derive(Deserialize, Debug, Clone, Default)]
 pub struct InstallManifest {
     pub bucket: Option<String>,
+    /// The architecture Scoop installed, e.g. `64bit`.
+    pub architecture: Option<String>,
 }
@@ -0,0 +1,580 @@
+//! Desktop notifications for finished operations.
+//!
+//! Operations describe what finished as a [`Notification`]; this module
+//! decides whether and how to show it. Native backends are WinRT toasts on
+//! Windows and the freedesktop notification service (D-Bus) on Linux. When
+//! neither is available, or the user picks it, notifications go to the webview
+//! as an `app-notification` event instead.
+//!
+//! Settings:
+//! - `notifications.mutedKinds`: operation kinds that never notify.
+//! - `notifications.groupQueued` (default on): while more operations are
+//!   queued, results are collected and shown as one summary at the end.
+//!   Results offering "Install Anyway", "Clear Cache" or "Retry" are still
+//!   shown on their own.
+//! - `notifications.showActions` (default on): attach buttons such as
+//!   "Open log" and "Retry".
+//! - `notifications.backend`: `"auto"` (default) or `"in-app"`.
+use crate::commands::settings;
+use crate::operations::{self, EnqueueAction, OperationKind};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const EVENT_NOTIFICATION: &str = "app-notification";
+pub const MUTED_KINDS_KEY: &str = "notifications.mutedKinds";
+pub const GROUP_QUEUED_KEY: &str = "notifications.groupQueued";
+pub const SHOW_ACTIONS_KEY: &str = "notifications.showActions";
+pub const BACKEND_KEY: &str = "notifications.backend";
+
+/// Failed operations listed by name in a grouped summary before "and N more".
+const GROUP_LISTED_FAILURES: usize = 3;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum NotificationAction {
+    InstallAnyway,
+    ClearCache,
+    OpenLog,
+    Retry,
+    Dismiss,
+}
+
+impl NotificationAction {
+    pub fn id(self) -> &'static str {
+        match self {
+            NotificationAction::InstallAnyway => "install-anyway",
+            NotificationAction::ClearCache => "clear-cache",
+            NotificationAction::OpenLog => "open-log",
+            NotificationAction::Retry => "retry",
+            NotificationAction::Dismiss => "dismiss",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NotificationAction::InstallAnyway => "Install Anyway",
+            NotificationAction::ClearCache => "Clear Cache",
+            NotificationAction::OpenLog => "Open log",
+            NotificationAction::Retry => "Retry",
+            NotificationAction::Dismiss => "Dismiss",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        [
+            NotificationAction::InstallAnyway,
+            NotificationAction::ClearCache,
+            NotificationAction::OpenLog,
+            NotificationAction::Retry,
+            NotificationAction::Dismiss,
+        ]
+        .into_iter()
+        .find(|action| action.id() == id)
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    pub success: bool,
+    /// `success`, `warning` or `error`, as in `CommandResult`.
+    pub status: String,
+    pub kind: OperationKind,
+    pub actions: Vec<NotificationAction>,
+    /// What the Retry action enqueues.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry: Option<EnqueueAction>,
+}
+
+impl Notification {
+    fn marker(&self) -> &'static str {
+        match self.status.as_str() {
+            "warning" => "⚠",
+            _ if self.success => "✓",
+            _ => "✗",
+        }
+    }
+
+    /// Title with the outcome marker, as shown by native backends.
+    pub fn headline(&self) -> String {
+        format!("{} {}", self.title, self.marker())
+    }
+
+    /// Whether it offers an action that only makes sense for this result.
+    fn needs_own_toast(&self) -> bool {
+        self.actions.iter().any(|action| {
+            matches!(
+                action,
+                NotificationAction::InstallAnyway
+                    | NotificationAction::ClearCache
+                    | NotificationAction::Retry
+            )
+        })
+    }
+}
+
+/// Something that can put a notification in front of the user.
+pub trait NotificationBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn show(&self, app: &AppHandle, notification: &Notification) -> Result<(), String>;
+}
+
+// --- Grouping ----------------------------------------------------------------
+
+/// Results collected while the queue keeps running.
+#[derive(Default)]
+struct Batch {
+    succeeded: usize,
+    warnings: usize,
+    failed: Vec<String>,
+    last_kind: Option<OperationKind>,
+}
+
+impl Batch {
+    fn is_empty(&self) -> bool {
+        self.succeeded + self.warnings + self.failed.len() == 0
+    }
+
+    fn add(&mut self, notification: &Notification) {
+        match notification.status.as_str() {
+            _ if !notification.success => self.failed.push(notification.title.clone()),
+            "warning" => self.warnings += 1,
+            _ => self.succeeded += 1,
+        }
+        self.last_kind = Some(notification.kind.clone());
+    }
+
+    /// Decides what to show for one finished operation, or for a muted one
+    /// (`None`). Results are held back while more operations are queued and
+    /// come out as one summary after the last one, muted or not.
+    fn route(
+        &mut self,
+        notification: Option<Notification>,
+        more_queued: bool,
+    ) -> Vec<Notification> {
+        let mut shown = Vec::new();
+        let mut last = None;
+        match notification {
+            Some(notification) if notification.needs_own_toast() => shown.push(notification),
+            Some(notification) if more_queued => self.add(&notification),
+            Some(notification) => last = Some(notification),
+            None => {}
+        }
+        if !more_queued {
+            if let Some(summary) = self.finish(last) {
+                shown.insert(0, summary);
+            }
+        }
+        shown
+    }
+
+    /// Folds in the last result and returns one summary for all of them.
+    fn finish(&mut self, last: Option<Notification>) -> Option<Notification> {
+        if self.is_empty() {
+            return last;
+        }
+        if let Some(last) = &last {
+            self.add(last);
+        }
+        let batch = std::mem::take(self);
+        let kind = batch.last_kind?;
+        let total = batch.succeeded + batch.warnings + batch.failed.len();
+
+        let mut parts = vec![format!("{} succeeded", batch.succeeded)];
+        if batch.warnings > 0 {
+            parts.push(format!("{} with warnings", batch.warnings));
+        }
+        if !batch.failed.is_empty() {
+            let mut failed =
+                batch.failed[..batch.failed.len().min(GROUP_LISTED_FAILURES)].join(", ");
+            if batch.failed.len() > GROUP_LISTED_FAILURES {
+                failed.push_str(&format!(
+                    " and {} more",
+                    batch.failed.len() - GROUP_LISTED_FAILURES
+                ));
+            }
+            parts.push(format!("{} failed: {}", batch.failed.len(), failed));
+        }
+
+        let (success, status) = if !batch.failed.is_empty() {
+            (false, "error")
+        } else if batch.warnings > 0 {
+            (true, "warning")
+        } else {
+            (true, "success")
+        };
+        Some(Notification {
+            title: match total {
+                1 => "1 operation finished".to_string(),
+                _ => format!("{} operations finished", total),
+            },
+            body: parts.join(", "),
+            success,
+            status: status.to_string(),
+            kind,
+            actions: vec![NotificationAction::OpenLog],
+            retry: None,
+        })
+    }
+}
+
+static BATCH: Lazy<Mutex<Batch>> = Lazy::new(|| Mutex::new(Batch::default()));
+
+// --- Settings ----------------------------------------------------------------
+
+fn read_config(app: &AppHandle, key: &str) -> Option<Value> {
+    settings::get_config_value(app.clone(), key.to_string())
+        .ok()
+        .flatten()
+}
+
+fn read_flag(app: &AppHandle, key: &str) -> bool {
+    read_config(app, key)
+        .and_then(|value| value.as_bool())
+        .unwrap_or(true)
+}
+
+pub fn parse_muted_kinds(value: Option<Value>) -> Vec<OperationKind> {
+    value
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn backends(app: &AppHandle) -> Vec<Box<dyn NotificationBackend>> {
+    let in_app_only = read_config(app, BACKEND_KEY)
+        .and_then(|value| value.as_str().map(|s| s.eq_ignore_ascii_case("in-app")))
+        .unwrap_or(false);
+    let mut backends: Vec<Box<dyn NotificationBackend>> = Vec::new();
+    if !in_app_only {
+        #[cfg(windows)]
+        backends.push(Box::new(WinRtBackend));
+        #[cfg(target_os = "linux")]
+        backends.push(Box::new(DbusBackend));
+    }
+    backends.push(Box::new(InAppBackend));
+    backends
+}
+
+// --- Dispatch ----------------------------------------------------------------
+
+/// Shows a finished operation, honouring mute, grouping and action settings.
+/// `more_queued` tells whether another operation starts right after this one.
+///
+/// Nothing is shown while the main window is focused; the user sees the
+/// in-app result. A closed, minimized or background window still notifies,
+/// so a VirusTotal warning or a failure is never missed.
+pub fn notify(app: &AppHandle, mut notification: Notification, more_queued: bool) {
+    let muted = parse_muted_kinds(read_config(app, MUTED_KINDS_KEY)).contains(&notification.kind);
+    if !read_flag(app, SHOW_ACTIONS_KEY) {
+        notification.actions.clear();
+    }
+
+    let hold = more_queued && read_flag(app, GROUP_QUEUED_KEY);
+    let shown = BATCH
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .route((!muted).then_some(notification), hold);
+    if shown.is_empty() {
+        return;
+    }
+
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_focused().unwrap_or(false) {
+            return;
+        }
+    }
+
+    for notification in &shown {
+        for backend in backends(app) {
+            match backend.show(app, notification) {
+                Ok(()) => break,
+                Err(e) => log::warn!("{} notification failed: {}", backend.name(), e),
+            }
+        }
+    }
+}
+
+/// Runs a notification action (or a click on its body, `None`).
+pub fn activate(app: &AppHandle, action: Option<NotificationAction>, retry: Option<EnqueueAction>) {
+    let restore = |app: &AppHandle| {
+        crate::tray::show_or_create_main_window(app);
+        let _ = app.emit(operations::EVENT_RESTORE, ());
+    };
+    match action {
+        Some(NotificationAction::ClearCache) => {
+            if let Err(e) = operations::run_pending_chain(app) {
+                log::warn!("notification clear-cache failed: {}", e);
+            }
+            restore(app);
+        }
+        Some(NotificationAction::InstallAnyway) => {
+            if let Err(e) = operations::confirm_install_anyway(app) {
+                log::warn!("notification install-anyway failed: {}", e);
+            }
+            restore(app);
+        }
+        Some(NotificationAction::Retry) => {
+            match retry {
+                Some(action) => {
+                    operations::enqueue(app, action);
+                }
+                None => log::warn!("notification retry has nothing to retry"),
+            }
+            restore(app);
+        }
+        Some(NotificationAction::Dismiss) => {}
+        // Body click or "Open log": bring the app back with the operation
+        // modal unminimized so the result and its output are visible.
+        Some(NotificationAction::OpenLog) | None => restore(app),
+    }
+}
+
+/// Action callbacks arrive on backend threads; window work and commands must
+/// run on the main thread.
+fn activate_on_main_thread(app: &AppHandle, action_id: Option<&str>, retry: Option<EnqueueAction>) {
+    let action = action_id.and_then(NotificationAction::from_id);
+    let app_for_main = app.clone();
+    let _ = app.run_on_main_thread(move || activate(&app_for_main, action, retry));
+}
+
+// --- Backends ----------------------------------------------------------------
+
+/// Emits the notification to the webview, which renders it in-app.
+pub struct InAppBackend;
+
+impl NotificationBackend for InAppBackend {
+    fn name(&self) -> &'static str {
+        "in-app"
+    }
+
+    fn show(&self, app: &AppHandle, notification: &Notification) -> Result<(), String> {
+        app.emit(EVENT_NOTIFICATION, notification)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(windows)]
+pub struct WinRtBackend;
+
+#[cfg(windows)]
+impl NotificationBackend for WinRtBackend {
+    fn name(&self) -> &'static str {
+        "WinRT"
+    }
+
+    fn show(&self, app: &AppHandle, notification: &Notification) -> Result<(), String> {
+        use tauri_winrt_notification::{Duration, Toast};
+
+        let headline = notification.headline();
+        let mut toast = Toast::new(&resolve_aumid(app))
+            .title(&headline)
+            .text1(&notification.body)
+            .duration(Duration::Short);
+        for action in &notification.actions {
+            toast = toast.add_button(action.label(), action.id());
+        }
+
+        // Fires on body click (argument = None) and on button click
+        // (argument = Some(action_id)).
+        let app_for_cb = app.clone();
+        let retry = notification.retry.clone();
+        toast = toast.on_activated(move |action: Option<String>| {
+            activate_on_main_thread(&app_for_cb, action.as_deref(), retry.clone());
+            Ok(())
+        });
+
+        toast.show().map_err(|e| e.to_string())
+    }
+}
+
+/// Pick the AppUserModelID for toasts. Uses our bundle identifier when the
+/// binary is in an installed location (installer registers the AUMID via
+/// the start-menu shortcut). In dev / raw `cargo build` binaries, we fall
+/// back to PowerShell's AUMID so the toast still appears — the icon and
+/// source name won't match rscoop, but actions still work.
+#[cfg(windows)]
+fn resolve_aumid(app: &AppHandle) -> String {
+    use std::path::MAIN_SEPARATOR as SEP;
+    use tauri_winrt_notification::Toast;
+
+    let is_dev = std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|d| d.display().to_string()))
+        .map(|d| {
+            d.ends_with(&format!("{SEP}target{SEP}debug"))
+                || d.ends_with(&format!("{SEP}target{SEP}release"))
+        })
+        .unwrap_or(true);
+
+    if is_dev {
+        Toast::POWERSHELL_APP_ID.to_string()
+    } else {
+        app.config().identifier.clone()
+    }
+}
+
+/// The freedesktop notification service over D-Bus.
+#[cfg(target_os = "linux")]
+pub struct DbusBackend;
+
+#[cfg(target_os = "linux")]
+impl NotificationBackend for DbusBackend {
+    fn name(&self) -> &'static str {
+        "D-Bus"
+    }
+
+    fn show(&self, app: &AppHandle, notification: &Notification) -> Result<(), String> {
+        use notify_rust::{Timeout, Urgency};
+
+        let mut native = notify_rust::Notification::new();
+        native
+            .appname("rScoop")
+            .summary(&notification.headline())
+            .body(&notification.body)
+            .timeout(Timeout::Default)
+            .urgency(if notification.success {
+                Urgency::Normal
+            } else {
+                Urgency::Critical
+            });
+        // "default" is the body click.
+        native.action("default", "Open");
+        for action in &notification.actions {
+            native.action(action.id(), action.label());
+        }
+        let handle = native.show().map_err(|e| e.to_string())?;
+
+        // Blocks until the notification is acted on or closed.
+        let app = app.clone();
+        let retry = notification.retry.clone();
+        std::thread::spawn(move || {
+            handle.wait_for_action(|action| match action {
+                "__closed" => {}
+                "default" => activate_on_main_thread(&app, None, None),
+                id => activate_on_main_thread(&app, Some(id), retry),
+            });
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn finished(title: &str, status: &str) -> Notification {
+        Notification {
+            title: title.to_string(),
+            body: String::new(),
+            success: status != "error",
+            status: status.to_string(),
+            kind: OperationKind::Update,
+            actions: vec![NotificationAction::OpenLog],
+            retry: None,
+        }
+    }
+
+    fn titles(shown: &[Notification]) -> Vec<&str> {
+        shown.iter().map(|n| n.title.as_str()).collect()
+    }
+
+    #[test]
+    fn single_results_pass_through_the_batch() {
+        let mut batch = Batch::default();
+        let shown = batch.route(Some(finished("Updating git", "success")), false);
+        assert_eq!(titles(&shown), vec!["Updating git"]);
+        assert_eq!(shown[0].headline(), "Updating git ✓");
+        assert_eq!(shown[0].actions, vec![NotificationAction::OpenLog]);
+    }
+
+    #[test]
+    fn results_with_their_own_actions_are_never_batched() {
+        let mut batch = Batch::default();
+        assert!(batch
+            .route(Some(finished("Updating git", "success")), true)
+            .is_empty());
+
+        let mut scan = finished("Installing tool", "warning");
+        scan.kind = OperationKind::Scan;
+        scan.actions = vec![
+            NotificationAction::InstallAnyway,
+            NotificationAction::Dismiss,
+        ];
+        let shown = batch.route(Some(scan), true);
+        assert_eq!(titles(&shown), vec!["Installing tool"]);
+        assert_eq!(shown[0].actions[0], NotificationAction::InstallAnyway);
+
+        let mut failed = finished("Updating curl", "error");
+        failed.actions = vec![NotificationAction::Retry];
+        let shown = batch.route(Some(failed), false);
+        assert_eq!(
+            titles(&shown),
+            vec!["1 operation finished", "Updating curl"]
+        );
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn a_muted_last_result_still_flushes_the_batch() {
+        let mut batch = Batch::default();
+        batch.route(Some(finished("Updating git", "success")), true);
+        batch.route(None, true);
+
+        let shown = batch.route(None, false);
+        assert_eq!(titles(&shown), vec!["1 operation finished"]);
+        assert_eq!(shown[0].body, "1 succeeded");
+        assert!(batch.route(None, false).is_empty());
+    }
+
+    #[test]
+    fn queued_results_collapse_into_one_summary() {
+        let mut batch = Batch::default();
+        for i in 0..26 {
+            batch.add(&finished(&format!("Updating app{}", i), "success"));
+        }
+        batch.add(&finished("Updating node", "warning"));
+        for name in ["git", "7zip", "curl"] {
+            batch.add(&finished(&format!("Updating {}", name), "error"));
+        }
+        let shown = batch
+            .finish(Some(finished("Updating vim", "error")))
+            .unwrap();
+
+        assert_eq!(shown.title, "31 operations finished");
+        assert_eq!(
+            shown.body,
+            "26 succeeded, 1 with warnings, 4 failed: Updating git, Updating 7zip, Updating curl and 1 more"
+        );
+        assert!(!shown.success);
+        assert_eq!(shown.headline(), "31 operations finished ✗");
+        assert_eq!(shown.actions, vec![NotificationAction::OpenLog]);
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn parses_actions_and_muted_kinds() {
+        for action in [
+            NotificationAction::InstallAnyway,
+            NotificationAction::ClearCache,
+            NotificationAction::OpenLog,
+            NotificationAction::Retry,
+            NotificationAction::Dismiss,
+        ] {
+            assert_eq!(NotificationAction::from_id(action.id()), Some(action));
+        }
+        assert_eq!(NotificationAction::from_id("default"), None);
+
+        assert_eq!(
+            parse_muted_kinds(Some(json!(["auto-update", "scan"]))),
+            vec![OperationKind::AutoUpdate, OperationKind::Scan]
+        );
+        assert!(parse_muted_kinds(Some(json!("auto-update"))).is_empty());
+        assert!(parse_muted_kinds(None).is_empty());
+    }
+}
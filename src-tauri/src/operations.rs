@@ -6,17 +6,22 @@
 //! `operation-state-changed` events to stay in sync.
 
 use crate::commands::scoop::{self, ScoopOp};
+use crate::commands::scoop_interpreter::{PackageResult, PackageStatus};
 use crate::commands::virustotal::{self, ScanWarning};
 use crate::state::AppState;
 use execra::tauri::ExecraExt;
 use execra::Finding;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::sync::Mutex;
 use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
 
 const OUTPUT_BUFFER_CAP: usize = 5000;
 const COMPLETED_HISTORY_CAP: usize = 20;
+/// Snapshots a slow listener may fall behind by before it skips to the latest.
+const STATE_CHANGES_CAP: usize = 64;
 
 pub const EVENT_OUTPUT: &str = "operation-output";
 pub const EVENT_FINISHED: &str = "operation-finished";
@@ -25,7 +30,7 @@ pub const EVENT_RESTORE: &str = "operation-restore";
 
 // --- Public DTOs -------------------------------------------------------------
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub enum OperationKind {
     Install,
@@ -49,6 +54,10 @@ pub struct CommandResult {
     pub success: bool,
     pub message: String,
     pub status: String,
+    /// Per-package results of a multi-package update, in the order Scoop
+    /// got to them. Empty for everything else.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<PackageResult>,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -57,6 +66,20 @@ pub struct OperationWarning {
     pub message: String,
 }
 
+/// A way to run a finished op again, offered from what went wrong.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RetryOption {
+    /// Enqueue the same action again.
+    Plain,
+    /// Hash mismatch: clear the package's download cache, then try again.
+    ClearCacheFirst,
+    /// Already installed: uninstall, then install again.
+    ForceReinstall,
+    /// Held: release the hold for one update, then hold the package again.
+    UnholdAndUpdate,
+}
+
 #[derive(Serialize, Clone, Debug)]
 pub struct CurrentOperation {
     pub id: String,
@@ -107,6 +130,9 @@ pub struct CurrentOperation {
     /// (uninstall completed with auto-clear-cache off).
     #[serde(rename = "canClearCache", default)]
     pub can_clear_cache: bool,
+    /// Ways to run the finished op again; see [`retry`].
+    #[serde(rename = "retryOptions", skip_serializing_if = "Vec::is_empty")]
+    pub retry_options: Vec<RetryOption>,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -135,6 +161,18 @@ pub struct CompletedOperation {
     pub operation_warnings: Vec<OperationWarning>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub findings: Vec<Finding>,
+    /// Interpreter code of the error that failed the op, if known.
+    #[serde(rename = "errorCode", skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    /// The action as enqueued; `None` for background tasks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<EnqueueAction>,
+    #[serde(rename = "retryOptions", skip_serializing_if = "Vec::is_empty")]
+    pub retry_options: Vec<RetryOption>,
+    /// Per-package breakdown of a multi-package update: updated from→to,
+    /// skipped (e.g. held) or failed with the interpreter's reason.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<PackageResult>,
 }
 
 #[derive(Serialize, Clone, Debug, Default)]
@@ -144,7 +182,7 @@ pub struct OperationStateSnapshot {
     pub completed: Vec<CompletedOperation>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "kebab-case")]
 pub enum EnqueueAction {
     Install {
@@ -157,6 +195,11 @@ pub enum EnqueueAction {
         package: String,
     },
     UpdateAll,
+    /// `scoop update` for an explicit set of packages (e.g. an update group),
+    /// in one op.
+    UpdateSet {
+        packages: Vec<String>,
+    },
     Uninstall {
         package: String,
         bucket: String,
@@ -183,6 +226,21 @@ pub enum EnqueueAction {
         #[serde(default)]
         version: Option<String>,
     },
+    /// Adds a bucket by cloning `url`. Succeeds without cloning when the
+    /// bucket already exists.
+    AddBucket {
+        bucket: String,
+        url: String,
+    },
+    /// Add-then-install for packages found in the remote package index:
+    /// expands into `AddBucket` + chained `Install`.
+    AddBucketAndInstall {
+        package: String,
+        bucket: String,
+        url: String,
+        #[serde(default)]
+        version: Option<String>,
+    },
 }
 
 impl EnqueueAction {
@@ -196,6 +254,10 @@ impl EnqueueAction {
             },
             EnqueueAction::Update { package } => format!("Updating {}", package),
             EnqueueAction::UpdateAll => "Updating all packages".to_string(),
+            EnqueueAction::UpdateSet { packages } => match packages.as_slice() {
+                [package] => format!("Updating {}", package),
+                _ => format!("Updating {} packages", packages.len()),
+            },
             EnqueueAction::Uninstall { package, .. } => format!("Uninstalling {}", package),
             EnqueueAction::ClearCache { package, .. } => format!("Clearing cache for {}", package),
             EnqueueAction::CleanupApps => "Cleaning up old app versions".to_string(),
@@ -203,6 +265,10 @@ impl EnqueueAction {
             EnqueueAction::Scan { package, .. } | EnqueueAction::ScanAndInstall { package, .. } => {
                 format!("Scanning {} with VirusTotal", package)
             }
+            EnqueueAction::AddBucket { bucket, .. }
+            | EnqueueAction::AddBucketAndInstall { bucket, .. } => {
+                format!("Adding bucket {}", bucket)
+            }
         }
     }
 
@@ -210,13 +276,16 @@ impl EnqueueAction {
         match self {
             EnqueueAction::Install { .. } => OperationKind::Install,
             EnqueueAction::Update { .. } => OperationKind::Update,
-            EnqueueAction::UpdateAll => OperationKind::UpdateAll,
+            EnqueueAction::UpdateAll | EnqueueAction::UpdateSet { .. } => OperationKind::UpdateAll,
             EnqueueAction::Uninstall { .. } => OperationKind::Uninstall,
             EnqueueAction::ClearCache { .. } => OperationKind::ClearCache,
             EnqueueAction::CleanupApps | EnqueueAction::CleanupCache => OperationKind::Cleanup,
             EnqueueAction::Scan { .. } | EnqueueAction::ScanAndInstall { .. } => {
                 OperationKind::Scan
             }
+            EnqueueAction::AddBucket { .. } | EnqueueAction::AddBucketAndInstall { .. } => {
+                OperationKind::Install
+            }
         }
     }
 
@@ -227,7 +296,8 @@ impl EnqueueAction {
             | EnqueueAction::Uninstall { package, .. }
             | EnqueueAction::ClearCache { package, .. }
             | EnqueueAction::Scan { package, .. }
-            | EnqueueAction::ScanAndInstall { package, .. } => Some(package.clone()),
+            | EnqueueAction::ScanAndInstall { package, .. }
+            | EnqueueAction::AddBucketAndInstall { package, .. } => Some(package.clone()),
             _ => None,
         }
     }
@@ -248,6 +318,12 @@ struct PendingOp {
     /// If false, the chain is a DEFERRED action (user must confirm — e.g.
     /// the "Clear Cache" button after an uninstall when auto-clear is off).
     auto_chain: bool,
+    /// The action as enqueued, before expansion, so the op can be run
+    /// again. `None` for synthetic ops, whose `action` is a placeholder.
+    origin: Option<EnqueueAction>,
+    /// Package to unhold while this op runs and hold again once it has
+    /// finished, whatever the outcome. Set by an unhold-and-update retry.
+    unhold: Option<String>,
 }
 
 struct ActiveOp {
@@ -267,9 +343,14 @@ struct ActiveOp {
     /// `Finding`s emitted by the interpreter — Notes blocks, recommendations,
     /// post-install hints. Surfaced in the modal/completed-op viewer.
     findings: Vec<Finding>,
+    /// Which packages a `scoop update` run updated, skipped or failed.
+    package_results: Vec<PackageResult>,
     /// Set by the Execra interpreter via `KnownError` events. Surfaces a
     /// diagnostic message over the generic "process exited with code N".
     known_error: Option<String>,
+    /// Code of `known_error` (e.g. `scoop.hash_mismatch`). Picks the retry
+    /// options offered for a failed op.
+    known_error_code: Option<String>,
     /// Set by the Execra interpreter via `Summary` events. Used as the
     /// success message in lieu of the generic "X completed successfully".
     summary: Option<String>,
@@ -300,7 +381,9 @@ impl ActiveOp {
             scan_warning: None,
             operation_warnings: Vec::new(),
             findings: Vec::new(),
+            package_results: Vec::new(),
             known_error: None,
+            known_error_code: None,
             summary: None,
             current_phase: None,
             phase_stack: Vec::new(),
@@ -309,6 +392,41 @@ impl ActiveOp {
     }
 }
 
+impl ActiveOp {
+    fn retry_options(&self) -> Vec<RetryOption> {
+        let Some(result) = self.result.as_ref() else {
+            return Vec::new();
+        };
+        retry_options(
+            self.pending.origin.as_ref(),
+            result.success,
+            self.known_error_code.as_deref(),
+            self.operation_warnings.iter().map(|w| w.code.as_str()),
+        )
+    }
+
+    /// The finished op as it appears in history.
+    fn to_completed(&self, result: &CommandResult) -> CompletedOperation {
+        CompletedOperation {
+            id: self.pending.id.clone(),
+            title: self.pending.title.clone(),
+            kind: self.pending.kind.clone(),
+            package_name: self.pending.package_name.clone(),
+            success: result.success,
+            message: result.message.clone(),
+            output: self.output.iter().cloned().collect(),
+            status: result.status.clone(),
+            scan_warning: self.scan_warning.clone(),
+            operation_warnings: self.operation_warnings.clone(),
+            findings: self.findings.clone(),
+            error_code: self.known_error_code.clone(),
+            action: self.pending.origin.clone(),
+            retry_options: self.retry_options(),
+            packages: result.packages.clone(),
+        }
+    }
+}
+
 pub struct OperationManager {
     next_id: u64,
     current: Option<ActiveOp>,
@@ -347,16 +465,18 @@ impl OperationManager {
                 operation_warnings: s.operation_warnings.clone(),
                 findings: s.findings.clone(),
                 scan_warning: s.scan_warning.clone(),
-                can_override_scan: matches!(
-                    s.pending.chain.as_deref(),
-                    Some(EnqueueAction::Install { .. })
-                ),
+                can_override_scan: matches!(s.pending.action, EnqueueAction::Scan { .. })
+                    && matches!(
+                        s.pending.chain.as_deref(),
+                        Some(EnqueueAction::Install { .. })
+                    ),
                 can_clear_cache: !s.pending.auto_chain
                     && matches!(
                         s.pending.chain.as_deref(),
                         Some(EnqueueAction::ClearCache { .. })
                     )
                     && s.result.as_ref().map(|r| r.success).unwrap_or(false),
+                retry_options: s.retry_options(),
             }),
             queue: self
                 .queue
@@ -384,8 +504,20 @@ pub fn snapshot(app: &AppHandle) -> OperationStateSnapshot {
     manager(app).lock().unwrap().snapshot()
 }
 
+/// State changes for listeners outside the webview, i.e. the automation API.
+static STATE_CHANGES: Lazy<broadcast::Sender<OperationStateSnapshot>> =
+    Lazy::new(|| broadcast::channel(STATE_CHANGES_CAP).0);
+
+/// Receives a snapshot after every change to the operation state.
+pub fn subscribe() -> broadcast::Receiver<OperationStateSnapshot> {
+    STATE_CHANGES.subscribe()
+}
+
 fn emit_state(app: &AppHandle) {
     let snap = snapshot(app);
+    if STATE_CHANGES.receiver_count() > 0 {
+        let _ = STATE_CHANGES.send(snap.clone());
+    }
     if let Err(e) = app.emit(EVENT_STATE_CHANGED, snap) {
         log::warn!("failed to emit state change: {}", e);
     }
@@ -424,157 +556,78 @@ pub fn has_active_work(app: &AppHandle) -> bool {
     active_running || !m.queue.is_empty()
 }
 
-/// What pending decision, if any, the current op is waiting on — drives
-/// which action buttons we attach to the toast.
-#[derive(Debug, Clone, Copy)]
-enum PendingDecision {
-    ClearCache,
-    InstallAnyway,
-}
-
-/// Fire a Windows toast notifying the user an operation finished.
-/// Skipped only when the main window is focused and visible (the user can
-/// see the in-app result). Fires when the window is closed, minimized, or
-/// behind other windows — so the user never misses a VT warning, failure,
-/// or "already installed" result.
-#[cfg(windows)]
+/// Hand the finished current op to the notifications module. Scan warnings
+/// offer "Install Anyway", a deferred cache-clear chain offers "Clear Cache",
+/// and failures offer "Retry" when the op can be run again.
 fn notify_result(app: &AppHandle, title: &str, success: bool, status: &str, message: &str) {
-    use tauri_winrt_notification::{Duration, Toast};
-
-    if let Some(window) = app.get_webview_window("main") {
-        if window.is_focused().unwrap_or(false) {
-            return;
-        }
-    }
+    use crate::notifications::{self, Notification, NotificationAction};
 
-    // Inspect the current op to decide which action buttons (if any) to
-    // attach. Scan warning → "Install Anyway"; deferred cache-clear chain
-    // → "Clear Cache"; otherwise no action buttons.
-    let decision: Option<PendingDecision> = {
+    let (kind, mut actions, retry, more_queued) = {
         let state = manager(app);
         let m = state.lock().unwrap();
-        m.current.as_ref().and_then(|a| {
-            if a.scan_warning.is_some()
-                && matches!(
-                    a.pending.chain.as_deref(),
-                    Some(EnqueueAction::Install { .. })
-                )
-            {
-                Some(PendingDecision::InstallAnyway)
-            } else if !a.pending.auto_chain
-                && matches!(
-                    a.pending.chain.as_deref(),
-                    Some(EnqueueAction::ClearCache { .. })
-                )
-                && success
-            {
-                Some(PendingDecision::ClearCache)
-            } else {
-                None
+        let Some(a) = m.current.as_ref() else {
+            return;
+        };
+        let mut actions = Vec::new();
+        let mut retry = None;
+        if a.scan_warning.is_some()
+            && matches!(
+                a.pending.chain.as_deref(),
+                Some(EnqueueAction::Install { .. })
+            )
+        {
+            actions.extend([
+                NotificationAction::InstallAnyway,
+                NotificationAction::Dismiss,
+            ]);
+        } else if !a.pending.auto_chain
+            && matches!(
+                a.pending.chain.as_deref(),
+                Some(EnqueueAction::ClearCache { .. })
+            )
+            && success
+        {
+            actions.extend([NotificationAction::ClearCache, NotificationAction::Dismiss]);
+        } else if !success {
+            retry = a.pending.origin.clone();
+            if retry.is_some() {
+                actions.push(NotificationAction::Retry);
             }
-        })
-    };
-
-    let marker = match status {
-        "warning" => "⚠",
-        _ if success => "✓",
-        _ => "✗",
-    };
-    let toast_title = format!("{} {}", title, marker);
-
-    let mut toast = Toast::new(&resolve_aumid(app))
-        .title(&toast_title)
-        .text1(message)
-        .duration(Duration::Short);
-
-    match decision {
-        Some(PendingDecision::ClearCache) => {
-            toast = toast
-                .add_button("Clear Cache", "clear-cache")
-                .add_button("Dismiss", "dismiss");
         }
-        Some(PendingDecision::InstallAnyway) => {
-            toast = toast
-                .add_button("Install Anyway", "install-anyway")
-                .add_button("Cancel", "dismiss");
-        }
-        None => {}
+        (a.pending.kind.clone(), actions, retry, !m.queue.is_empty())
+    };
+    if actions.is_empty() {
+        actions.push(NotificationAction::OpenLog);
     }
 
-    // Activation callback — fires on body click (argument = None) and on
-    // button click (argument = Some(action_id)).
-    let app_for_cb = app.clone();
-    toast = toast.on_activated(move |action: Option<String>| {
-        let app = app_for_cb.clone();
-        // Dispatch on the main thread: window recreation + Tauri command
-        // invocations must not happen on the WinRT callback thread.
-        let _ = app.clone().run_on_main_thread(move || {
-            match action.as_deref() {
-                Some("clear-cache") => {
-                    if let Err(e) = run_pending_chain(&app) {
-                        log::warn!("toast clear-cache failed: {}", e);
-                    }
-                    crate::tray::show_or_create_main_window(&app);
-                    let _ = app.emit(EVENT_RESTORE, ());
-                }
-                Some("install-anyway") => {
-                    if let Err(e) = confirm_install_anyway(&app) {
-                        log::warn!("toast install-anyway failed: {}", e);
-                    }
-                    crate::tray::show_or_create_main_window(&app);
-                    let _ = app.emit(EVENT_RESTORE, ());
-                }
-                Some("dismiss") => {
-                    // No-op: let Windows hide the toast.
-                }
-                _ => {
-                    // Body click or unknown action → restore the app and
-                    // force the frontend to unminimize the operation modal
-                    // so the user sees the result immediately.
-                    crate::tray::show_or_create_main_window(&app);
-                    let _ = app.emit(EVENT_RESTORE, ());
-                }
-            }
-        });
-        Ok(())
-    });
-
-    if let Err(e) = toast.show() {
-        log::warn!("failed to show toast: {}", e);
-    }
+    notifications::notify(
+        app,
+        Notification {
+            title: title.to_string(),
+            body: message.to_string(),
+            success,
+            status: status.to_string(),
+            kind,
+            actions,
+            retry,
+        },
+        more_queued,
+    );
 }
 
-/// Pick the AppUserModelID for toasts. Uses our bundle identifier when the
-/// binary is in an installed location (installer registers the AUMID via
-/// the start-menu shortcut). In dev / raw `cargo build` binaries, we fall
-/// back to PowerShell's AUMID so the toast still appears — the icon and
-/// source name won't match rscoop, but actions still work.
-#[cfg(windows)]
-fn resolve_aumid(app: &AppHandle) -> String {
-    use std::path::MAIN_SEPARATOR as SEP;
-    use tauri_winrt_notification::Toast;
-
-    let is_dev = std::env::current_exe()
-        .ok()
-        .and_then(|p| p.parent().map(|d| d.display().to_string()))
-        .map(|d| {
-            d.ends_with(&format!("{SEP}target{SEP}debug"))
-                || d.ends_with(&format!("{SEP}target{SEP}release"))
-        })
-        .unwrap_or(true);
-
-    if is_dev {
-        Toast::POWERSHELL_APP_ID.to_string()
-    } else {
-        app.config().identifier.clone()
+/// Reports the finishing current op to the configured notification sinks.
+/// Unlike the toast this fires regardless of window focus.
+fn notify_sinks(app: &AppHandle, result: &CommandResult) {
+    let completed = {
+        let state = manager(app);
+        let m = state.lock().unwrap();
+        m.current.as_ref().map(|active| active.to_completed(result))
+    };
+    if let Some(completed) = completed {
+        crate::commands::notification_sinks::dispatch(app, &completed);
     }
 }
 
-#[cfg(not(windows))]
-fn notify_result(_app: &AppHandle, _title: &str, _success: bool, _status: &str, _message: &str) {
-    // rscoop is Windows-only; no-op elsewhere.
-}
-
 /// Append one output line to the current operation and emit to the frontend.
 ///
 /// This is a pure transcript append — `line`/`source` are preserved verbatim.
@@ -619,10 +672,11 @@ pub fn push_operation_warning(app: &AppHandle, warning: OperationWarning) {
 /// Stash a semantic known-error surfaced by the interpreter. Used so the
 /// runner can report the interpreter's diagnostic instead of a generic
 /// "process exited with code N".
-pub fn set_known_error(app: &AppHandle, message: String) {
+pub fn set_known_error(app: &AppHandle, code: String, message: String) {
     with_current_mut(app, |active| {
         if active.known_error.is_none() {
             active.known_error = Some(message);
+            active.known_error_code = Some(code);
         }
         false
     });
@@ -647,6 +701,36 @@ pub fn push_finding(app: &AppHandle, finding: Finding) {
     });
 }
 
+/// Record one package's result inside a multi-package update.
+pub fn push_package_result(app: &AppHandle, result: PackageResult) {
+    with_current_mut(app, |active| {
+        active.package_results.push(result);
+        true
+    });
+}
+
+/// One-line tally of a multi-package update, e.g. "Updated 2 packages,
+/// 1 skipped, 1 failed".
+fn package_summary(packages: &[PackageResult]) -> String {
+    let count = |status| packages.iter().filter(|p| p.status == status).count();
+    let updated = count(PackageStatus::Updated);
+    let mut parts = vec![format!(
+        "Updated {} package{}",
+        updated,
+        if updated == 1 { "" } else { "s" }
+    )];
+    for (status, label) in [
+        (PackageStatus::Skipped, "skipped"),
+        (PackageStatus::Failed, "failed"),
+    ] {
+        let n = count(status);
+        if n > 0 {
+            parts.push(format!("{} {}", n, label));
+        }
+    }
+    parts.join(", ")
+}
+
 /// Push a phase label onto the active op's phase stack.
 pub fn push_phase(app: &AppHandle, label: String) {
     with_current_mut(app, |active| {
@@ -736,7 +820,25 @@ pub fn cancel_current_job(app: &AppHandle) -> Result<bool, String> {
 
 /// Enqueue an action. Starts immediately if idle; otherwise queues.
 pub fn enqueue(app: &AppHandle, action: EnqueueAction) -> String {
+    // Taken before expansion so meta-actions whose primary step has no
+    // package (add-bucket-then-install) still report the package.
+    let package_name = action.package_name();
+    let origin = action.clone();
     let (primary, chain, auto_chain) = expand_action(action);
+    push_pending(app, package_name, primary, chain, auto_chain, origin, None)
+}
+
+/// Queues `primary` with an already-expanded chain, starting it right away
+/// when nothing else runs.
+fn push_pending(
+    app: &AppHandle,
+    package_name: Option<String>,
+    primary: EnqueueAction,
+    chain: Option<Box<EnqueueAction>>,
+    auto_chain: bool,
+    origin: EnqueueAction,
+    unhold: Option<String>,
+) -> String {
     let (id, to_run) = {
         let state = manager(app);
         let mut m = state.lock().unwrap();
@@ -749,10 +851,12 @@ pub fn enqueue(app: &AppHandle, action: EnqueueAction) -> String {
             id: id.clone(),
             title: primary.title(),
             kind: primary.kind(),
-            package_name: primary.package_name(),
+            package_name,
             action: primary,
             chain,
             auto_chain,
+            origin: Some(origin),
+            unhold,
         };
         if m.current.is_none() {
             m.current = Some(ActiveOp::new(pending.clone()));
@@ -802,10 +906,88 @@ fn expand_action(action: EnqueueAction) -> (EnqueueAction, Option<Box<EnqueueAct
             }));
             (action, chain, auto_clear_cache)
         }
+        EnqueueAction::AddBucketAndInstall {
+            package,
+            bucket,
+            url,
+            version,
+        } => (
+            EnqueueAction::AddBucket {
+                bucket: bucket.clone(),
+                url,
+            },
+            Some(Box::new(EnqueueAction::Install {
+                package,
+                bucket,
+                version,
+            })),
+            true,
+        ),
         other => (other, None, true),
     }
 }
 
+// --- Update-all expansion ----------------------------------------------------
+
+const EXPAND_UPDATE_ALL_KEY: &str = "operations.expandUpdateAll";
+
+fn expand_update_all_enabled(app: &AppHandle) -> bool {
+    crate::commands::settings::get_config_value(app.clone(), EXPAND_UPDATE_ALL_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false)
+}
+
+/// Outdated packages that `scoop update *` would touch, by name. Held
+/// packages are left out — Scoop would skip them anyway.
+async fn update_targets(app: &AppHandle) -> Result<Vec<String>, String> {
+    let state = app.state::<AppState>();
+    let root = crate::commands::installed::resolved_root(app.clone(), &state).await;
+    let held: HashSet<String> = root.held_packages()?.into_iter().collect();
+    let mut names: Vec<String> = root
+        .updates()
+        .await?
+        .into_iter()
+        .map(|update| update.name)
+        .filter(|name| !held.contains(name))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// With `operations.expandUpdateAll` on, queues one `Update` per outdated
+/// package behind the running `UpdateAll` op, so one bad package doesn't
+/// hide the rest. Returns how many were queued, or `None` when the update
+/// should run as one op: the setting is off, nothing is outdated, or the
+/// check failed. Runs when the op starts, so every way of queueing an
+/// update-all honours the setting.
+async fn expand_update_all(app: &AppHandle) -> Option<usize> {
+    if !expand_update_all_enabled(app) {
+        return None;
+    }
+    match update_targets(app).await {
+        Ok(packages) if !packages.is_empty() => {
+            log::info!("Expanding update-all into {} updates", packages.len());
+            for package in &packages {
+                append_output(app, format!("Queued update of {}", package), "stdout");
+                enqueue(
+                    app,
+                    EnqueueAction::Update {
+                        package: package.clone(),
+                    },
+                );
+            }
+            Some(packages.len())
+        }
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("Could not expand update-all, running it as one op: {}", e);
+            None
+        }
+    }
+}
+
 /// Clear completed history.
 pub fn clear_completed(app: &AppHandle) {
     {
@@ -828,20 +1010,8 @@ fn archive_finished_current(m: &mut OperationManager) -> bool {
         return false;
     }
     if let Some(active) = m.current.take() {
-        let result = active.result.expect("checked above");
-        let completed = CompletedOperation {
-            id: active.pending.id,
-            title: active.pending.title,
-            kind: active.pending.kind,
-            package_name: active.pending.package_name,
-            success: result.success,
-            message: result.message,
-            output: active.output.into_iter().collect(),
-            status: result.status,
-            scan_warning: active.scan_warning,
-            operation_warnings: active.operation_warnings,
-            findings: active.findings,
-        };
+        let result = active.result.as_ref().expect("checked above");
+        let completed = active.to_completed(result);
         if m.completed.len() >= COMPLETED_HISTORY_CAP {
             m.completed.pop_front();
         }
@@ -906,6 +1076,7 @@ pub fn confirm_install_anyway(app: &AppHandle) -> Result<(), String> {
         let m = state.lock().unwrap();
         m.current
             .as_ref()
+            .filter(|a| matches!(a.pending.action, EnqueueAction::Scan { .. }))
             .and_then(|a| a.pending.chain.as_deref().cloned())
     };
     match install_action {
@@ -917,6 +1088,178 @@ pub fn confirm_install_anyway(app: &AppHandle) -> Result<(), String> {
     }
 }
 
+// --- Retry -------------------------------------------------------------------
+
+/// `(package, bucket, version)` of an action that ends in `scoop install`.
+fn install_target(action: &EnqueueAction) -> Option<(&String, &String, &Option<String>)> {
+    match action {
+        EnqueueAction::Install {
+            package,
+            bucket,
+            version,
+        }
+        | EnqueueAction::ScanAndInstall {
+            package,
+            bucket,
+            version,
+        }
+        | EnqueueAction::AddBucketAndInstall {
+            package,
+            bucket,
+            version,
+            ..
+        } => Some((package, bucket, version)),
+        _ => None,
+    }
+}
+
+/// Retry options for a finished op, from its enqueued action, outcome,
+/// known-error code and warning codes.
+fn retry_options<'a>(
+    action: Option<&EnqueueAction>,
+    success: bool,
+    error_code: Option<&str>,
+    warning_codes: impl Iterator<Item = &'a str>,
+) -> Vec<RetryOption> {
+    let Some(action) = action else {
+        return Vec::new();
+    };
+    let warning_codes: Vec<&str> = warning_codes.collect();
+    let installs = install_target(action).is_some();
+    let updates_one = matches!(action, EnqueueAction::Update { .. });
+
+    let mut options = Vec::new();
+    if !success {
+        options.push(RetryOption::Plain);
+        if error_code == Some("scoop.hash_mismatch") && (installs || updates_one) {
+            options.push(RetryOption::ClearCacheFirst);
+        }
+    }
+    if installs && warning_codes.contains(&"scoop.install.already_installed") {
+        options.push(RetryOption::ForceReinstall);
+    }
+    if updates_one && warning_codes.contains(&"scoop.update.held") {
+        options.push(RetryOption::UnholdAndUpdate);
+    }
+    options
+}
+
+/// What a retry does: run `primary` followed by `chain` in one op, with
+/// `unhold` released for the op's duration, then queue `then` as its own op.
+#[derive(Debug)]
+struct RetryPlan {
+    unhold: Option<String>,
+    primary: EnqueueAction,
+    chain: Option<EnqueueAction>,
+    /// A follow-up that expands into several steps itself, such as a
+    /// scanned install, and so cannot run as a chain.
+    then: Option<EnqueueAction>,
+}
+
+fn retry_plan(action: &EnqueueAction, option: RetryOption) -> Result<RetryPlan, String> {
+    let install = install_target(action).map(|(package, bucket, version)| EnqueueAction::Install {
+        package: package.clone(),
+        bucket: bucket.clone(),
+        version: version.clone(),
+    });
+    // A retried install scans again when the original did. The scan
+    // expands into steps of its own, so it then runs as a separate op;
+    // add-bucket installs never scanned and chain a plain install.
+    let scans = matches!(action, EnqueueAction::ScanAndInstall { .. });
+    let plan = |primary, chain: Option<EnqueueAction>| RetryPlan {
+        unhold: None,
+        primary,
+        then: chain.as_ref().filter(|_| scans).map(|_| action.clone()),
+        chain: chain.filter(|_| !scans),
+    };
+    match (option, action, install) {
+        (RetryOption::Plain, _, _) => Ok(plan(action.clone(), None)),
+        (RetryOption::ClearCacheFirst, _, Some(install)) => {
+            let EnqueueAction::Install {
+                package, bucket, ..
+            } = &install
+            else {
+                unreachable!("install_target always builds an install");
+            };
+            let clear = EnqueueAction::ClearCache {
+                package: package.clone(),
+                bucket: bucket.clone(),
+            };
+            Ok(plan(clear, Some(install)))
+        }
+        (RetryOption::ClearCacheFirst, EnqueueAction::Update { package }, None) => Ok(plan(
+            EnqueueAction::ClearCache {
+                package: package.clone(),
+                bucket: String::new(),
+            },
+            Some(action.clone()),
+        )),
+        (RetryOption::ForceReinstall, _, Some(install)) => {
+            let EnqueueAction::Install {
+                package, bucket, ..
+            } = &install
+            else {
+                unreachable!("install_target always builds an install");
+            };
+            let uninstall = EnqueueAction::Uninstall {
+                package: package.clone(),
+                bucket: bucket.clone(),
+                auto_clear_cache: false,
+            };
+            Ok(plan(uninstall, Some(install)))
+        }
+        (RetryOption::UnholdAndUpdate, EnqueueAction::Update { package }, _) => Ok(RetryPlan {
+            unhold: Some(package.clone()),
+            primary: action.clone(),
+            chain: None,
+            then: None,
+        }),
+        (option, action, _) => Err(format!("{:?} does not apply to {}", option, action.title())),
+    }
+}
+
+/// Runs a finished op (the lingering current one or one from history) again,
+/// optionally with one of its [`RetryOption`]s. Returns the new op id.
+pub fn retry(app: &AppHandle, id: &str, option: RetryOption) -> Result<String, String> {
+    let (action, options) = {
+        let state = manager(app);
+        let m = state.lock().unwrap();
+        if let Some(active) = m.current.as_ref().filter(|a| a.pending.id == id) {
+            if active.result.is_none() {
+                return Err("The operation is still running".to_string());
+            }
+            (active.pending.origin.clone(), active.retry_options())
+        } else if let Some(completed) = m.completed.iter().find(|c| c.id == id) {
+            (completed.action.clone(), completed.retry_options.clone())
+        } else {
+            return Err(format!("No finished operation with id {}", id));
+        }
+    };
+    let action = action.ok_or("This operation cannot be retried")?;
+    if !options.contains(&option) {
+        return Err(format!("{:?} is not offered for this operation", option));
+    }
+
+    let plan = retry_plan(&action, option)?;
+    log::info!("Retrying {} with {:?}", id, option);
+    let new_id = match (plan.chain, plan.unhold) {
+        (None, None) => enqueue(app, plan.primary),
+        (chain, unhold) => push_pending(
+            app,
+            action.package_name(),
+            plan.primary,
+            chain.map(Box::new),
+            true,
+            action,
+            unhold,
+        ),
+    };
+    if let Some(then) = plan.then {
+        enqueue(app, then);
+    }
+    Ok(new_id)
+}
+
 /// Explicit dismissal of a finished current op — moves it to completed.
 pub fn dismiss_current_result(app: &AppHandle) {
     let changed = {
@@ -956,6 +1299,8 @@ pub fn start_synthetic(
             action: EnqueueAction::UpdateAll,
             chain: None,
             auto_chain: true,
+            origin: None,
+            unhold: None,
         }));
         id
     };
@@ -965,23 +1310,30 @@ pub fn start_synthetic(
 
 /// Complete a synthetic op: emit finished, decide queue vs. linger, notify.
 pub fn finish_synthetic(app: &AppHandle, success: bool, message: String) {
+    // Capture the synthetic op's title for the toast before we mutate state.
+    let (title, packages) = {
+        let state = manager(app);
+        let m = state.lock().unwrap();
+        (
+            m.current
+                .as_ref()
+                .map(|a| a.pending.title.clone())
+                .unwrap_or_else(|| "Background task".to_string()),
+            m.current
+                .as_ref()
+                .map(|a| a.package_results.clone())
+                .unwrap_or_default(),
+        )
+    };
     let result = CommandResult {
         success,
         message: message.clone(),
         status: if success { "success" } else { "error" }.to_string(),
+        packages,
     };
     let _ = app.emit(EVENT_FINISHED, result.clone());
-
-    // Capture the synthetic op's title for the toast before we mutate state.
-    let title = {
-        let state = manager(app);
-        let m = state.lock().unwrap();
-        m.current
-            .as_ref()
-            .map(|a| a.pending.title.clone())
-            .unwrap_or_else(|| "Background task".to_string())
-    };
     notify_result(app, &title, success, &result.status, &message);
+    notify_sinks(app, &result);
 
     let next = {
         let state = manager(app);
@@ -1004,9 +1356,42 @@ fn spawn_runner(app: AppHandle, pending: PendingOp) {
     });
 }
 
+/// Holds or releases `package`, noting the change in the current op's output.
+fn set_hold(app: &AppHandle, package: &str, hold: bool) -> Result<(), String> {
+    let scoop_path = app.state::<AppState>().scoop_path();
+    let result = crate::commands::hold::modify_hold_status(&scoop_path, package, hold);
+    match (&result, hold) {
+        (Ok(()), false) => append_output(
+            app,
+            format!("Released the hold on {} for this update", package),
+            "stdout",
+        ),
+        (Ok(()), true) => append_output(app, format!("Put {} back on hold", package), "stdout"),
+        (Err(e), _) => {
+            let verb = if hold { "hold" } else { "unhold" };
+            log::warn!("Failed to {} {}: {}", verb, package, e);
+            append_output(
+                app,
+                format!("Failed to {} {}: {}", verb, package, e),
+                "stderr",
+            );
+        }
+    }
+    result
+}
+
 async fn run_action(app: AppHandle, pending: PendingOp) {
+    // Release a hold for this op only; it is restored once the op is done.
+    let unheld = match &pending.unhold {
+        Some(package) => set_hold(&app, package, false),
+        None => Ok(()),
+    };
+
     // Execute the primary action
-    let primary_result = execute_action(&app, &pending.action).await;
+    let primary_result = match &unheld {
+        Ok(()) => execute_action(&app, &pending.action).await,
+        Err(e) => Err(e.clone()),
+    };
     let primary_had_warnings = has_operation_warnings(&app);
 
     // If success and there's an auto chain, run it in the SAME op
@@ -1032,7 +1417,14 @@ async fn run_action(app: AppHandle, pending: PendingOp) {
         primary_result
     };
 
-    let (has_scan_warning, first_warning, warning_count, summary, known_error) = {
+    // Hold the package again whether the op succeeded, failed or was
+    // cancelled. A failure here is reported in the output but does not
+    // change the op's result.
+    if let (Some(package), Ok(())) = (&pending.unhold, &unheld) {
+        let _ = set_hold(&app, package, true);
+    }
+
+    let (has_scan_warning, first_warning, warning_count, summary, known_error, packages) = {
         let state = manager(&app);
         let m = state.lock().unwrap();
         let active = m.current.as_ref();
@@ -1045,6 +1437,9 @@ async fn run_action(app: AppHandle, pending: PendingOp) {
             warnings.len(),
             active.and_then(|a| a.summary.clone()),
             active.and_then(|a| a.known_error.clone()),
+            active
+                .map(|a| a.package_results.clone())
+                .unwrap_or_default(),
         )
     };
 
@@ -1052,32 +1447,48 @@ async fn run_action(app: AppHandle, pending: PendingOp) {
     // precedence over generic strings; warnings flip status without flipping
     // success. When multiple warnings fired, the first carries the message
     // and the count is appended ("…and 2 more") so the toast/bar stays
-    // single-line while the modal renders the full list.
+    // single-line while the modal renders the full list. Multi-package
+    // updates lead with the per-package tally instead.
+    let tally = (!packages.is_empty()).then(|| package_summary(&packages));
     let result = match &final_result {
         Ok(()) if let Some(warning) = first_warning => CommandResult {
             success: true,
-            message: if warning_count > 1 {
+            message: if let Some(tally) = tally {
+                tally
+            } else if warning_count > 1 {
                 format!("{} (+{} more)", warning.message, warning_count - 1)
             } else {
                 warning.message
             },
             status: "warning".to_string(),
+            packages,
         },
         Ok(()) => CommandResult {
             success: true,
-            message: summary.unwrap_or_else(|| format!("{} completed successfully", pending.title)),
+            message: tally
+                .or(summary)
+                .unwrap_or_else(|| format!("{} completed successfully", pending.title)),
             status: "success".to_string(),
+            packages,
         },
         Err(e) if has_scan_warning => CommandResult {
             success: true,
             message: "Scan completed with warnings".to_string(),
             status: "warning".to_string(),
+            packages,
         },
-        Err(e) => CommandResult {
-            success: false,
-            message: known_error.unwrap_or_else(|| e.clone()),
-            status: "error".to_string(),
-        },
+        Err(e) => {
+            let error = known_error.unwrap_or_else(|| e.clone());
+            CommandResult {
+                success: false,
+                message: match tally {
+                    Some(tally) => format!("{}. {}", tally, error),
+                    None => error,
+                },
+                status: "error".to_string(),
+                packages,
+            }
+        }
     };
 
     // Finish post-op hooks before notifying listeners. The frontend reloads
@@ -1110,6 +1521,7 @@ async fn run_action(app: AppHandle, pending: PendingOp) {
         &result.status,
         &result.message,
     );
+    notify_sinks(&app, &result);
 
     // Decide: if there's a queued op, move current → completed and start it.
     // Otherwise keep current with result set so the user can see the outcome
@@ -1151,7 +1563,9 @@ fn reset_semantic_state(app: &AppHandle) {
     with_current_mut(app, |active| {
         active.operation_warnings.clear();
         active.findings.clear();
+        active.package_results.clear();
         active.known_error = None;
+        active.known_error_code = None;
         active.summary = None;
         active.current_phase = None;
         active.phase_stack.clear();
@@ -1192,8 +1606,20 @@ async fn execute_action(app: &AppHandle, action: &EnqueueAction) -> Result<(), S
             scoop::execute_scoop(app.clone(), ScoopOp::Update, Some(package), None).await
         }
         EnqueueAction::UpdateAll => {
+            if let Some(count) = expand_update_all(app).await {
+                set_summary(app, format!("Queued {} package updates", count));
+                return Ok(());
+            }
             scoop::execute_scoop(app.clone(), ScoopOp::UpdateAll, None, None).await
         }
+        EnqueueAction::UpdateSet { packages } => {
+            let outcome = scoop::update_packages_outcome(app.clone(), packages).await?;
+            if outcome.is_success() {
+                Ok(())
+            } else {
+                Err(outcome.message())
+            }
+        }
         EnqueueAction::Uninstall {
             package, bucket, ..
         } => {
@@ -1228,6 +1654,12 @@ async fn execute_action(app: &AppHandle, action: &EnqueueAction) -> Result<(), S
             // primary=Scan + chain=Install at enqueue time.
             Err("ScanAndInstall was not expanded before execution".to_string())
         }
+        EnqueueAction::AddBucket { bucket, url } => {
+            crate::commands::bucket_install::add_bucket_for_operation(app, bucket, url).await
+        }
+        EnqueueAction::AddBucketAndInstall { .. } => {
+            Err("AddBucketAndInstall was not expanded before execution".to_string())
+        }
     }
 }
 
@@ -1240,7 +1672,9 @@ async fn run_post_hooks(app: &AppHandle, pending: &PendingOp, result: &Result<()
         EnqueueAction::Install { .. }
         | EnqueueAction::Uninstall { .. }
         | EnqueueAction::Update { .. }
-        | EnqueueAction::UpdateAll => {
+        | EnqueueAction::UpdateAll
+        | EnqueueAction::UpdateSet { .. }
+        | EnqueueAction::AddBucket { .. } => {
             invalidate_manifest_cache(&state.scoop_path()).await;
             invalidate_installed_cache(state.clone()).await;
             if result.is_ok() {
@@ -1253,3 +1687,174 @@ async fn run_post_hooks(app: &AppHandle, pending: &PendingOp, result: &Result<()
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn install(package: &str) -> EnqueueAction {
+        EnqueueAction::ScanAndInstall {
+            package: package.to_string(),
+            bucket: "main".to_string(),
+            version: None,
+        }
+    }
+
+    fn package(name: &str, status: PackageStatus) -> PackageResult {
+        PackageResult {
+            package: name.to_string(),
+            status,
+            from: None,
+            to: None,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn tallies_package_results() {
+        assert_eq!(
+            package_summary(&[package("git", PackageStatus::Updated)]),
+            "Updated 1 package"
+        );
+        assert_eq!(
+            package_summary(&[
+                package("nodejs", PackageStatus::Skipped),
+                package("git", PackageStatus::Updated),
+                package("firefox", PackageStatus::Skipped),
+                package("7zip", PackageStatus::Failed),
+            ]),
+            "Updated 1 package, 2 skipped, 1 failed"
+        );
+        assert_eq!(
+            package_summary(&[package("7zip", PackageStatus::Failed)]),
+            "Updated 0 packages, 1 failed"
+        );
+    }
+
+    #[test]
+    fn history_keeps_per_package_breakdown() {
+        let active = ActiveOp::new(PendingOp {
+            id: "op-1".to_string(),
+            title: EnqueueAction::UpdateAll.title(),
+            kind: OperationKind::UpdateAll,
+            package_name: None,
+            action: EnqueueAction::UpdateAll,
+            chain: None,
+            auto_chain: true,
+            origin: Some(EnqueueAction::UpdateAll),
+            unhold: None,
+        });
+        let packages = vec![
+            PackageResult {
+                from: Some("2.47.0".to_string()),
+                to: Some("2.47.1".to_string()),
+                ..package("git", PackageStatus::Updated)
+            },
+            PackageResult {
+                reason: Some("held at 20.11.0".to_string()),
+                ..package("nodejs", PackageStatus::Skipped)
+            },
+        ];
+        let completed = active.to_completed(&CommandResult {
+            success: true,
+            message: package_summary(&packages),
+            status: "warning".to_string(),
+            packages: packages.clone(),
+        });
+        assert_eq!(completed.packages, packages);
+
+        let json = serde_json::to_value(&completed).unwrap();
+        assert_eq!(json["packages"][0]["status"], "updated");
+        assert_eq!(json["packages"][0]["to"], "2.47.1");
+        assert_eq!(json["packages"][1]["reason"], "held at 20.11.0");
+        assert!(json["packages"][1].get("from").is_none());
+    }
+
+    #[test]
+    fn offers_retries_from_error_and_warning_codes() {
+        let git = install("git");
+        let none = std::iter::empty::<&str>;
+        assert_eq!(
+            retry_options(Some(&git), false, Some("scoop.hash_mismatch"), none()),
+            vec![RetryOption::Plain, RetryOption::ClearCacheFirst]
+        );
+        assert_eq!(
+            retry_options(Some(&git), false, Some("scoop.download.failed"), none()),
+            vec![RetryOption::Plain]
+        );
+        assert_eq!(
+            retry_options(
+                Some(&git),
+                true,
+                None,
+                ["scoop.install.already_installed"].into_iter()
+            ),
+            vec![RetryOption::ForceReinstall]
+        );
+
+        let update = EnqueueAction::Update {
+            package: "node".to_string(),
+        };
+        assert_eq!(
+            retry_options(Some(&update), true, None, ["scoop.update.held"].into_iter()),
+            vec![RetryOption::UnholdAndUpdate]
+        );
+        assert!(retry_options(Some(&update), true, None, none()).is_empty());
+        // Background tasks have nothing to re-enqueue.
+        assert!(retry_options(None, false, None, none()).is_empty());
+    }
+
+    #[test]
+    fn plans_retries_as_chained_actions() {
+        let git = install("git");
+
+        let plan = retry_plan(&git, RetryOption::Plain).unwrap();
+        assert!(matches!(plan.primary, EnqueueAction::ScanAndInstall { .. }));
+        assert!(plan.chain.is_none());
+
+        let plan = retry_plan(&git, RetryOption::ClearCacheFirst).unwrap();
+        assert!(matches!(
+            plan.primary,
+            EnqueueAction::ClearCache { ref package, ref bucket } if package == "git" && bucket == "main"
+        ));
+        // The reinstall scans again, so it follows as its own op.
+        assert!(plan.chain.is_none());
+        assert!(matches!(
+            plan.then,
+            Some(EnqueueAction::ScanAndInstall { .. })
+        ));
+
+        let plan = retry_plan(&git, RetryOption::ForceReinstall).unwrap();
+        assert!(matches!(
+            plan.primary,
+            EnqueueAction::Uninstall {
+                auto_clear_cache: false,
+                ..
+            }
+        ));
+        assert!(plan.chain.is_none());
+        assert!(matches!(
+            plan.then,
+            Some(EnqueueAction::ScanAndInstall { .. })
+        ));
+
+        let unscanned = EnqueueAction::Install {
+            package: "git".to_string(),
+            bucket: "main".to_string(),
+            version: None,
+        };
+        let plan = retry_plan(&unscanned, RetryOption::ForceReinstall).unwrap();
+        assert!(matches!(plan.chain, Some(EnqueueAction::Install { .. })));
+        assert!(plan.then.is_none());
+
+        let update = EnqueueAction::Update {
+            package: "node".to_string(),
+        };
+        let plan = retry_plan(&update, RetryOption::UnholdAndUpdate).unwrap();
+        assert_eq!(plan.unhold.as_deref(), Some("node"));
+        assert!(matches!(plan.primary, EnqueueAction::Update { .. }));
+
+        assert!(retry_plan(&git, RetryOption::UnholdAndUpdate).is_err());
+        assert!(retry_plan(&EnqueueAction::UpdateAll, RetryOption::ForceReinstall).is_err());
+    }
+}
@@ -1,4 +1,7 @@
 use crate::commands;
+use crate::commands::bucket_changes::BucketUpdateReport;
+use crate::commands::bucket_install::BucketInstallResult;
+use crate::commands::update_groups::AutoUpdatePlan;
 use crate::operations::{self, OperationKind};
 use crate::state;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -8,6 +11,13 @@ use tauri::Manager;
 const INTERVAL_KEY: &str = "buckets.autoUpdateInterval";
 const LAST_RUN_KEY: &str = "buckets.lastAutoUpdateTs";
 const UPDATE_PACKAGES_KEY: &str = "buckets.autoUpdatePackagesEnabled";
+const LAST_REPORT_KEY: &str = "buckets.lastAutoUpdateReport";
+const RETRIES_KEY: &str = "buckets.autoUpdateNetworkRetries";
+const RETRY_DELAY_KEY: &str = "buckets.autoUpdateRetryDelaySecs";
+const DEFAULT_RETRIES: u64 = 2;
+const DEFAULT_RETRY_DELAY_SECS: u64 = 30;
+/// Longest wait between two attempts, however many retries are configured.
+const MAX_RETRY_DELAY_SECS: u64 = 15 * 60;
 const OFF_POLL_SECS: u64 = 30;
 const BUSY_RETRY_SECS: u64 = 60;
 const MAX_SLEEP_SECS: u64 = 60;
@@ -23,6 +33,114 @@ enum SchedulerRun {
     Busy,
 }
 
+/// Automatic retries for scheduled runs that fail on network problems.
+/// The delay doubles after every attempt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct RetryPolicy {
+    retries: u64,
+    base_delay_secs: u64,
+}
+
+impl RetryPolicy {
+    fn from_settings(app: &AppHandle) -> Self {
+        Self {
+            retries: read_config_u64(app, RETRIES_KEY).unwrap_or(DEFAULT_RETRIES),
+            base_delay_secs: read_config_u64(app, RETRY_DELAY_KEY)
+                .unwrap_or(DEFAULT_RETRY_DELAY_SECS),
+        }
+    }
+
+    /// Delay before retry number `retry` (1-based).
+    fn delay_secs(&self, retry: u64) -> u64 {
+        let factor = 1u64
+            .checked_shl(retry.saturating_sub(1) as u32)
+            .unwrap_or(u64::MAX);
+        self.base_delay_secs
+            .saturating_mul(factor)
+            .min(MAX_RETRY_DELAY_SECS)
+    }
+
+    /// Waits out the delay before retry number `retry`, or returns false
+    /// when the retries are used up.
+    async fn wait_before(&self, app: &AppHandle, retry: u64) -> bool {
+        if retry > self.retries {
+            return false;
+        }
+        let delay = self.delay_secs(retry);
+        operations::append_output(
+            app,
+            format!(
+                "Network problem; retrying in {}s (retry {} of {})",
+                delay, retry, self.retries
+            ),
+            "stderr",
+        );
+        sleep_secs(delay).await;
+        true
+    }
+}
+
+/// Scoop errors worth retrying: the download failed or the remote was
+/// unreachable. Hash mismatches, missing manifests and the like fail the same
+/// way again. Scoop only reports text; bucket fetches are classified by their
+/// git error instead.
+fn is_network_failure(code: Option<&str>, message: &str) -> bool {
+    if code == Some("scoop.download.failed") {
+        return true;
+    }
+    let message = message.to_lowercase();
+    [
+        "could not resolve",
+        "remote name could not be resolved",
+        "failed to connect",
+        "unable to connect",
+        "unable to access",
+        "connection reset",
+        "connection refused",
+        "timed out",
+        "network is unreachable",
+        "temporary failure in name resolution",
+        "failed to resolve address",
+        "ssl/tls",
+        "ssl connection",
+        "download failed",
+    ]
+    .iter()
+    .any(|needle| message.contains(needle))
+}
+
+/// Buckets whose fetch failed in the network or TLS layer.
+fn network_failed_buckets(results: &[BucketInstallResult]) -> Vec<String> {
+    results
+        .iter()
+        .filter(|r| !r.success && r.network_error)
+        .map(|r| r.bucket_name.clone())
+        .collect()
+}
+
+/// Swaps in the results of a retry, keeping the original bucket order.
+fn replace_results(results: &mut [BucketInstallResult], retried: Vec<BucketInstallResult>) {
+    for retried in retried {
+        if let Some(slot) = results
+            .iter_mut()
+            .find(|r| r.bucket_name == retried.bucket_name)
+        {
+            *slot = retried;
+        }
+    }
+}
+
+fn outcome_is_network_failure(outcome: &execra::Outcome) -> bool {
+    match outcome {
+        execra::Outcome::Failed {
+            reason: execra::FailureReason::KnownError { code, message },
+            ..
+        } => is_network_failure(Some(code), message),
+        execra::Outcome::Failed { .. } => is_network_failure(None, &outcome.message()),
+        _ => false,
+    }
+}
+
 pub fn start_background_tasks(app: AppHandle) {
     tauri::async_runtime::spawn(async move {
         loop {
@@ -104,8 +222,25 @@ async fn run_auto_bucket_update(
     );
 
     let mut should_update_packages = false;
+    let retry = RetryPolicy::from_settings(app);
+    let mut attempt = 0;
+    let mut update_result = commands::bucket_install::update_all_buckets(app.clone()).await;
+    while let Ok(results) = &mut update_result {
+        let failed = network_failed_buckets(results);
+        attempt += 1;
+        if failed.is_empty() || !retry.wait_before(app, attempt).await {
+            break;
+        }
+        match commands::bucket_install::update_named_buckets(app.clone(), &failed).await {
+            Ok(retried) => replace_results(results, retried),
+            Err(e) => {
+                log::warn!("Could not retry bucket updates: {}", e);
+                break;
+            }
+        }
+    }
 
-    match commands::bucket_install::update_all_buckets(app.clone()).await {
+    match update_result {
         Ok(results) => {
             let successes = results.iter().filter(|r| r.success).count();
             let total = results.len();
@@ -118,7 +253,15 @@ async fn run_auto_bucket_update(
             );
 
             for result in &results {
-                let (line, source) = if result.success {
+                let (line, source) = if let Some(reference) = &result.pinned_ref {
+                    (
+                        format!(
+                            "Skipped pinned bucket: {} ({})",
+                            result.bucket_name, reference
+                        ),
+                        "stdout",
+                    )
+                } else if result.success {
                     (format!("Updated bucket: {}", result.bucket_name), "stdout")
                 } else {
                     (
@@ -132,6 +275,20 @@ async fn run_auto_bucket_update(
                 operations::append_output(app, line, source);
             }
 
+            let report =
+                BucketUpdateReport::from_reports(results.iter().filter_map(|r| r.changes.as_ref()));
+            if !report.installed_updates.is_empty() {
+                operations::append_output(
+                    app,
+                    format!(
+                        "{} installed apps have updates after this pull",
+                        report.installed_updates.len()
+                    ),
+                    "stdout",
+                );
+            }
+            store_bucket_update_report(app, &report);
+
             operations::finish_synthetic(
                 app,
                 successes == total,
@@ -181,14 +338,72 @@ async fn run_auto_package_update(app: &AppHandle) {
         "stdout",
     );
 
+    // Update groups and policies may narrow `scoop update *` down to a list.
+    // If they can't be applied, update nothing rather than everything.
+    let packages = match commands::update_groups::auto_update_plan(app).await {
+        Ok(AutoUpdatePlan::All) => None,
+        Ok(AutoUpdatePlan::Only {
+            packages,
+            held_back,
+        }) => {
+            for line in &held_back {
+                operations::append_output(
+                    app,
+                    format!("Held back by update policy: {}", line),
+                    "stdout",
+                );
+            }
+            if packages.is_empty() {
+                let message = if held_back.is_empty() {
+                    "All apps are up to date".to_string()
+                } else {
+                    format!(
+                        "No automatic updates ({} held back by policy)",
+                        held_back.len()
+                    )
+                };
+                operations::finish_synthetic(app, true, message);
+                return;
+            }
+            Some(packages)
+        }
+        Err(e) => {
+            log::warn!("Could not apply update policies: {}", e);
+            operations::append_output(app, format!("Error: {}", e), "stderr");
+            operations::finish_synthetic(
+                app,
+                false,
+                format!("Automatic package update failed: {}", e),
+            );
+            return;
+        }
+    };
+
     let state = app.state::<state::AppState>();
-    let update_outcome = commands::scoop::execute_scoop_outcome(
-        app.clone(),
-        commands::scoop::ScoopOp::UpdateAll,
-        None,
-        None,
-    )
-    .await;
+    let retry = RetryPolicy::from_settings(app);
+    let mut attempt = 0;
+    let update_outcome = loop {
+        let outcome = match &packages {
+            None => {
+                commands::scoop::execute_scoop_outcome(
+                    app.clone(),
+                    commands::scoop::ScoopOp::UpdateAll,
+                    None,
+                    None,
+                )
+                .await
+            }
+            Some(packages) => commands::scoop::update_packages_outcome(app.clone(), packages).await,
+        };
+        let network_failure = match &outcome {
+            Ok(outcome) => outcome_is_network_failure(outcome),
+            Err(e) => is_network_failure(None, e),
+        };
+        attempt += 1;
+        if !network_failure || !retry.wait_before(app, attempt).await {
+            break outcome;
+        }
+    };
 
     match update_outcome {
         Ok(outcome) if outcome.is_success() => {
@@ -281,6 +496,23 @@ fn read_config_bool(app: &AppHandle, key: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Persists the latest bucket change report so the UI can show what the last
+/// automatic pull brought in, even after a restart.
+fn store_bucket_update_report(app: &AppHandle, report: &BucketUpdateReport) {
+    let value = match serde_json::to_value(report) {
+        Ok(value) => value,
+        Err(e) => {
+            log::warn!("Failed to serialize bucket update report: {}", e);
+            return;
+        }
+    };
+    if let Err(e) =
+        commands::settings::set_config_value(app.clone(), LAST_REPORT_KEY.to_string(), value)
+    {
+        log::warn!("Failed to store bucket update report: {}", e);
+    }
+}
+
 fn mark_auto_update_finished(app: &AppHandle) {
     let finished_at = unix_now_secs();
     let _ = commands::settings::set_config_value(
@@ -339,6 +571,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn backs_off_exponentially_up_to_a_cap() {
+        let policy = RetryPolicy {
+            retries: 10,
+            base_delay_secs: 30,
+        };
+        assert_eq!(policy.delay_secs(1), 30);
+        assert_eq!(policy.delay_secs(2), 60);
+        assert_eq!(policy.delay_secs(3), 120);
+        assert_eq!(policy.delay_secs(10), MAX_RETRY_DELAY_SECS);
+        assert_eq!(policy.delay_secs(200), MAX_RETRY_DELAY_SECS);
+    }
+
+    #[test]
+    fn retries_only_network_failures() {
+        assert!(is_network_failure(
+            Some("scoop.download.failed"),
+            "Download failed."
+        ));
+        assert!(is_network_failure(
+            None,
+            "fatal: unable to access 'https://github.com/ScoopInstaller/Main/': Could not resolve host: github.com"
+        ));
+        assert!(is_network_failure(
+            None,
+            "Failed to fetch: connection timed out"
+        ));
+        assert!(is_network_failure(
+            None,
+            "failed to resolve address for github.com: Name or service not known"
+        ));
+        assert!(is_network_failure(
+            None,
+            "Could not establish trust relationship for the SSL/TLS secure channel."
+        ));
+        assert!(!is_network_failure(
+            Some("scoop.hash_mismatch"),
+            "Downloaded file hash did not match manifest"
+        ));
+        assert!(!is_network_failure(
+            None,
+            "Couldn't find manifest for 'nope'"
+        ));
+    }
+
+    fn bucket_result(name: &str, success: bool, network_error: bool) -> BucketInstallResult {
+        BucketInstallResult {
+            success,
+            message: String::new(),
+            bucket_name: name.to_string(),
+            bucket_path: None,
+            manifest_count: None,
+            changes: None,
+            pinned_ref: None,
+            network_error,
+        }
+    }
+
+    #[test]
+    fn retries_only_buckets_that_failed_on_the_network() {
+        let mut results = vec![
+            bucket_result("extras", false, true),
+            bucket_result("main", true, false),
+            bucket_result("nirsoft", false, false),
+            bucket_result("versions", false, true),
+        ];
+        assert_eq!(network_failed_buckets(&results), ["extras", "versions"]);
+
+        replace_results(
+            &mut results,
+            vec![
+                bucket_result("versions", true, false),
+                bucket_result("extras", false, true),
+            ],
+        );
+        let names: Vec<_> = results.iter().map(|r| r.bucket_name.as_str()).collect();
+        assert_eq!(names, ["extras", "main", "nirsoft", "versions"]);
+        assert!(results[3].success);
+        assert_eq!(network_failed_buckets(&results), ["extras"]);
+    }
+
     #[test]
     fn computes_due_time_from_finished_timestamp() {
         assert_eq!(seconds_until_due(0, 1_000, 3_600), 0);
@@ -0,0 +1,163 @@
+//! A Scoop installation addressed by its root path.
+//!
+//! `ScoopRoot` is the plain-Rust API behind the Tauri commands and
+//! `rscoop-cli`. It takes no `AppHandle` or `State`, so it can be driven
+//! against a fake root from tests on any platform. The app lends it the
+//! installed-packages cache through `AppState::root`; a root built with
+//! [`ScoopRoot::new`] reads the disk on every call.
+use crate::commands::doctor::cache::{self, CacheEntry};
+use crate::commands::doctor::shim::{self, Shim};
+use crate::commands::updates::UpdatablePackage;
+use crate::commands::{bucket, hold, installed, search, status, updates};
+use crate::models::{BucketInfo, ScoopPackage, ScoopStatus, SearchResult};
+use crate::state::InstalledPackagesCache;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+pub struct ScoopRoot<'a> {
+    path: PathBuf,
+    installed_cache: Option<&'a Mutex<Option<InstalledPackagesCache>>>,
+}
+
+impl ScoopRoot<'static> {
+    /// A root without caches.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            installed_cache: None,
+        }
+    }
+}
+
+impl<'a> ScoopRoot<'a> {
+    /// A root that reuses `cache` for installed packages while the apps
+    /// directory is unchanged.
+    pub(crate) fn with_installed_cache(
+        path: PathBuf,
+        cache: &'a Mutex<Option<InstalledPackagesCache>>,
+    ) -> Self {
+        Self {
+            path,
+            installed_cache: Some(cache),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn apps_dir(&self) -> PathBuf {
+        self.path.join("apps")
+    }
+
+    pub fn buckets_dir(&self) -> PathBuf {
+        self.path.join("buckets")
+    }
+
+    pub fn cache_dir(&self) -> PathBuf {
+        self.path.join("cache")
+    }
+
+    pub fn persist_dir(&self) -> PathBuf {
+        self.path.join("persist")
+    }
+
+    pub fn shims_dir(&self) -> PathBuf {
+        self.path.join("shims")
+    }
+
+    /// Installed packages, from `apps/<name>/current` (or the newest version
+    /// directory when `current` is missing).
+    pub async fn installed_packages(&self) -> Result<Vec<ScoopPackage>, String> {
+        match self.installed_cache {
+            Some(cache) => installed::cached_installed_packages(&self.path, cache).await,
+            None => installed::scan_installed_packages(&self.path),
+        }
+    }
+
+    /// Names of installed packages whose `install.json` has `"hold": true`.
+    pub fn held_packages(&self) -> Result<Vec<String>, String> {
+        hold::held_packages(&self.path)
+    }
+
+    /// What `scoop status` would report, from local repositories only.
+    pub async fn status(&self) -> Result<ScoopStatus, String> {
+        let installed = self.installed_packages().await?;
+        let held: HashSet<String> = self.held_packages()?.into_iter().collect();
+        Ok(status::compute_scoop_status(&self.path, &installed, &held).await)
+    }
+
+    /// Installed packages whose bucket manifest has a different version.
+    pub async fn updates(&self) -> Result<Vec<UpdatablePackage>, String> {
+        let installed = self.installed_packages().await?;
+        updates::find_updates(self.path.clone(), installed).await
+    }
+
+    pub async fn buckets(&self) -> Result<Vec<BucketInfo>, String> {
+        bucket::list_buckets(&self.path).await
+    }
+
+    /// Like [`buckets`](Self::buckets), with each bucket's health filled in.
+    /// This reads every manifest of buckets that changed since the last call.
+    pub async fn buckets_with_health(&self) -> Result<Vec<BucketInfo>, String> {
+        let mut buckets = self.buckets().await?;
+        bucket::attach_health(&mut buckets, &self.buckets_dir()).await;
+        Ok(buckets)
+    }
+
+    /// Searches the manifests of all added buckets, marking installed ones.
+    pub async fn search(&self, term: &str) -> Result<SearchResult, String> {
+        let installed = self.installed_packages().await.unwrap_or_default();
+        search::search_packages(&self.path, term, &installed).await
+    }
+
+    /// Downloads in `cache/`, with downloads of versioned installs marked as
+    /// unsafe to delete.
+    pub async fn cache_entries(&self) -> Result<Vec<CacheEntry>, String> {
+        let installed = self.installed_packages().await?;
+        cache::cache_entries(&self.path, &installed)
+    }
+
+    /// Local and global shims.
+    pub fn shims(&self) -> Result<Vec<Shim>, String> {
+        shim::all_shims(&self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::AppState;
+    use crate::test_support::TempDir;
+    use std::fs;
+    use std::path::Path;
+
+    fn install(root: &Path, name: &str) {
+        let dir = root.join("apps").join(name).join("current");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("manifest.json"), r#"{ "version": "1.0" }"#).unwrap();
+        fs::write(dir.join("install.json"), r#"{ "bucket": "main" }"#).unwrap();
+    }
+
+    #[tokio::test]
+    async fn app_root_caches_installed_packages_until_apps_change() {
+        let temp = TempDir::new("scoop-root-cache");
+        install(&temp.path, "git");
+        let state = AppState::new(temp.path.clone());
+
+        assert_eq!(state.root().installed_packages().await.unwrap().len(), 1);
+        let fingerprint = state
+            .installed_packages
+            .lock()
+            .await
+            .as_ref()
+            .map(|cache| cache.fingerprint.clone())
+            .expect("scan fills the cache");
+
+        install(&temp.path, "jq");
+        let packages = state.root().installed_packages().await.unwrap();
+        assert_eq!(packages.len(), 2);
+        let cache = state.installed_packages.lock().await;
+        assert_ne!(cache.as_ref().unwrap().fingerprint, fingerprint);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::models::ScoopPackage;
+use crate::scoop_root::ScoopRoot;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Mutex as StdMutex, RwLock};
@@ -48,6 +49,11 @@ impl AppState {
             .clone()
     }
 
+    /// The current Scoop root, sharing this state's installed-packages cache.
+    pub fn root(&self) -> ScoopRoot<'_> {
+        ScoopRoot::with_installed_cache(self.scoop_path(), &self.installed_packages)
+    }
+
     /// Updates the Scoop root path stored in the application state.
     ///
     /// Returns whether the path changed. When it does, path-dependent caches
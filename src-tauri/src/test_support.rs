@@ -0,0 +1,37 @@
+//! Helpers shared by the unit tests.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A uniquely named directory under the system temp dir, removed on drop.
+pub(crate) struct TempDir {
+    pub(crate) path: PathBuf,
+}
+
+impl TempDir {
+    pub(crate) fn new(name: &str) -> Self {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock before Unix epoch")
+            .as_nanos();
+        let path =
+            std::env::temp_dir().join(format!("rscoop-{}-{}-{}", name, process::id(), unique));
+        fs::create_dir_all(&path).expect("create temp test directory");
+        Self { path }
+    }
+
+    /// Writes `contents` to `relative`, creating parent directories.
+    pub(crate) fn write(&self, relative: &str, contents: &str) {
+        let path = self.path.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
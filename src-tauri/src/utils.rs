@@ -309,34 +309,37 @@ pub fn resolve_scoop_root<R: Runtime>(app: AppHandle<R>) -> Result<PathBuf, Stri
         }
     }
 
-    let candidates = build_candidate_list(std::iter::empty());
-
-    if let Some(best) = select_best_scoop_root(candidates, None) {
-        let best_path = best.path.clone();
+    let best_path = detect_scoop_root().map_err(|_| {
+        "Unable to determine Scoop root directory. Please configure it explicitly in Settings."
+            .to_string()
+    })?;
 
-        log::info!(
-            "Auto-detected Scoop root: {} (apps_dir={}, buckets_dir={}, installs={})",
+    if let Err(e) = settings::persist_scoop_path(app.clone(), &best_path.to_string_lossy()) {
+        log::warn!(
+            "Failed to persist detected Scoop path '{}': {}",
             best_path.display(),
-            best.has_apps_dir,
-            best.has_buckets_dir,
-            best.installed_count
+            e
         );
+    }
 
-        if let Err(e) = settings::persist_scoop_path(app.clone(), &best_path.to_string_lossy()) {
-            log::warn!(
-                "Failed to persist detected Scoop path '{}': {}",
-                best_path.display(),
-                e
-            );
-        }
+    Ok(best_path)
+}
 
-        return Ok(best_path);
-    }
+/// Scores the likely Scoop directories and returns the best match, without
+/// consulting or updating the stored setting.
+pub fn detect_scoop_root() -> Result<PathBuf, String> {
+    let candidates = build_candidate_list(std::iter::empty());
+    let best = select_best_scoop_root(candidates, None)
+        .ok_or("Unable to determine Scoop root directory.")?;
 
-    Err(
-        "Unable to determine Scoop root directory. Please configure it explicitly in Settings."
-            .to_string(),
-    )
+    log::info!(
+        "Auto-detected Scoop root: {} (apps_dir={}, buckets_dir={}, installs={})",
+        best.path.display(),
+        best.has_apps_dir,
+        best.has_buckets_dir,
+        best.installed_count
+    );
+    Ok(best.path)
 }
 
 // -----------------------------------------------------------------------------
@@ -935,32 +938,8 @@ pub fn count_manifests(bucket_path: &std::path::Path) -> u32 {
 #[cfg(test)]
 mod manifest_tests {
     use super::*;
+    use crate::test_support::TempDir;
     use std::io;
-    use std::process;
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    struct TempDir {
-        path: PathBuf,
-    }
-
-    impl TempDir {
-        fn new(name: &str) -> Self {
-            let unique = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("system clock before Unix epoch")
-                .as_nanos();
-            let path =
-                env::temp_dir().join(format!("rscoop-{}-{}-{}", name, process::id(), unique));
-            fs::create_dir_all(&path).expect("create temp test directory");
-            Self { path }
-        }
-    }
-
-    impl Drop for TempDir {
-        fn drop(&mut self) {
-            let _ = fs::remove_dir_all(&self.path);
-        }
-    }
 
     fn write_manifest(path: &Path) {
         if let Some(parent) = path.parent() {
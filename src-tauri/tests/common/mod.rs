@@ -0,0 +1,157 @@
+//! Builder for fake Scoop roots in a temporary directory.
+//!
+//! Layouts follow what Scoop writes on Windows, except that `apps/<name>/current`
+//! is a plain copy of the version directory rather than a junction, so the
+//! same fixtures work on Linux.
+#![allow(dead_code)]
+
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+pub struct ScoopFixture {
+    root: PathBuf,
+}
+
+impl ScoopFixture {
+    /// An empty root with the directories Scoop creates on install.
+    pub fn new(name: &str) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let root =
+            std::env::temp_dir().join(format!("rscoop-fixture-{}-{}-{}", name, process::id(), id));
+        let _ = fs::remove_dir_all(&root);
+        for dir in ["apps", "buckets", "cache", "persist", "shims"] {
+            fs::create_dir_all(root.join(dir)).expect("create fixture directory");
+        }
+        Self { root }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    fn write(&self, relative: impl AsRef<Path>, contents: &str) -> PathBuf {
+        let path = self.root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).expect("create fixture parent");
+        fs::write(&path, contents).expect("write fixture file");
+        path
+    }
+
+    fn write_json(&self, relative: impl AsRef<Path>, value: &Value) -> PathBuf {
+        self.write(relative, &serde_json::to_string_pretty(value).unwrap())
+    }
+
+    /// A manifest in `buckets/<bucket>/bucket/<name>.json`.
+    pub fn bucket_manifest(self, bucket: &str, name: &str, version: &str) -> Self {
+        self.write_json(
+            format!("buckets/{}/bucket/{}.json", bucket, name),
+            &json!({
+                "version": version,
+                "description": format!("{} from {}", name, bucket),
+                "homepage": format!("https://example.com/{}", name),
+                "license": "MIT",
+                "bin": format!("{}.exe", name),
+            }),
+        );
+        self
+    }
+
+    fn install(&self, name: &str, version: &str, install: &Value, current: bool) {
+        let manifest = json!({
+            "version": version,
+            "description": format!("{} {}", name, version),
+            "bin": format!("{}.exe", name),
+        });
+        let mut dirs = vec![version];
+        if current {
+            dirs.push("current");
+        }
+        for dir in dirs {
+            let base = format!("apps/{}/{}", name, dir);
+            self.write_json(format!("{}/manifest.json", base), &manifest);
+            self.write_json(format!("{}/install.json", base), install);
+            self.write(format!("{}/{}.exe", base, name), "");
+        }
+    }
+
+    /// An app installed from `bucket`.
+    pub fn app(self, name: &str, version: &str, bucket: &str) -> Self {
+        self.install(
+            name,
+            version,
+            &json!({ "bucket": bucket, "architecture": "64bit" }),
+            true,
+        );
+        self
+    }
+
+    /// An app installed from `bucket` and on hold.
+    pub fn held_app(self, name: &str, version: &str, bucket: &str) -> Self {
+        self.install(
+            name,
+            version,
+            &json!({ "bucket": bucket, "architecture": "64bit", "hold": true }),
+            true,
+        );
+        self
+    }
+
+    /// An app installed from a manifest URL or file, which leaves no bucket in
+    /// `install.json`.
+    pub fn versioned_app(self, name: &str, version: &str) -> Self {
+        self.install(name, version, &json!({ "architecture": "64bit" }), true);
+        self
+    }
+
+    /// An app whose `current` link is missing, as after an interrupted update.
+    pub fn app_without_current(self, name: &str, version: &str, bucket: &str) -> Self {
+        self.install(
+            name,
+            version,
+            &json!({ "bucket": bucket, "architecture": "64bit" }),
+            false,
+        );
+        self
+    }
+
+    /// A download in `cache/`, named `<name>#<version>#<file>`.
+    pub fn cached_download(self, name: &str, version: &str, file: &str, size: usize) -> Self {
+        self.write(
+            format!("cache/{}#{}#{}", name, version, file),
+            &"x".repeat(size),
+        );
+        self
+    }
+
+    /// A file under `persist/<name>/`.
+    pub fn persisted(self, name: &str, relative: &str, contents: &str) -> Self {
+        self.write(format!("persist/{}/{}", name, relative), contents);
+        self
+    }
+
+    /// An executable shim pointing at `apps/<app>/current/<target>`.
+    pub fn shim(self, name: &str, app: &str, target: &str) -> Self {
+        let target = self
+            .root
+            .join("apps")
+            .join(app)
+            .join("current")
+            .join(target);
+        self.write(
+            format!("shims/{}.shim", name),
+            &format!("path = \"{}\"\n", target.display()),
+        );
+        self.write(format!("shims/{}.exe", name), "");
+        self
+    }
+}
+
+impl Drop for ScoopFixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
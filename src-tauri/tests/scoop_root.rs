@@ -0,0 +1,169 @@
+//! Drives `ScoopRoot` against fake Scoop roots, without Tauri or Windows.
+mod common;
+
+use common::ScoopFixture;
+use rscoop_lib::scoop_root::ScoopRoot;
+
+fn names<T>(items: &[T], name: impl Fn(&T) -> &str) -> Vec<String> {
+    let mut names: Vec<String> = items.iter().map(|item| name(item).to_string()).collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn lists_installed_packages() {
+    let fixture = ScoopFixture::new("installed")
+        .bucket_manifest("main", "git", "2.47.0")
+        .app("git", "2.47.0", "main")
+        .versioned_app("mytool", "1.0.0")
+        .app_without_current("7zip", "24.08", "main")
+        // Persisted data outlives an uninstall and must not count as an app.
+        .persisted("oldapp", "settings.ini", "[core]");
+    let root = ScoopRoot::new(fixture.path());
+
+    let packages = root.installed_packages().await.unwrap();
+    assert_eq!(names(&packages, |p| &p.name), vec!["7zip", "git", "mytool"]);
+
+    let git = packages.iter().find(|p| p.name == "git").unwrap();
+    assert_eq!(git.version, "2.47.0");
+    assert_eq!(git.source, "main");
+    assert!(git.is_installed);
+    assert!(!git.is_versioned_install);
+
+    let mytool = packages.iter().find(|p| p.name == "mytool").unwrap();
+    assert!(mytool.is_versioned_install);
+
+    let sevenzip = packages.iter().find(|p| p.name == "7zip").unwrap();
+    assert_eq!(sevenzip.version, "24.08");
+}
+
+#[tokio::test]
+async fn empty_root_has_nothing_installed() {
+    let fixture = ScoopFixture::new("empty");
+    let root = ScoopRoot::new(fixture.path().join("missing"));
+
+    assert!(root.installed_packages().await.unwrap().is_empty());
+    assert!(root.held_packages().unwrap().is_empty());
+    assert!(root.buckets().await.unwrap().is_empty());
+    assert!(root.cache_entries().await.unwrap().is_empty());
+    assert!(root.shims().unwrap().is_empty());
+}
+
+fn outdated_root() -> ScoopFixture {
+    ScoopFixture::new("outdated")
+        .bucket_manifest("main", "git", "2.47.0")
+        .bucket_manifest("main", "python", "3.13.0")
+        .bucket_manifest("main", "jq", "1.7.1")
+        .app("git", "2.46.0", "main")
+        .held_app("python", "3.12.0", "main")
+        .app("jq", "1.7.1", "main")
+}
+
+#[tokio::test]
+async fn reports_holds_and_updates() {
+    let fixture = outdated_root();
+    let root = ScoopRoot::new(fixture.path());
+
+    assert_eq!(root.held_packages().unwrap(), vec!["python"]);
+
+    // Held packages are still reported; the caller decides what to update.
+    let updates = root.updates().await.unwrap();
+    assert_eq!(names(&updates, |u| &u.name), vec!["git", "python"]);
+    let git = updates.iter().find(|u| u.name == "git").unwrap();
+    assert_eq!(
+        (git.current.as_str(), git.available.as_str()),
+        ("2.46.0", "2.47.0")
+    );
+}
+
+#[tokio::test]
+async fn status_flags_outdated_and_held_apps() {
+    let fixture = outdated_root();
+    let status = ScoopRoot::new(fixture.path()).status().await.unwrap();
+
+    assert!(!status.scoop_needs_update);
+    assert!(!status.bucket_needs_update);
+    assert_eq!(
+        names(&status.apps_with_issues, |a| &a.name),
+        vec!["git", "python"]
+    );
+
+    let python = status
+        .apps_with_issues
+        .iter()
+        .find(|a| a.name == "python")
+        .unwrap();
+    assert!(python.is_outdated && python.is_held);
+    assert!(python.info.iter().any(|i| i == "Held package"));
+
+    let git = status
+        .apps_with_issues
+        .iter()
+        .find(|a| a.name == "git")
+        .unwrap();
+    assert_eq!(git.latest_version.as_deref(), Some("2.47.0"));
+    assert!(git.is_outdated && !git.is_held);
+    assert!(!status.is_everything_ok);
+}
+
+#[tokio::test]
+async fn status_is_ok_when_everything_is_current() {
+    let fixture = ScoopFixture::new("current")
+        .bucket_manifest("main", "jq", "1.7.1")
+        .app("jq", "1.7.1", "main")
+        // A held package behind its manifest is not actionable.
+        .bucket_manifest("main", "python", "3.13.0")
+        .held_app("python", "3.12.0", "main");
+
+    let status = ScoopRoot::new(fixture.path()).status().await.unwrap();
+    assert!(status.is_everything_ok);
+}
+
+#[tokio::test]
+async fn lists_buckets() {
+    let fixture = ScoopFixture::new("buckets")
+        .bucket_manifest("main", "git", "2.47.0")
+        .bucket_manifest("main", "jq", "1.7.1")
+        .bucket_manifest("extras", "vscode", "1.95.0");
+
+    let buckets = ScoopRoot::new(fixture.path()).buckets().await.unwrap();
+    assert_eq!(names(&buckets, |b| &b.name), vec!["extras", "main"]);
+    let main = buckets.iter().find(|b| b.name == "main").unwrap();
+    assert_eq!(main.manifest_count, 2);
+    assert!(!main.is_git_repo);
+}
+
+#[tokio::test]
+async fn keeps_downloads_of_versioned_installs() {
+    let fixture = ScoopFixture::new("cache")
+        .app("git", "2.47.0", "main")
+        .versioned_app("mytool", "1.0.0")
+        .cached_download("git", "2.46.0", "PortableGit.7z.exe", 64)
+        .cached_download("mytool", "1.0.0", "mytool.zip", 16);
+
+    let entries = ScoopRoot::new(fixture.path())
+        .cache_entries()
+        .await
+        .unwrap();
+    assert_eq!(names(&entries, |e| &e.name), vec!["git", "mytool"]);
+
+    let git = entries.iter().find(|e| e.name == "git").unwrap();
+    assert_eq!((git.version.as_str(), git.length), ("2.46.0", 64));
+    assert!(git.is_safe_to_delete);
+    let mytool = entries.iter().find(|e| e.name == "mytool").unwrap();
+    assert!(!mytool.is_safe_to_delete);
+}
+
+#[tokio::test]
+async fn lists_shims_with_their_package() {
+    let fixture = ScoopFixture::new("shims")
+        .app("ripgrep", "14.1.1", "main")
+        .shim("rg", "ripgrep", "rg.exe");
+
+    let shims = ScoopRoot::new(fixture.path()).shims().unwrap();
+    assert_eq!(shims.len(), 1);
+    assert_eq!(shims[0].name, "rg");
+    assert_eq!(shims[0].source, "ripgrep");
+    assert!(!shims[0].is_global);
+    assert!(shims[0].path.ends_with("rg.exe"));
+}
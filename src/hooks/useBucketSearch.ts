@@ -12,6 +12,8 @@ export interface SearchableBucket {
   apps: number;
   last_updated: string;
   is_verified: boolean;
+  /** Id of the directory source that listed this bucket. */
+  source?: string;
 }
 
 export type BucketSortKey = "stars" | "relevance" | "apps" | "name";
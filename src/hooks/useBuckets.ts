@@ -10,6 +10,22 @@ export interface BucketInfo {
   git_url?: string;
   git_branch?: string;
   last_updated?: string;
+  pinned_ref?: string;
+  commits_behind?: number;
+  health?: BucketHealth;
+}
+
+export interface BucketHealth {
+  bucket_name: string;
+  score: number;
+  days_since_last_commit?: number;
+  is_stale: boolean;
+  manifest_count: number;
+  unusable_checkvers: number;
+  unusable_checkver_ratio: number;
+  malformed_manifests: string[];
+  duplicate_packages: { name: string; other_buckets: string[] }[];
+  dead_hosts?: string[];
 }
 
 export function useBuckets() {
@@ -57,7 +57,9 @@ export function useSearch() {
         debounceTimer = setTimeout(() => handleSearch(), 300);
     }));
 
-    const packageResults = () => results().filter((p) => p.match_source === "name");
+    // Everything but binary matches belongs on the packages tab (name,
+    // description, shortcut, homepage and license matches).
+    const packageResults = () => results().filter((p) => p.match_source !== "binary");
     const binaryResults = () => results().filter((p) => p.match_source === "binary");
     const resultsToShow = () => {
         return activeTab() === "packages" ? packageResults() : binaryResults();
@@ -1,7 +1,7 @@
 import { createRoot, createSignal } from "solid-js";
 import { listen, UnlistenFn } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/core";
-import { ScoopPackage } from "../types/scoop";
+import { RemotePackageMatch, ScoopPackage } from "../types/scoop";
 import installedPackagesStore from "./installedPackagesStore";
 import settingsStore from "./settings";
 import { getErrorMessage } from "../utils/errors";
@@ -274,6 +274,7 @@ function createOperationsStore() {
   type EnqueueAction =
     | { type: "scan-and-install"; package: string; bucket: string; version: string | null }
     | { type: "install"; package: string; bucket: string; version: string | null }
+    | { type: "add-bucket-and-install"; package: string; bucket: string; url: string; version: string | null }
     | { type: "update"; package: string }
     | { type: "update-all" }
     | { type: "uninstall"; package: string; bucket: string; auto_clear_cache: boolean }
@@ -327,6 +328,26 @@ function createOperationsStore() {
     }
   }
 
+  /** Adds the package's bucket, then installs the package, in one operation. */
+  async function queueRemoteInstall(
+    pkg: RemotePackageMatch,
+    onComplete?: (wasSuccess: boolean) => void,
+  ): Promise<string | null> {
+    if (onComplete) {
+      onNextFinish(
+        (op) => op.kind === "install" && op.packageName === pkg.name,
+        onComplete,
+      );
+    }
+    return await enqueue({
+      type: "add-bucket-and-install",
+      package: pkg.name,
+      bucket: pkg.bucket,
+      url: pkg.bucket_url,
+      version: null,
+    });
+  }
+
   async function handleInstallConfirm() {
     // Rust extracts the install params from the current scan op's chain
     // and enqueues a plain install, archiving the scan op.
@@ -436,6 +457,7 @@ function createOperationsStore() {
     completed,
     isMinimized,
     queueInstall,
+    queueRemoteInstall,
     queueUpdate,
     queueUpdateAll,
     queueUninstall,
@@ -5,9 +5,44 @@ export interface ScoopPackage {
   updated: string;
   is_installed: boolean;
   info: string;
-  match_source: "name" | "binary" | "none" | "path";
+  match_source: MatchSource;
   available_version?: string;
   is_versioned_install?: boolean;
+  /** Search ranking; higher is better. */
+  match_score?: number;
+  /** The manifest value the search matched. */
+  matched_text?: string;
+  /** Character ranges of `matched_text` to highlight. */
+  highlights?: MatchSpan[];
+}
+
+export type MatchSource =
+  | "name"
+  | "binary"
+  | "description"
+  | "homepage"
+  | "shortcut"
+  | "license"
+  | "none"
+  | "path";
+
+/** Half-open character range `[start, end)`. */
+export interface MatchSpan {
+  start: number;
+  end: number;
+}
+
+/** A package in a directory bucket that is not added locally. */
+export interface RemotePackageMatch {
+  name: string;
+  version: string;
+  description: string;
+  /** Local name to add the bucket under. */
+  bucket: string;
+  bucket_full_name: string;
+  bucket_url: string;
+  match_source: MatchSource;
+  match_score: number;
 }
 
 export interface ScoopInfo {